@@ -0,0 +1,48 @@
+use egui_timeline::{ruler, Bar, TimeDomain, TimeSig, TimelineApi};
+
+/// Zoom levels from a few beats to hundreds of bars across the timeline.
+pub const TICKS_PER_POINT: [f32; 4] = [1.0, 10.0, 100.0, 1_000.0];
+
+/// A project in 4/4 at 960 PPQN, viewed from `start`.
+pub struct Project {
+    pub start: f32,
+    pub ticks_per_point: f32,
+}
+
+impl Project {
+    /// A project viewed from its start at the given zoom level.
+    pub fn new(ticks_per_point: f32) -> Self {
+        Self {
+            start: 0.0,
+            ticks_per_point,
+        }
+    }
+}
+
+impl ruler::MusicalInfo for Project {
+    fn ticks_per_beat(&self) -> u32 {
+        960
+    }
+    fn bar_at_ticks(&self, tick: f32) -> Bar {
+        let bar_ticks = 960.0 * 4.0;
+        let tick = self.start + tick;
+        let start = (tick / bar_ticks).floor() * bar_ticks - self.start;
+        Bar {
+            tick_range: start..start + bar_ticks,
+            time_sig: TimeSig { top: 4, bottom: 4 },
+        }
+    }
+    fn ticks_per_point(&self) -> f32 {
+        self.ticks_per_point
+    }
+}
+
+impl TimelineApi for Project {
+    fn time_domain(&self) -> &dyn TimeDomain {
+        self
+    }
+    fn shift_timeline_start(&mut self, ticks: f32) {
+        self.start += ticks;
+    }
+    fn zoom(&mut self, _y_delta: f32) {}
+}
@@ -0,0 +1,34 @@
+use common::{Project, TICKS_PER_POINT};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use egui_timeline::{ruler, TimeDomain};
+
+mod common;
+
+/// The width of the timeline in points.
+const VISIBLE_LEN: f32 = 1920.0;
+
+fn steps(c: &mut Criterion) {
+    let mut group = c.benchmark_group("steps");
+    for &ticks_per_point in TICKS_PER_POINT.iter() {
+        let project = Project::new(ticks_per_point);
+        let id = BenchmarkId::new("musical", ticks_per_point);
+        group.bench_with_input(id, &project, |b, project| {
+            b.iter(|| {
+                project
+                    .steps(black_box(VISIBLE_LEN), egui_timeline::MIN_STEP_GAP)
+                    .count()
+            })
+        });
+        let id = BenchmarkId::new("musical_raw", ticks_per_point);
+        group.bench_with_input(id, &project, |b, project| {
+            b.iter(|| {
+                let info: &dyn ruler::MusicalInfo = project;
+                ruler::Steps::new(info, black_box(VISIBLE_LEN), egui_timeline::MIN_STEP_GAP).count()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, steps);
+criterion_main!(benches);
@@ -0,0 +1,115 @@
+use common::{Project, TICKS_PER_POINT};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use egui_timeline::{clip, waveform, Timeline};
+use std::ops::Range;
+
+mod common;
+
+struct BenchClip {
+    id: egui::Id,
+    tick_range: Range<f32>,
+}
+
+impl clip::Info for BenchClip {
+    fn id(&self) -> egui::Id {
+        self.id
+    }
+    fn tick_range(&self) -> Range<f32> {
+        self.tick_range.clone()
+    }
+}
+
+impl clip::Interaction for BenchClip {}
+
+/// The number of clips or peaks in small, medium and large projects.
+const PROJECT_SIZES: [usize; 3] = [100, 10_000, 1_000_000];
+
+/// Run a single frame over a 1920x1080 screen.
+fn frame(ctx: &egui::Context, mut f: impl FnMut(&mut egui::Ui)) {
+    let size = egui::vec2(1920.0, 1080.0);
+    let input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, size)),
+        ..Default::default()
+    };
+    let _ = ctx.run(input, |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| f(ui));
+    });
+}
+
+fn grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("grid");
+    let ctx = egui::Context::default();
+    for &ticks_per_point in TICKS_PER_POINT.iter() {
+        let mut project = Project::new(ticks_per_point);
+        let id = BenchmarkId::from_parameter(ticks_per_point);
+        group.bench_function(id, |b| {
+            b.iter(|| {
+                // Scroll each frame so that the cached grid is regenerated.
+                project.start += ticks_per_point;
+                frame(&ctx, |ui| {
+                    let show = Timeline::new().show(ui, &mut project);
+                    show.paint_grid(&project);
+                });
+            })
+        });
+    }
+    group.finish();
+}
+
+fn waveform(c: &mut Criterion) {
+    let mut group = c.benchmark_group("waveform");
+    let ctx = egui::Context::default();
+    for &len in PROJECT_SIZES.iter() {
+        let peaks: Vec<[f32; 2]> = (0..len)
+            .map(|i| {
+                let v = (i as f32 * 0.01).sin();
+                [-v.abs(), v.abs()]
+            })
+            .collect();
+        group.bench_with_input(BenchmarkId::from_parameter(len), &peaks, |b, peaks| {
+            b.iter(|| {
+                frame(&ctx, |ui| {
+                    let rect = ui.max_rect();
+                    let color = ui.visuals().text_color();
+                    waveform::paint(ui.painter(), rect, black_box(peaks), 1.0, color);
+                });
+            })
+        });
+    }
+    group.finish();
+}
+
+fn clips(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clips");
+    let ctx = egui::Context::default();
+    for &count in PROJECT_SIZES[..2].iter() {
+        let mut clips: Vec<BenchClip> = (0..count)
+            .map(|i| BenchClip {
+                id: egui::Id::new(i),
+                tick_range: i as f32 * 960.0..(i + 1) as f32 * 960.0,
+            })
+            .collect();
+        let mut project = Project::new(10.0);
+        group.bench_function(BenchmarkId::from_parameter(count), |b| {
+            b.iter(|| {
+                frame(&ctx, |ui| {
+                    Timeline::new()
+                        .show(ui, &mut project)
+                        .tracks(|tracks, _viewport, ui| {
+                            tracks.next(ui).show(|timeline, ui| {
+                                let size = egui::vec2(ui.available_width(), 40.0);
+                                let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                                for clip in clips.iter_mut() {
+                                    clip::set(ui, timeline, rect.y_range(), clip);
+                                }
+                            });
+                        });
+                });
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, grid, waveform, clips);
+criterion_main!(benches);
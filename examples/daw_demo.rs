@@ -0,0 +1,1376 @@
+//! A small DAW-style arrangement exercising most of the timeline's subsystems.
+//!
+//! Shows track headers, a pinned ruler with a loop brace and markers, clips with gradients and
+//! waveforms, an automation lane, a piano roll with a ghosted harmony part over a highlighted chord
+//! progression, a step-sequenced drum lane and a playhead driven by a fake transport. Press `Space`
+//! to play or pause, `Record` to preview capture on tracks armed with `R`, `Stop` to return to
+//! where playback started, drag the playhead to scrub and show the scrub rate, drag or double-click
+//! the tempo above the track headers to change it, right-click a clip to delete it, drag the
+//! grouped `Beat` and `Bassline` clips together, `Alt`-drag a clip's edge to stretch it and badge
+//! it with its new tempo, drag a vocal clip's warp markers or the fade handles in a hovered clip's
+//! top corners, `+` and `-` to zoom, or scroll with `Ctrl` + `Shift` held to scale the track
+//! heights or zoom the automation lane's values. Click the strip beneath each track to expand its
+//! pan automation, or the arrow in its header to show all of its automation lanes. Enable `Ripple`
+//! to shift the following clips when deleting a clip or stretching its end. Click `F` in a track's
+//! header to show the progress of a fake freeze over it. Click clips or track names to select them,
+//! holding `Shift` to add clips to the selection, or drag on the ruler to select a range of time.
+//! The `Select` menu selects the clips after the playhead, within a range or on a track.
+//!
+//! Run with `cargo run --example daw_demo`.
+
+use eframe::egui;
+use egui_timeline::{
+    automation,
+    clip::{self, WarpMarker},
+    cue,
+    curve::{self, CurvePoint},
+    detail::DetailLevel,
+    drum,
+    format::TimeFormatter,
+    goto,
+    intervals::IntervalIndex,
+    loop_region,
+    note::Note,
+    piano_roll::{self, Harmony, PitchClasses},
+    playhead, progress, readout, recording,
+    ripple::{self, Ripple},
+    ruler,
+    scrub::Scrub,
+    selection::{self, Candidate, Command, Item, SelectionState},
+    tempo,
+    view::ViewState,
+    zoom, Bar, Timeline, TrackZoom, ZoomAnchor,
+};
+use std::{
+    collections::BTreeMap,
+    ops::{Range, RangeInclusive},
+};
+
+/// The width of the track headers.
+const HEADER_WIDTH: f32 = 140.0;
+/// The height of each clip track.
+const TRACK_HEIGHT: f32 = 56.0;
+/// The height of the automation lane.
+const AUTOMATION_HEIGHT: f32 = 80.0;
+/// The height of the piano roll lane.
+const PIANO_ROLL_HEIGHT: f32 = 120.0;
+/// The height of the drum lane.
+const DRUM_HEIGHT: f32 = 72.0;
+/// The initial tempo of the fake transport and the tempo of the clips' source material in beats
+/// per minute.
+const TEMPO: f32 = 120.0;
+/// The number of snap divisions per beat.
+const SNAP_DIVISIONS: f32 = 4.0;
+/// While following, the fraction of the visible timeline the playhead may reach before the view
+/// jumps to catch up.
+const FOLLOW_EDGE: f32 = 0.9;
+/// The duration in seconds over which the beat indicator fades after each beat.
+const FLASH_SECS: f64 = 0.15;
+/// The duration in seconds of the fake freeze operation on a track.
+const FREEZE_SECS: f32 = 4.0;
+
+fn main() -> eframe::Result {
+    let options = eframe::NativeOptions::default();
+    eframe::run_native(
+        "egui_timeline - DAW demo",
+        options,
+        Box::new(|_cc| Ok(Box::new(Demo::default()))),
+    )
+}
+
+/// The state of the whole demo. All positions are in absolute ticks.
+struct Demo {
+    view: ViewState,
+    tracks: Vec<Track>,
+    automation: Vec<CurvePoint>,
+    /// The notes of the piano roll in absolute ticks.
+    keys: Vec<Note>,
+    /// A harmony part shown as ghost notes behind `keys`.
+    harmony: Vec<Note>,
+    /// The chord progression highlighted behind the piano roll in absolute ticks.
+    chords: Vec<Harmony>,
+    /// The name of each drum row and its velocity at each snap step from the start of the project.
+    drums: Vec<(&'static str, BTreeMap<i64, u8>)>,
+    markers: Vec<Marker>,
+    loop_range: Option<Range<f32>>,
+    /// The selected clips and tracks, along with the time selection in absolute ticks.
+    selection: SelectionState,
+    transport: Transport,
+    follow: bool,
+    /// Whether deleting or stretching a clip shifts the following clips on its track.
+    ripple_edit: bool,
+    snap: bool,
+    tooltip: bool,
+    /// A marker chosen from the jump-to menu, scrolled into view once the timeline is laid out.
+    jump_to: Option<f32>,
+    /// The number of ticks visible during the last frame, used to follow the playhead.
+    visible_ticks: f32,
+}
+
+/// A fake transport advanced by the frame time rather than an audio clock.
+struct Transport {
+    playhead: f32,
+    /// The position from which playback last started, returned to when stopped.
+    start: f32,
+    playing: bool,
+    recording: bool,
+    looping: bool,
+    /// The metric level of the last beat crossed and the time at which it was crossed.
+    flash: Option<(u8, f64)>,
+    /// The velocity in ticks per second while the playhead or ruler is dragged to scrub.
+    scrub: Option<f32>,
+    /// The tempo in beats per minute, changed via the readout in the ruler's header.
+    bpm: f32,
+}
+
+struct Track {
+    name: String,
+    color: egui::Color32,
+    muted: bool,
+    soloed: bool,
+    armed: bool,
+    bypassed: bool,
+    /// The progress of freezing the track, while in progress.
+    freezing: Option<f32>,
+    clips: Vec<DemoClip>,
+    /// The name and points of each automation sub-lane in absolute ticks. The first is
+    /// summarised beneath the track's clips.
+    automation: Vec<(&'static str, Vec<CurvePoint>)>,
+}
+
+struct DemoClip {
+    id: egui::Id,
+    name: String,
+    ticks: Range<f32>,
+    peaks: Vec<[f32; 2]>,
+    /// The tempo at which the clip plays back, changed by stretching it.
+    tempo: f32,
+    /// Warp markers in absolute ticks, with their source time in seconds from the clip's start.
+    warps: Vec<WarpMarker>,
+    /// The group of clips edited together with this one, if any.
+    group: Option<egui::Id>,
+    /// The lengths of the fades at the start and end of the clip in ticks.
+    fades: [f32; 2],
+}
+
+struct Marker {
+    text: String,
+    ticks: Range<f32>,
+}
+
+/// Snaps absolute ticks to the grid when enabled.
+#[derive(Copy, Clone)]
+struct Snap {
+    step: Option<f32>,
+}
+
+/// Adapts a clip's absolute ticks to the timeline.
+struct ClipApi<'a> {
+    clip: &'a mut DemoClip,
+    /// The clip's warp markers relative to the start of the timeline.
+    warps: Vec<WarpMarker>,
+    neighbours: &'a IntervalIndex<egui::Id>,
+    /// The track's color darkened, used for the bottom of the clip's gradient.
+    shadow: egui::Color32,
+    muted: bool,
+    start: f32,
+    snap: Snap,
+    edits: &'a mut Vec<ClipEdit>,
+}
+
+/// Edits affecting other clips, applied once all clips have been set.
+enum ClipEdit {
+    Delete(egui::Id),
+    Ripple(Ripple),
+    Group(egui::Id, clip::EditKind),
+}
+
+struct AutomationApi<'a> {
+    points: &'a mut [CurvePoint],
+    start: f32,
+}
+
+/// The automation sub-lanes of a track.
+struct SubLanesApi<'a> {
+    lanes: Vec<(&'static str, AutomationApi<'a>)>,
+}
+
+/// Adapts the piano roll's absolute ticks to the timeline.
+struct PianoApi<'a> {
+    notes: &'a mut [Note],
+    ghost: Vec<Note>,
+    chords: Vec<Harmony>,
+    start: f32,
+    snap: Snap,
+}
+
+/// Adapts the drum rows to the timeline, with one step per snap division.
+struct DrumsApi<'a> {
+    rows: &'a mut [(&'static str, BTreeMap<i64, u8>)],
+    step_ticks: f32,
+    start: f32,
+}
+
+struct MarkersApi<'a> {
+    markers: &'a mut [Marker],
+    start: f32,
+    snap: Snap,
+}
+
+struct LoopApi<'a> {
+    range: &'a mut Option<Range<f32>>,
+    start: f32,
+    snap: Snap,
+}
+
+struct RulerApi<'a> {
+    view: &'a ViewState,
+    transport: &'a mut Transport,
+    snap: Snap,
+    /// The tick double-clicked this frame, about which to zoom in.
+    zoom_at: &'a mut Option<f32>,
+    time_selection: TimeSelectionApi<'a>,
+}
+
+/// Adapts the time selection's absolute ticks to the timeline.
+struct TimeSelectionApi<'a> {
+    range: &'a mut Option<Range<f32>>,
+    start: f32,
+    snap: Snap,
+}
+
+/// Adapts the transport's absolute ticks to the readout.
+struct ReadoutApi<'a> {
+    view: &'a ViewState,
+    playhead: f32,
+    end: f32,
+    bpm: f32,
+}
+
+struct RecordingApi<'a> {
+    tracks: &'a [Track],
+    transport: &'a Transport,
+    start: f32,
+}
+
+struct FreezeApi<'a> {
+    tracks: &'a [Track],
+}
+
+struct PlayheadApi<'a> {
+    view: &'a ViewState,
+    transport: &'a mut Transport,
+    /// The current time in seconds, recorded alongside each beat crossed.
+    time: f64,
+}
+
+impl Default for Demo {
+    fn default() -> Self {
+        let view = ViewState::default();
+        let bar = view.bar_ticks();
+        let curve = |points: &[(f32, f32)]| {
+            points
+                .iter()
+                .map(|&(bars, value)| {
+                    let tangent = bar * 0.5;
+                    CurvePoint {
+                        tick: bars * bar,
+                        value,
+                        in_tangent: [-tangent, 0.0],
+                        out_tangent: [tangent, 0.0],
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let track = |name: &str, color, clips: &[(&str, Range<f32>)]| Track {
+            name: name.to_string(),
+            color,
+            muted: false,
+            soloed: false,
+            armed: false,
+            bypassed: false,
+            freezing: None,
+            clips: clips
+                .iter()
+                .map(|(clip, bars)| DemoClip {
+                    id: egui::Id::new((name, clip)),
+                    name: clip.to_string(),
+                    ticks: bars.start * bar..bars.end * bar,
+                    peaks: peaks((bars.end - bars.start) as usize * 64, bars.start),
+                    tempo: TEMPO,
+                    warps: vec![],
+                    group: None,
+                    fades: [0.0; 2],
+                })
+                .collect(),
+            automation: vec![
+                (
+                    "Pan",
+                    curve(&[(0.0, 0.5), (4.0, 0.3), (12.0, 0.7), (16.0, 0.5)]),
+                ),
+                ("Volume", curve(&[(0.0, 0.8), (16.0, 0.8)])),
+                ("Reverb send", curve(&[(0.0, 0.1), (8.0, 0.4), (16.0, 0.1)])),
+            ],
+        };
+        let mut tracks = vec![
+            track(
+                "Drums",
+                egui::Color32::from_rgb(220, 120, 80),
+                &[
+                    ("Intro", 0.0..4.0),
+                    ("Beat", 4.0..12.0),
+                    ("Fill", 12.0..13.0),
+                ],
+            ),
+            track(
+                "Bass",
+                egui::Color32::from_rgb(90, 160, 220),
+                &[("Bassline", 4.0..12.0), ("Outro", 13.0..16.0)],
+            ),
+            track(
+                "Keys",
+                egui::Color32::from_rgb(140, 200, 110),
+                &[("Pad", 0.0..8.0), ("Chords", 8.0..16.0)],
+            ),
+            track(
+                "Vocals",
+                egui::Color32::from_rgb(200, 120, 200),
+                &[("Verse", 4.0..8.0), ("Chorus", 8.0..12.0)],
+            ),
+        ];
+        // Group the drums and bass recorded in the same take so that they're edited together.
+        let take = egui::Id::new("take_1");
+        for clip in tracks.iter_mut().flat_map(|t| t.clips.iter_mut()) {
+            if clip.name == "Beat" || clip.name == "Bassline" {
+                clip.group = Some(take);
+            }
+        }
+        // Anchor the vocals at each bar, ready for warping.
+        let secs_per_tick = 60.0 / (TEMPO as f64 * view.ticks_per_beat as f64);
+        if let Some(vocals) = tracks.last_mut() {
+            for clip in &mut vocals.clips {
+                let bars = (clip.ticks.end - clip.ticks.start) / bar;
+                clip.warps = (1..bars.round() as usize)
+                    .map(|ix| WarpMarker {
+                        tick: clip.ticks.start + ix as f32 * bar,
+                        source: (ix as f32 * bar) as f64 * secs_per_tick,
+                    })
+                    .collect();
+            }
+        }
+        let automation = curve(&[(0.0, 0.2), (4.0, 0.8), (8.0, 0.5), (12.0, 1.0), (16.0, 0.0)]);
+        // A melody over each bar of the chorus, with a harmony a third below.
+        let beat = bar / 4.0;
+        let melody = |keys: &[u8]| {
+            keys.iter()
+                .enumerate()
+                .map(|(ix, &key)| {
+                    let start = 8.0 * bar + ix as f32 * beat;
+                    Note {
+                        ticks: start..start + beat * 0.9,
+                        key,
+                        velocity: 100,
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let keys = melody(&[
+            72, 74, 76, 77, 79, 77, 76, 74, 72, 71, 72, 74, 76, 74, 72, 72,
+        ]);
+        let harmony = melody(&[
+            69, 71, 72, 74, 76, 74, 72, 71, 69, 67, 69, 71, 72, 71, 69, 69,
+        ]);
+        // A I-V-vi-IV progression in C major through the chorus, one chord per bar.
+        let c_major = PitchClasses::new(&[0, 2, 4, 5, 7, 9, 11]);
+        let chords = [(0, 4, 7), (7, 11, 2), (9, 0, 4), (5, 9, 0)]
+            .iter()
+            .enumerate()
+            .map(|(ix, &(root, third, fifth))| {
+                let start = (8.0 + ix as f32) * bar;
+                Harmony {
+                    ticks: start..start + bar,
+                    scale: c_major,
+                    chord: PitchClasses::new(&[root, third, fifth]),
+                }
+            })
+            .collect();
+        // A four-on-the-floor pattern with snares on the backbeat and off-beat hats.
+        let steps = 16 * SNAP_DIVISIONS as i64;
+        let pattern = |every: i64, offset: i64, velocity: u8| {
+            (0..steps)
+                .filter(|step| step % every == offset)
+                .map(|step| (step, velocity))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let drums = vec![
+            ("Kick", pattern(4, 0, 120)),
+            ("Snare", pattern(8, 4, 110)),
+            ("Hat", pattern(2, 1, 70)),
+        ];
+        let markers = [
+            ("Intro", 0.0),
+            ("Verse", 4.0),
+            ("Chorus", 8.0),
+            ("Outro", 13.0),
+        ]
+        .iter()
+        .map(|&(text, bars)| Marker {
+            text: text.to_string(),
+            ticks: bars * bar..(bars + 1.0) * bar,
+        })
+        .collect();
+        Self {
+            view,
+            tracks,
+            automation,
+            keys,
+            harmony,
+            chords,
+            drums,
+            markers,
+            loop_range: Some(4.0 * bar..8.0 * bar),
+            selection: SelectionState::default(),
+            transport: Transport {
+                playhead: 0.0,
+                start: 0.0,
+                playing: false,
+                recording: false,
+                flash: None,
+                scrub: None,
+                bpm: TEMPO,
+                looping: true,
+            },
+            follow: true,
+            ripple_edit: false,
+            snap: true,
+            tooltip: true,
+            jump_to: None,
+            visible_ticks: 0.0,
+        }
+    }
+}
+
+impl Demo {
+    fn snap(&self) -> Snap {
+        let step = self.view.ticks_per_beat as f32 / SNAP_DIVISIONS;
+        Snap {
+            step: self.snap.then_some(step),
+        }
+    }
+
+    /// Advance the fake transport by the frame time, wrapping within the loop region.
+    fn advance(&mut self, dt: f32) {
+        let transport = &mut self.transport;
+        if !transport.playing {
+            return;
+        }
+        let ticks_per_sec = transport.bpm / 60.0 * self.view.ticks_per_beat as f32;
+        transport.playhead += dt * ticks_per_sec;
+        if let Some(range) = self.loop_range.as_ref().filter(|_| transport.looping) {
+            let len = range.end - range.start;
+            if transport.playhead >= range.end && len > 0.0 {
+                transport.playhead = range.start + (transport.playhead - range.end) % len;
+            }
+        }
+
+        // Page the view along with the playhead.
+        let visible = self.visible_ticks;
+        let ph = transport.playhead;
+        let start = self.view.start;
+        if self.follow && visible > 0.0 && (ph < start || ph > start + visible * FOLLOW_EDGE) {
+            let edge = visible * (1.0 - FOLLOW_EDGE);
+            self.view.start = (ph - edge).max(0.0);
+        }
+    }
+
+    /// Advance the fake freeze operation on each track, repainting until all have finished.
+    fn freeze(&mut self, ctx: &egui::Context, dt: f32) {
+        for track in &mut self.tracks {
+            if let Some(progress) = track.freezing.as_mut() {
+                *progress += dt / FREEZE_SECS;
+                ctx.request_repaint();
+            }
+            track.freezing = track.freezing.filter(|&p| p < 1.0);
+        }
+    }
+
+    fn transport_bar(&mut self, ui: &mut egui::Ui) {
+        let snap = self.snap();
+        ui.horizontal(|ui| {
+            let transport = &mut self.transport;
+            let label = if transport.playing { "Pause" } else { "Play" };
+            if ui.button(label).clicked() {
+                transport.toggle();
+            }
+            let record = ui.toggle_value(&mut transport.recording, "Record");
+            if record.clicked() && transport.recording && !transport.playing {
+                transport.toggle();
+            }
+            if ui.button("Stop").clicked() {
+                transport.playing = false;
+                transport.recording = false;
+                transport.playhead = transport.start;
+            }
+            ui.checkbox(&mut transport.looping, "Loop");
+            ui.checkbox(&mut self.follow, "Follow");
+            ui.checkbox(&mut self.snap, "Snap");
+            ui.checkbox(&mut self.ripple_edit, "Ripple");
+            ui.checkbox(&mut self.tooltip, "Tooltip");
+            ui.separator();
+            let end = self.tracks.iter().flat_map(|t| &t.clips);
+            let end = end.map(|c| c.ticks.end).fold(0.0, f32::max);
+            let view = &self.view;
+            let playhead = transport.playhead;
+            readout::show(
+                ui,
+                "readout",
+                &ReadoutApi {
+                    view,
+                    playhead,
+                    end,
+                    bpm: transport.bpm,
+                },
+            );
+            beat_indicator(ui, transport.flash);
+            if let Some(velocity) = transport.scrub {
+                let ticks_per_sec = transport.bpm / 60.0 * view.ticks_per_beat as f32;
+                ui.label(format!("Scrub {:+.2}x", velocity / ticks_per_sec));
+            }
+            ui.separator();
+
+            // Seek to the chosen marker, revealing it once the timeline is laid out.
+            let markers = &mut self.markers[..];
+            let start = view.start;
+            let entries = goto::Entry::cues(&MarkersApi {
+                markers,
+                start,
+                snap,
+            });
+            if let Some(tick) = goto::menu(ui, "Jump to", &entries, view) {
+                transport.playhead = start + tick;
+                self.jump_to = Some(start + tick);
+            }
+            ui.menu_button("Select", |ui| self.select_menu(ui));
+        });
+    }
+
+    /// Commands selecting clips by position or track, holding `Shift` to add to the selection.
+    fn select_menu(&mut self, ui: &mut egui::Ui) {
+        let mut commands = vec![(
+            "After playhead",
+            Command::Following(self.transport.playhead),
+        )];
+        if let Some(range) = self.loop_range.clone() {
+            commands.push(("Within loop", Command::Within(range)));
+        }
+        if let Some(range) = self.selection.time.clone() {
+            commands.push(("Within time selection", Command::Within(range)));
+        }
+        for track in &self.tracks {
+            let command = Command::Track(egui::Id::new(&track.name));
+            commands.push((&track.name, command));
+        }
+        let mut chosen = None;
+        for (label, command) in commands {
+            if ui.button(label).clicked() {
+                chosen = Some(command);
+            }
+        }
+        if ui.button("None").clicked() {
+            self.selection.clear();
+            ui.close_menu();
+        }
+        if let Some(command) = chosen {
+            let candidates = self.tracks.iter().flat_map(|track| {
+                let track_id = egui::Id::new(&track.name);
+                track.clips.iter().map(move |clip| Candidate {
+                    item: Item::Clip(clip.id),
+                    track: Some(track_id),
+                    range: clip.ticks.clone(),
+                })
+            });
+            let add = ui.input(|i| i.modifiers.shift);
+            self.selection.select(&command, candidates, add);
+            ui.close_menu();
+        }
+    }
+
+    fn timeline(&mut self, ui: &mut egui::Ui) {
+        let snap = self.snap();
+        let Self {
+            ref mut view,
+            ref mut tracks,
+            ref mut automation,
+            ref mut keys,
+            ref harmony,
+            ref chords,
+            ref mut drums,
+            ref mut markers,
+            ref mut loop_range,
+            ref mut selection,
+            ref mut transport,
+            tooltip,
+            ref mut jump_to,
+            ripple_edit,
+            ..
+        } = *self;
+
+        // Keep the playhead in place while zooming during playback.
+        let anchor = match transport.playing {
+            true => ZoomAnchor::Tick(transport.playhead - view.start),
+            false => ZoomAnchor::Pointer,
+        };
+        let ladder = zoom::Ladder::default();
+        let show = Timeline::new()
+            .header(HEADER_WIDTH)
+            .zoom_anchor(anchor)
+            .zoom_levels(ladder)
+            .track_zoom(TrackZoom::default())
+            .ripple(ripple_edit.then_some(ripple::Scope::Track))
+            .selection(selection.clone())
+            .show(ui, view);
+        let start = view.start;
+        let mut edits = vec![];
+        let step_ticks = view.ticks_per_beat as f32 / SNAP_DIVISIONS;
+        let mut zoom_at = None;
+        let layout = show
+            .paint_grid(&*view)
+            .pinned_tracks(|tracks, ui| {
+                tracks
+                    .next(ui)
+                    .header(|ui| {
+                        tempo::show(ui, "tempo", transport);
+                    })
+                    .show(|timeline, ui| {
+                        let view = &*view;
+                        let zoom_at = &mut zoom_at;
+                        let mut selected = timeline.selection(ui.ctx()).unwrap_or_default();
+                        let range = &mut selected.time;
+                        let mut api = RulerApi {
+                            view,
+                            transport,
+                            snap,
+                            zoom_at,
+                            time_selection: TimeSelectionApi { range, start, snap },
+                        };
+                        ruler::musical(ui, &mut api);
+                        timeline.set_selection(ui.ctx(), selected);
+                        let range = &mut *loop_range;
+                        let mut api = LoopApi { range, start, snap };
+                        loop_region::brace(ui, "loop", timeline, 12.0, &mut api);
+                    });
+                tracks
+                    .next(ui)
+                    .header(|ui| {
+                        ui.label("Markers");
+                    })
+                    .show(|timeline, ui| {
+                        let markers = &mut markers[..];
+                        let mut api = MarkersApi {
+                            markers,
+                            start,
+                            snap,
+                        };
+                        cue::lane(ui, "markers", timeline, 18.0, &mut api);
+                    });
+            })
+            .tracks(|ctx, _viewport, ui| {
+                for track in tracks.iter_mut() {
+                    let Track {
+                        ref name,
+                        color,
+                        ref mut muted,
+                        ref mut soloed,
+                        ref mut armed,
+                        ref mut bypassed,
+                        ref mut freezing,
+                        ref mut clips,
+                        automation: ref mut lanes,
+                    } = *track;
+                    ctx.next(ui)
+                        .id(name)
+                        .color(color)
+                        .muted(*muted)
+                        .soloed(*soloed)
+                        .header(|ui| {
+                            ui.horizontal(|ui| {
+                                automation::toggle(ui, (name, "automation"));
+                                let label = egui::Label::new(name).sense(egui::Sense::click());
+                                if ui.add(label).on_hover_text("Select").clicked() {
+                                    let timeline = &ctx.timeline;
+                                    let mut state =
+                                        timeline.selection(ui.ctx()).unwrap_or_default();
+                                    let id = egui::Id::new(name);
+                                    if !state.tracks.remove(&id) {
+                                        state.tracks.insert(id);
+                                    }
+                                    timeline.set_selection(ui.ctx(), state);
+                                }
+                                ui.toggle_value(muted, "M");
+                                ui.toggle_value(soloed, "S");
+                                ui.toggle_value(armed, "R");
+                                ui.toggle_value(bypassed, "B")
+                                    .on_hover_text("Bypass, or double-click the track");
+                                let freeze =
+                                    ui.add_enabled(freezing.is_none(), egui::Button::new("F"));
+                                if freeze.on_hover_text("Freeze").clicked() {
+                                    *freezing = Some(0.0);
+                                }
+                            });
+                        })
+                        .bypass(bypassed)
+                        .show(|timeline, ui| {
+                            let w = ui.available_width();
+                            let size = egui::vec2(w, TRACK_HEIGHT * timeline.track_scale);
+                            let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                            let [r, g, b, _] = color.to_array();
+                            let shadow = egui::Color32::from_rgb(r / 3, g / 3, b / 3);
+                            let neighbours: IntervalIndex<egui::Id> = clips
+                                .iter()
+                                .map(|c| (c.ticks.start - start..c.ticks.end - start, c.id))
+                                .collect();
+                            for clip in clips.iter_mut() {
+                                let warps = clip
+                                    .warps
+                                    .iter()
+                                    .map(|w| WarpMarker {
+                                        tick: w.tick - start,
+                                        ..*w
+                                    })
+                                    .collect();
+                                let mut api = ClipApi {
+                                    clip,
+                                    warps,
+                                    neighbours: &neighbours,
+                                    shadow,
+                                    muted: *muted || timeline.track_bypassed,
+                                    start,
+                                    snap,
+                                    edits: &mut edits,
+                                };
+                                clip::set(ui, timeline, rect.y_range(), &mut api);
+                            }
+
+                            // Summarise the first automation lane, expanding to edit it.
+                            let mut api = AutomationApi {
+                                points: &mut lanes[0].1[..],
+                                start,
+                            };
+                            let h = AUTOMATION_HEIGHT * timeline.track_scale;
+                            curve::footer(ui, (name, "footer"), timeline, h, &mut api);
+                        });
+                    let mut api = SubLanesApi {
+                        lanes: lanes
+                            .iter_mut()
+                            .map(|(name, points)| {
+                                let points = &mut points[..];
+                                (*name, AutomationApi { points, start })
+                            })
+                            .collect(),
+                    };
+                    let h = AUTOMATION_HEIGHT * 0.75;
+                    automation::sub_lanes(ctx, ui, (name, "automation"), h, &mut api);
+                }
+                ctx.next(ui)
+                    .header(|ui| {
+                        ui.label("Volume");
+                    })
+                    .show(|timeline, ui| {
+                        let points = &mut automation[..];
+                        let mut api = AutomationApi { points, start };
+                        let h = AUTOMATION_HEIGHT * timeline.track_scale;
+                        curve::lane(ui, "volume", timeline, h, &mut api);
+                    });
+                ctx.next(ui)
+                    .header(|ui| {
+                        ui.label("Keys");
+                    })
+                    .show(|timeline, ui| {
+                        let notes = &mut keys[..];
+                        let ghost = harmony
+                            .iter()
+                            .map(|note| Note {
+                                ticks: note.ticks.start - start..note.ticks.end - start,
+                                ..note.clone()
+                            })
+                            .collect();
+                        let chords = chords
+                            .iter()
+                            .map(|chord| Harmony {
+                                ticks: chord.ticks.start - start..chord.ticks.end - start,
+                                ..chord.clone()
+                            })
+                            .collect();
+                        let mut api = PianoApi {
+                            notes,
+                            ghost,
+                            chords,
+                            start,
+                            snap,
+                        };
+                        let h = PIANO_ROLL_HEIGHT * timeline.track_scale;
+                        piano_roll::lane(ui, "keys", timeline, h, &mut api);
+                    });
+                ctx.next(ui)
+                    .header(|ui| {
+                        ui.label("Drums");
+                    })
+                    .show(|timeline, ui| {
+                        let mut api = DrumsApi {
+                            rows: &mut drums[..],
+                            step_ticks,
+                            start,
+                        };
+                        let h = DRUM_HEIGHT * timeline.track_scale;
+                        drum::lane(ui, "drums", timeline, h, &mut api);
+                    });
+            });
+
+        // Apply deletions, ripples and group edits now that no clips are borrowed.
+        for edit in edits {
+            match edit {
+                ClipEdit::Delete(id) => {
+                    for track in tracks.iter_mut() {
+                        track.clips.retain(|clip| clip.id != id);
+                    }
+                }
+                ClipEdit::Ripple(ripple) => {
+                    for track in tracks.iter_mut() {
+                        let track_id = Some(egui::Id::new(&track.name));
+                        for clip in &mut track.clips {
+                            let range = clip.ticks.start - start..clip.ticks.end - start;
+                            if !ripple.applies_to(clip.id, track_id, range.clone()) {
+                                continue;
+                            }
+                            let delta = ripple.shift(range.clone()).start - range.start;
+                            clip.ticks = clip.ticks.start + delta..clip.ticks.end + delta;
+                            for warp in &mut clip.warps {
+                                warp.tick += delta;
+                            }
+                        }
+                    }
+                }
+                ClipEdit::Group(group, edit) => {
+                    let min_len = clip::EDGE_WIDTH * 2.0 * view.ticks_per_point;
+                    let members = tracks
+                        .iter_mut()
+                        .flat_map(|track| track.clips.iter_mut())
+                        .filter(|clip| clip.group == Some(group));
+                    for clip in members {
+                        let range = clip.ticks.start - start..clip.ticks.end - start;
+                        let range = edit.apply(range, min_len);
+                        let ticks = start + range.start..start + range.end;
+                        match edit {
+                            clip::EditKind::Move(_) => clip.move_to(ticks, snap),
+                            _ => clip.stretch_to(ticks, snap),
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(state) = layout.selection_changed.clone() {
+            *selection = state;
+        }
+        let range = &mut selection.time;
+        layout.time_selection(ui, &TimeSelectionApi { range, start, snap });
+
+        let modifiers = ui.input(|i| i.modifiers);
+        let range = &mut *loop_range;
+        layout.loop_column(
+            ui,
+            "loop_column",
+            modifiers,
+            &mut LoopApi { range, start, snap },
+        );
+        let config = playhead::Config {
+            playing_repaint_rate: Some(60.0),
+            ..Default::default()
+        };
+        let tracks = &tracks[..];
+        layout.recording(
+            ui,
+            &RecordingApi {
+                tracks,
+                transport,
+                start,
+            },
+        );
+        layout.progress(ui, &FreezeApi { tracks });
+        let time = ui.input(|i| i.time);
+        let mut api = PlayheadApi {
+            view,
+            transport,
+            time,
+        };
+        layout.playhead(ui, &config, &mut api);
+        if tooltip {
+            layout.hover_tooltip(ui, &*view);
+        }
+        self.visible_ticks = layout.visible_ticks.end;
+
+        if let Some(tick) = jump_to.take() {
+            goto::reveal(ui.ctx(), &layout, tick - view.start, view);
+        }
+
+        // Zoom in to the next level about a double-clicked ruler position.
+        if let Some(tick) = zoom_at {
+            let anchor_x = tick / view.ticks_per_point;
+            let tpp = ladder.step(view.ticks_per_point, -1);
+            zoom::set(view, tpp, Some(anchor_x));
+        }
+    }
+}
+
+impl Transport {
+    /// Display the scrub rate while dragging. A real host would drive varispeed audio here.
+    fn scrub(&mut self, scrub: Scrub) {
+        self.scrub = match scrub {
+            Scrub::Moved { velocity, .. } => Some(velocity),
+            Scrub::Stopped { .. } => None,
+        };
+    }
+
+    /// Play or pause, remembering where playback started.
+    fn toggle(&mut self) {
+        self.playing = !self.playing;
+        if self.playing {
+            self.start = self.playhead;
+        }
+    }
+}
+
+impl eframe::App for Demo {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if ctx.input(|i| i.key_pressed(egui::Key::Space)) {
+            self.transport.toggle();
+        }
+        let dt = ctx.input(|i| i.stable_dt);
+        self.advance(dt);
+        self.freeze(ctx, dt);
+        egui::TopBottomPanel::top("transport").show(ctx, |ui| self.transport_bar(ui));
+        egui::CentralPanel::default().show(ctx, |ui| self.timeline(ui));
+    }
+}
+
+impl DemoClip {
+    /// Move the clip and its warp markers to the given absolute ticks, snapping its start.
+    fn move_to(&mut self, ticks: Range<f32>, snap: Snap) {
+        let len = ticks.end - ticks.start;
+        let start = snap.ticks(ticks.start).max(0.0);
+        let delta = start - self.ticks.start;
+        self.ticks = start..start + len;
+        for warp in &mut self.warps {
+            warp.tick += delta;
+        }
+    }
+
+    /// Stretch the clip to the given absolute ticks, snapping both edges and scaling its tempo and
+    /// warp markers to match.
+    fn stretch_to(&mut self, ticks: Range<f32>, snap: Snap) {
+        let ticks = snap.ticks(ticks.start).max(0.0)..snap.ticks(ticks.end);
+        if ticks.end > ticks.start {
+            let initial = self.ticks.clone();
+            let ratio = (ticks.end - ticks.start) / (initial.end - initial.start);
+            self.tempo /= ratio;
+            for warp in &mut self.warps {
+                warp.tick = ticks.start + (warp.tick - initial.start) * ratio;
+            }
+            self.ticks = ticks;
+        }
+    }
+}
+
+impl Snap {
+    fn ticks(&self, ticks: f32) -> f32 {
+        match self.step {
+            Some(step) => (ticks / step).round() * step,
+            None => ticks,
+        }
+    }
+}
+
+impl<'a> clip::Info for ClipApi<'a> {
+    fn id(&self) -> egui::Id {
+        self.clip.id
+    }
+    fn tick_range(&self) -> Range<f32> {
+        self.clip.ticks.start - self.start..self.clip.ticks.end - self.start
+    }
+    fn waveform(&self) -> Option<&[[f32; 2]]> {
+        Some(&self.clip.peaks)
+    }
+    fn name(&self) -> Option<&str> {
+        Some(&self.clip.name)
+    }
+    fn is_muted(&self) -> bool {
+        self.muted
+    }
+    fn tempo(&self) -> Option<f32> {
+        Some(self.clip.tempo)
+    }
+    fn gradient_color(&self) -> Option<egui::Color32> {
+        Some(self.shadow)
+    }
+    fn is_deletable(&self) -> bool {
+        true
+    }
+    fn paint_overlay(&self, painter: &egui::Painter, rect: egui::Rect, state: clip::PaintState) {
+        // Badge stretched clips with their new tempo.
+        if self.clip.tempo == TEMPO || state.detail == DetailLevel::Block {
+            return;
+        }
+        let text = format!("{:.1} BPM", self.clip.tempo);
+        let font_id = egui::TextStyle::Small.resolve(&painter.ctx().style());
+        let color = egui::Color32::WHITE;
+        let galley = painter.layout_no_wrap(text, font_id, color);
+        let pad = egui::vec2(3.0, 1.0);
+        let size = galley.size() + pad * 2.0;
+        let badge =
+            egui::Rect::from_min_size(rect.right_top() + egui::vec2(-size.x - 2.0, 2.0), size);
+        painter.rect_filled(badge, 3.0, egui::Color32::from_black_alpha(160));
+        painter.galley(badge.min + pad, galley, color);
+    }
+    fn warp_markers(&self) -> &[WarpMarker] {
+        &self.warps
+    }
+    fn fade_in(&self) -> Option<f32> {
+        Some(self.clip.fades[0])
+    }
+    fn fade_out(&self) -> Option<f32> {
+        Some(self.clip.fades[1])
+    }
+    fn group(&self) -> Option<egui::Id> {
+        self.clip.group
+    }
+    fn neighbours(&self) -> Option<&IntervalIndex<egui::Id>> {
+        Some(self.neighbours)
+    }
+}
+
+impl<'a> clip::Interaction for ClipApi<'a> {
+    fn set_tick_range(&mut self, range: Range<f32>) {
+        let ticks = self.start + range.start..self.start + range.end;
+        self.clip.move_to(ticks, self.snap);
+    }
+    fn group_edit(&mut self, group: egui::Id, edit: clip::EditKind) {
+        self.edits.push(ClipEdit::Group(group, edit));
+    }
+    fn delete(&mut self) {
+        self.edits.push(ClipEdit::Delete(self.clip.id));
+    }
+    fn set_fade_in(&mut self, ticks: f32) {
+        self.clip.fades[0] = self.snap.ticks(ticks);
+    }
+    fn set_fade_out(&mut self, ticks: f32) {
+        self.clip.fades[1] = self.snap.ticks(ticks);
+    }
+    fn ripple(&mut self, mut ripple: Ripple) {
+        // Ripple by the snapped change in length, matching `stretch`.
+        let from = self.start + ripple.from;
+        ripple.delta = self.snap.ticks(from + ripple.delta) - from;
+        self.edits.push(ClipEdit::Ripple(ripple));
+    }
+    fn set_warp_marker(&mut self, index: usize, tick: f32) {
+        self.warps[index].tick = tick;
+        self.clip.warps[index].tick = self.start + tick;
+    }
+    fn stretch(&mut self, stretch: clip::Stretch) {
+        let range = stretch.range;
+        let ticks = self.start + range.start..self.start + range.end;
+        self.clip.stretch_to(ticks, self.snap);
+    }
+}
+
+impl<'a> curve::Curve for AutomationApi<'a> {
+    fn value_range(&self) -> RangeInclusive<f32> {
+        0.0..=1.0
+    }
+    fn point_count(&self) -> usize {
+        self.points.len()
+    }
+    fn point(&self, index: usize) -> CurvePoint {
+        let mut point = self.points[index];
+        point.tick -= self.start;
+        point
+    }
+    fn set_point(&mut self, index: usize, mut point: CurvePoint) {
+        // Keep the points ordered by clamping between their neighbours.
+        let min = index.checked_sub(1).map_or(0.0, |ix| self.points[ix].tick);
+        let max = self.points.get(index + 1).map_or(f32::MAX, |p| p.tick);
+        point.tick = (self.start + point.tick).clamp(min, max);
+        point.value = point.value.clamp(0.0, 1.0);
+        self.points[index] = point;
+    }
+}
+
+impl<'a> automation::Automation for SubLanesApi<'a> {
+    fn lane_count(&self) -> usize {
+        self.lanes.len()
+    }
+    fn lane_name(&self, lane: usize) -> &str {
+        self.lanes[lane].0
+    }
+    fn lane_curve(&mut self, lane: usize) -> &mut dyn curve::Curve {
+        &mut self.lanes[lane].1
+    }
+}
+
+impl<'a> piano_roll::PianoRoll for PianoApi<'a> {
+    fn key_range(&self) -> RangeInclusive<u8> {
+        60..=84
+    }
+    fn note_count(&self) -> usize {
+        self.notes.len()
+    }
+    fn note(&self, index: usize) -> Note {
+        let note = &self.notes[index];
+        Note {
+            ticks: note.ticks.start - self.start..note.ticks.end - self.start,
+            ..note.clone()
+        }
+    }
+    fn set_note(&mut self, index: usize, note: Note) {
+        // Moved notes keep their length, while resized notes snap their end to the grid.
+        let prev = &self.notes[index].ticks;
+        let len = note.ticks.end - note.ticks.start;
+        let moved = (len - (prev.end - prev.start)).abs() < 1e-3;
+        let start = self.snap.ticks(self.start + note.ticks.start).max(0.0);
+        let end = self.snap.ticks(self.start + note.ticks.end);
+        let end = match moved || end <= start {
+            true => start + len,
+            false => end,
+        };
+        self.notes[index] = Note {
+            ticks: start..end,
+            ..note
+        };
+    }
+    fn ghost_notes(&self) -> &[Note] {
+        &self.ghost
+    }
+    fn harmony(&self) -> &[Harmony] {
+        &self.chords
+    }
+}
+
+impl<'a> drum::Drums for DrumsApi<'a> {
+    fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+    fn row_name(&self, row: usize) -> &str {
+        self.rows[row].0
+    }
+    fn step_ticks(&self) -> Option<f32> {
+        Some(self.step_ticks)
+    }
+    fn step_origin(&self) -> f32 {
+        -self.start
+    }
+    fn cell(&self, row: usize, step: i64) -> Option<u8> {
+        self.rows[row].1.get(&step).copied()
+    }
+    fn set_cell(&mut self, row: usize, step: i64, velocity: Option<u8>) {
+        let hits = &mut self.rows[row].1;
+        match velocity {
+            Some(velocity) if step >= 0 => {
+                hits.insert(step, velocity);
+            }
+            _ => {
+                hits.remove(&step);
+            }
+        }
+    }
+}
+
+impl<'a> cue::Cues for MarkersApi<'a> {
+    fn cue_count(&self) -> usize {
+        self.markers.len()
+    }
+    fn cue_range(&self, index: usize) -> Range<f32> {
+        let ticks = &self.markers[index].ticks;
+        ticks.start - self.start..ticks.end - self.start
+    }
+    fn cue_text(&self, index: usize) -> &str {
+        &self.markers[index].text
+    }
+    fn set_cue_range(&mut self, index: usize, range: Range<f32>) {
+        let start = self.snap.ticks(self.start + range.start);
+        let end = self.snap.ticks(self.start + range.end);
+        if end > start {
+            self.markers[index].ticks = start..end;
+        }
+    }
+}
+
+impl<'a> loop_region::LoopRegion for LoopApi<'a> {
+    fn loop_range(&self) -> Option<Range<f32>> {
+        let range = self.range.as_ref()?;
+        Some(range.start - self.start..range.end - self.start)
+    }
+    fn set_loop_range(&mut self, range: Range<f32>) {
+        let start = self.snap.ticks(self.start + range.start).max(0.0);
+        let end = self.snap.ticks(self.start + range.end);
+        if end > start {
+            *self.range = Some(start..end);
+        }
+    }
+}
+
+impl<'a> selection::TimeSelectionApi for TimeSelectionApi<'a> {
+    fn time_selection(&self) -> Option<Range<f32>> {
+        let range = self.range.as_ref()?;
+        Some(range.start - self.start..range.end - self.start)
+    }
+    fn set_time_selection(&mut self, selection: Option<Range<f32>>) {
+        *self.range = selection
+            .map(|r| self.snap.ticks(self.start + r.start)..self.snap.ticks(self.start + r.end))
+            .filter(|r| r.end > r.start);
+    }
+}
+
+impl tempo::Tempo for Transport {
+    fn bpm(&self) -> f32 {
+        self.bpm
+    }
+    fn set_bpm(&mut self, bpm: f32) {
+        self.bpm = bpm;
+    }
+}
+
+impl<'a> ruler::MusicalRuler for RulerApi<'a> {
+    fn info(&self) -> &dyn ruler::MusicalInfo {
+        self.view
+    }
+    fn interact(&mut self) -> &mut dyn ruler::MusicalInteract {
+        self
+    }
+    fn time_selection(&mut self) -> Option<&mut dyn selection::TimeSelectionApi> {
+        Some(&mut self.time_selection)
+    }
+}
+
+impl<'a> ruler::MusicalInteract for RulerApi<'a> {
+    fn click_at_tick(&mut self, tick: f32) {
+        self.transport.playhead = self.snap.ticks(self.view.start + tick).max(0.0);
+    }
+    fn double_click_at_tick(&mut self, tick: f32) {
+        *self.zoom_at = Some(tick);
+    }
+    fn scrub(&mut self, scrub: Scrub) {
+        self.transport.scrub(scrub);
+    }
+}
+
+impl<'a> readout::Readout for ReadoutApi<'a> {
+    fn position(&self) -> f32 {
+        self.playhead - self.view.start
+    }
+    fn range(&self) -> Range<f32> {
+        -self.view.start..self.end - self.view.start
+    }
+    fn formatter(&self) -> &dyn TimeFormatter {
+        self.view
+    }
+    fn seconds(&self, tick: f32) -> f64 {
+        let beats = (self.view.start + tick) / self.view.ticks_per_beat as f32;
+        (beats * 60.0 / self.bpm) as f64
+    }
+}
+
+impl<'a> recording::Recording for RecordingApi<'a> {
+    fn recording_range(&self) -> Option<Range<f32>> {
+        let transport = self.transport;
+        let range = transport.start - self.start..transport.playhead - self.start;
+        transport.recording.then_some(range)
+    }
+    fn is_armed(&self, track: egui::Id) -> bool {
+        self.tracks
+            .iter()
+            .any(|t| t.armed && egui::Id::new(&t.name) == track)
+    }
+}
+
+impl<'a> progress::Progress for FreezeApi<'a> {
+    fn track_progress(&self, track: egui::Id) -> Option<f32> {
+        self.tracks
+            .iter()
+            .find(|t| egui::Id::new(&t.name) == track)
+            .and_then(|t| t.freezing)
+    }
+}
+
+impl<'a> ruler::MusicalInfo for PlayheadApi<'a> {
+    fn ticks_per_beat(&self) -> u32 {
+        self.view.ticks_per_beat
+    }
+    fn bar_at_ticks(&self, tick: f32) -> Bar {
+        self.view.bar_at_ticks(tick)
+    }
+    fn ticks_per_point(&self) -> f32 {
+        self.view.ticks_per_point
+    }
+    fn first_bar_number(&self) -> i64 {
+        self.view.first_bar_number()
+    }
+}
+
+impl<'a> playhead::Info for PlayheadApi<'a> {
+    fn playhead_ticks(&self) -> f32 {
+        self.transport.playhead - self.view.start
+    }
+    fn is_playing(&self) -> bool {
+        self.transport.playing
+    }
+    fn return_ticks(&self) -> Option<f32> {
+        let transport = &self.transport;
+        transport
+            .playing
+            .then_some(transport.start - self.view.start)
+    }
+}
+
+impl<'a> playhead::Interaction for PlayheadApi<'a> {
+    fn set_playhead_ticks(&mut self, ticks: f32) {
+        self.transport.playhead = (self.view.start + ticks).max(0.0);
+    }
+    fn beat_crossed(&mut self, beat: playhead::Beat) {
+        self.transport.flash = Some((beat.level, self.time));
+    }
+    fn scrub(&mut self, scrub: Scrub) {
+        self.transport.scrub(scrub);
+    }
+}
+
+/// A dot that flashes on each beat, brighter on the downbeat.
+fn beat_indicator(ui: &mut egui::Ui, flash: Option<(u8, f64)>) {
+    let size = egui::Vec2::splat(ui.spacing().interact_size.y * 0.6);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let now = ui.input(|i| i.time);
+    let (level, t) = flash.map_or((2, 0.0), |(level, time)| {
+        (level, 1.0 - ((now - time) / FLASH_SECS).clamp(0.0, 1.0))
+    });
+    let color = match level {
+        0 => ui.visuals().selection.bg_fill,
+        _ => ui.visuals().strong_text_color(),
+    };
+    let fill = ui.visuals().faint_bg_color.lerp_to_gamma(color, t as f32);
+    ui.painter()
+        .circle_filled(rect.center(), rect.width() * 0.5, fill);
+    if t > 0.0 {
+        ui.ctx().request_repaint();
+    }
+}
+
+/// Some deterministic min/max peaks resembling a decaying signal.
+fn peaks(len: usize, seed: f32) -> Vec<[f32; 2]> {
+    (0..len)
+        .map(|i| {
+            let t = i as f32 / 64.0;
+            let envelope = 1.0 - t.fract() * 0.7;
+            let wobble = ((t + seed) * 13.7).sin().abs() * 0.4 + 0.4;
+            let peak = envelope * wobble;
+            [-peak, peak]
+        })
+        .collect()
+}
@@ -0,0 +1,109 @@
+//! A CLAP plugin whose editor shows a timeline following the host's transport.
+//!
+//! Build with `cargo build --example plugin --release` from the `nih_plug` directory, then bundle
+//! the resulting library as a `.clap` plugin.
+
+use egui_timeline::{
+    playhead,
+    view::{self, ViewState},
+};
+use egui_timeline_nih_plug::SharedTransport;
+use nih_plug::prelude::*;
+use nih_plug_egui::{create_egui_editor, egui, EguiState};
+use std::{num::NonZeroU32, sync::Arc};
+
+struct TimelinePlugin {
+    params: Arc<TimelineParams>,
+    /// Written by the audio thread and read by the editor.
+    transport: Arc<SharedTransport>,
+}
+
+#[derive(Params)]
+struct TimelineParams {
+    #[persist = "editor-state"]
+    editor_state: Arc<EguiState>,
+}
+
+impl Default for TimelinePlugin {
+    fn default() -> Self {
+        Self {
+            params: Arc::new(TimelineParams {
+                editor_state: EguiState::from_size(800, 300),
+            }),
+            transport: Default::default(),
+        }
+    }
+}
+
+impl Plugin for TimelinePlugin {
+    const NAME: &'static str = "Timeline Example";
+    const VENDOR: &'static str = "egui_timeline";
+    const URL: &'static str = "https://github.com/mitchmindtree/egui_timeline";
+    const EMAIL: &'static str = "mail@mitchellnordine.com";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {
+        main_input_channels: NonZeroU32::new(2),
+        main_output_channels: NonZeroU32::new(2),
+        ..AudioIOLayout::const_default()
+    }];
+
+    type SysExMessage = ();
+    type BackgroundTask = ();
+
+    fn params(&self) -> Arc<dyn Params> {
+        self.params.clone()
+    }
+
+    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
+        let transport = self.transport.clone();
+        create_egui_editor(
+            self.params.editor_state.clone(),
+            ViewState::default(),
+            |_, _| {},
+            move |ctx, _setter, view| {
+                // Load the latest transport written by the audio thread.
+                let host = transport.load();
+                host.sync_view(view);
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.label(format!("{:.1} BPM", host.tempo));
+                    let response = ui.add(view::widget(view, |tracks, _viewport, ui| {
+                        tracks.next(ui).show(|_timeline, ui| {
+                            ui.add_space(ui.spacing().interact_size.y * 4.0);
+                        });
+                    }));
+                    // The playhead is owned by the host, so it isn't interactive.
+                    let config = playhead::Config {
+                        interactive: false,
+                        playing_repaint_rate: Some(30.0),
+                        ..Default::default()
+                    };
+                    let start = Some(view.start);
+                    let mut playhead = host.playhead(view);
+                    let rect = response.rect;
+                    playhead::set(ui, rect, rect.bottom(), start, &config, &mut playhead);
+                });
+            },
+        )
+    }
+
+    fn process(
+        &mut self,
+        _buffer: &mut Buffer,
+        _aux: &mut AuxiliaryBuffers,
+        context: &mut impl ProcessContext<Self>,
+    ) -> ProcessStatus {
+        // Lock-free, so safe to call from the audio thread.
+        self.transport.store(context.transport());
+        ProcessStatus::Normal
+    }
+}
+
+impl ClapPlugin for TimelinePlugin {
+    const CLAP_ID: &'static str = "com.mitchmindtree.egui-timeline-example";
+    const CLAP_DESCRIPTION: Option<&'static str> = Some("A timeline following the transport");
+    const CLAP_MANUAL_URL: Option<&'static str> = Some(Self::URL);
+    const CLAP_SUPPORT_URL: Option<&'static str> = None;
+    const CLAP_FEATURES: &'static [ClapFeature] = &[ClapFeature::AudioEffect, ClapFeature::Utility];
+}
+
+nih_export_clap!(TimelinePlugin);
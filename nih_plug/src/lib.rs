@@ -0,0 +1,134 @@
+//! Adapters for driving an `egui_timeline` from a `nih_plug` plugin's transport.
+//!
+//! Kept apart from `egui_timeline` as `nih_plug` is only available as a git dependency. See the
+//! `plugin` example for a CLAP plugin whose editor follows the host's transport.
+
+use egui_timeline::{playhead, ruler::MusicalInfo, view::ViewState, Bar, TimeSig};
+use nih_plug::prelude::Transport;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+/// The tempo assumed when the host doesn't report one.
+pub const DEFAULT_TEMPO: f64 = 120.0;
+
+/// A snapshot of the host's transport, loaded on the GUI thread from a `SharedTransport`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HostTransport {
+    /// Whether or not the host is playing.
+    pub playing: bool,
+    /// The tempo in beats per minute.
+    pub tempo: f64,
+    /// The current time signature.
+    pub time_sig: TimeSig,
+    /// The position in quarter note beats from the start of the project.
+    pub pos_beats: f64,
+}
+
+/// The host's transport, stored on the audio thread and loaded on the GUI thread without locking.
+///
+/// Share between the plugin and its editor via an `Arc`, calling `store` with
+/// `ProcessContext::transport` at the start of each `Plugin::process` call.
+#[derive(Debug, Default)]
+pub struct SharedTransport {
+    playing: AtomicBool,
+    /// The bits of an `f64` tempo, where `0` is unknown.
+    tempo: AtomicU64,
+    /// The numerator in the high 16 bits and denominator in the low, where `0` is unknown.
+    time_sig: AtomicU32,
+    /// The bits of an `f64` position in beats.
+    pos_beats: AtomicU64,
+}
+
+/// Drives the timeline's playhead from the host's transport.
+///
+/// The host owns the transport, so the playhead can't be dragged.
+pub struct HostPlayhead<'a> {
+    /// The view over which the playhead is drawn.
+    pub view: &'a ViewState,
+    /// The transport from which the playhead position is read.
+    pub transport: HostTransport,
+}
+
+impl SharedTransport {
+    /// Store the given transport. Real-time safe.
+    pub fn store(&self, transport: &Transport) {
+        let sig = match (transport.time_sig_numerator, transport.time_sig_denominator) {
+            (Some(n), Some(d)) if n > 0 && d > 0 => (n as u32) << 16 | (d as u32 & 0xFFFF),
+            _ => 0,
+        };
+        let tempo = transport.tempo.unwrap_or(0.0);
+        let pos_beats = transport.pos_beats().unwrap_or(0.0);
+        self.playing.store(transport.playing, Ordering::Relaxed);
+        self.tempo.store(tempo.to_bits(), Ordering::Relaxed);
+        self.time_sig.store(sig, Ordering::Relaxed);
+        self.pos_beats.store(pos_beats.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Load the most recently stored transport, substituting defaults for anything the host
+    /// didn't report.
+    pub fn load(&self) -> HostTransport {
+        let tempo = f64::from_bits(self.tempo.load(Ordering::Relaxed));
+        let sig = self.time_sig.load(Ordering::Relaxed);
+        let time_sig = match sig {
+            0 => TimeSig { top: 4, bottom: 4 },
+            sig => TimeSig {
+                top: (sig >> 16) as u16,
+                bottom: sig as u16,
+            },
+        };
+        HostTransport {
+            playing: self.playing.load(Ordering::Relaxed),
+            tempo: if tempo > 0.0 { tempo } else { DEFAULT_TEMPO },
+            time_sig,
+            pos_beats: f64::from_bits(self.pos_beats.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl HostTransport {
+    /// Match the view's time signature to the host's.
+    pub fn sync_view(&self, view: &mut ViewState) {
+        view.time_sig = self.time_sig;
+    }
+
+    /// The position in absolute ticks for the given number of ticks per beat.
+    pub fn ticks(&self, ticks_per_beat: u32) -> f64 {
+        self.pos_beats * ticks_per_beat as f64
+    }
+
+    /// The playhead for the given view.
+    pub fn playhead<'a>(&self, view: &'a ViewState) -> HostPlayhead<'a> {
+        HostPlayhead {
+            view,
+            transport: *self,
+        }
+    }
+}
+
+impl<'a> MusicalInfo for HostPlayhead<'a> {
+    fn ticks_per_beat(&self) -> u32 {
+        self.view.ticks_per_beat()
+    }
+    fn bar_at_ticks(&self, tick: f32) -> Bar {
+        self.view.bar_at_ticks(tick)
+    }
+    fn ticks_per_point(&self) -> f32 {
+        MusicalInfo::ticks_per_point(self.view)
+    }
+    fn first_bar_number(&self) -> i64 {
+        self.view.first_bar_number()
+    }
+}
+
+impl<'a> playhead::Info for HostPlayhead<'a> {
+    fn playhead_ticks(&self) -> f32 {
+        let ticks = self.transport.ticks(self.view.ticks_per_beat);
+        (ticks - self.view.start as f64) as f32
+    }
+    fn is_playing(&self) -> bool {
+        self.transport.playing
+    }
+}
+
+impl<'a> playhead::Interaction for HostPlayhead<'a> {
+    fn set_playhead_ticks(&mut self, _ticks: f32) {}
+}
@@ -0,0 +1,221 @@
+use super::{
+    arbiter::{self, Layer},
+    cursor::{self, Target},
+    hover, touch, TimelineCtx,
+};
+use std::{hash::Hash, ops::Range};
+
+/// The width of the interactive area at either end of an annotation used for resizing.
+pub const EDGE_WIDTH: f32 = 4.0;
+/// The minimum width of an annotation created by dragging, in points.
+pub const MIN_CREATE_WIDTH: f32 = 8.0;
+
+/// A time-ranged text note, e.g. a production note, subtitle or review comment.
+#[derive(Clone, Debug, Default)]
+pub struct Annotation {
+    /// The start and end of the annotation in ticks relative to the start of the timeline.
+    pub tick_range: Range<f32>,
+    /// The text displayed within the annotation.
+    pub text: String,
+}
+
+/// Access to the annotations displayed within a lane.
+pub trait Annotations {
+    /// Mutable access to all annotations within the lane.
+    fn annotations_mut(&mut self) -> &mut [Annotation];
+    /// A new annotation was created by dragging over an empty area of the lane.
+    ///
+    /// Returns the index of the inserted annotation within `annotations_mut`, which is then edited,
+    /// or `None` if it was rejected.
+    fn insert(&mut self, annotation: Annotation) -> Option<usize>;
+    /// The annotation at the given index was deleted via its context menu.
+    fn remove(&mut self, index: usize);
+}
+
+/// State persisted between frames.
+#[derive(Clone, Copy, Default)]
+struct State {
+    /// The index of the annotation currently being edited.
+    editing: Option<usize>,
+}
+
+impl Annotations for Vec<Annotation> {
+    fn annotations_mut(&mut self) -> &mut [Annotation] {
+        &mut self[..]
+    }
+    fn insert(&mut self, annotation: Annotation) -> Option<usize> {
+        self.push(annotation);
+        Some(self.len() - 1)
+    }
+    fn remove(&mut self, index: usize) {
+        if index < self.len() {
+            Vec::remove(self, index);
+        }
+    }
+}
+
+/// Instantiate an annotation lane of the given height.
+///
+/// - Drag over an empty area to create a new annotation.
+/// - Drag an annotation to move it, or drag either of its edges to resize it.
+/// - Double-click an annotation to edit its text, and right-click to delete it.
+pub fn lane(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    api: &mut dyn Annotations,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let w = ui.available_width();
+    let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(w, height), egui::Sense::hover());
+    let mut response = ui.interact(rect, id, egui::Sense::click_and_drag());
+    let mut state: State = ui.data(|d| d.get_temp(id)).unwrap_or_default();
+    let tick_at = |x: f32| timeline.x_to_tick(x).max(0.0);
+    let ticks_per_point = timeline.ticks_per_point;
+
+    // Create a new annotation by dragging over an empty area.
+    cursor::set(&response, Target::Draw);
+    let mut start_editing = None;
+    let press_origin = ui.input(|i| i.pointer.press_origin());
+    let pointer = response.interact_pointer_pos();
+    let create_range = match (press_origin, pointer) {
+        (Some(a), Some(b)) if response.dragged() || response.drag_stopped() => {
+            let (l, r) = (a.x.min(b.x), a.x.max(b.x));
+            Some(tick_at(l)..tick_at(r.max(l + MIN_CREATE_WIDTH)))
+        }
+        _ => None,
+    };
+    let create_range = create_range.filter(|range| !timeline.is_locked(range.clone()));
+    if let Some(ref tick_range) = create_range {
+        if response.drag_stopped() {
+            let text = String::new();
+            let tick_range = tick_range.clone();
+            start_editing = api.insert(Annotation { tick_range, text });
+            response.mark_changed();
+        }
+    }
+
+    // Interact with and draw each of the existing annotations.
+    let mut remove = None;
+    for (ix, annotation) in api.annotations_mut().iter_mut().enumerate() {
+        let range = &mut annotation.tick_range;
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(range.start),
+            timeline.tick_to_x(range.end),
+        );
+        let a_rect = egui::Rect::from_x_y_ranges(x, rect.y_range()).shrink2([0.0, 1.0].into());
+        if !ui.clip_rect().intersects(a_rect) {
+            continue;
+        }
+        let a_id = id.with(ix);
+        let body_sense =
+            arbiter::sense(ui, Layer::Clip, a_rect, a_id, egui::Sense::click_and_drag());
+        let body = ui.interact(a_rect, a_id, body_sense);
+        hover::register(ui, a_rect, Some(&annotation.text), range.clone());
+        let mut start_rect = a_rect;
+        start_rect.set_width(EDGE_WIDTH);
+        let mut end_rect = a_rect;
+        end_rect.min.x = a_rect.right() - EDGE_WIDTH;
+        let edge = |ui: &mut egui::Ui, rect: egui::Rect, id: egui::Id| {
+            let rect = touch::inflate_x(ui, rect);
+            let sense = arbiter::sense(ui, Layer::Handle, rect, id, egui::Sense::drag());
+            ui.interact(rect, id, sense)
+        };
+        let start = edge(ui, start_rect, a_id.with("start"));
+        let end = edge(ui, end_rect, a_id.with("end"));
+
+        // Move or resize, unless the old or new range is locked.
+        let min_len = EDGE_WIDTH * 2.0 * ticks_per_point;
+        let locked = timeline.is_locked(range.clone());
+        let mut new_range = range.clone();
+        if body.dragged() {
+            let delta = body.drag_delta().x * ticks_per_point;
+            let delta = delta.max(-range.start);
+            new_range.start += delta;
+            new_range.end += delta;
+        }
+        if start.dragged() {
+            let delta = start.drag_delta().x * ticks_per_point;
+            new_range.start = (range.start + delta).min(range.end - min_len).max(0.0);
+        }
+        if end.dragged() {
+            let delta = end.drag_delta().x * ticks_per_point;
+            new_range.end = (range.end + delta).max(range.start + min_len);
+        }
+        if new_range != *range && !locked && !timeline.is_locked(new_range.clone()) {
+            *range = new_range;
+            response.mark_changed();
+        }
+        cursor::set(&body, Target::Move);
+        cursor::set(&start, Target::Edge);
+        cursor::set(&end, Target::Edge);
+        if body.double_clicked() && !locked {
+            start_editing = Some(ix);
+        }
+        body.context_menu(|ui| {
+            if ui
+                .add_enabled(!locked, egui::Button::new("Delete"))
+                .clicked()
+            {
+                remove = Some(ix);
+                ui.close_menu();
+            }
+        });
+
+        // Draw the box.
+        let visuals = ui.style().interact(&body);
+        ui.painter()
+            .rect(a_rect, visuals.rounding, visuals.bg_fill, visuals.bg_stroke);
+
+        // Draw the text or the text editor.
+        let text_rect = a_rect.shrink(ui.spacing().button_padding.x.min(a_rect.width() * 0.5));
+        if state.editing == Some(ix) || start_editing == Some(ix) {
+            let edit = egui::TextEdit::multiline(&mut annotation.text)
+                .frame(false)
+                .desired_width(text_rect.width());
+            let edit = ui.put(text_rect, edit);
+            if start_editing == Some(ix) {
+                edit.request_focus();
+            }
+            if edit.changed() {
+                response.mark_changed();
+            }
+            if edit.lost_focus() {
+                state.editing = None;
+            }
+        } else {
+            let painter = ui
+                .painter()
+                .with_clip_rect(ui.clip_rect().intersect(text_rect));
+            let font_id = egui::TextStyle::Small.resolve(ui.style());
+            let text = annotation.text.clone();
+            let galley = painter.layout(text, font_id, visuals.text_color(), text_rect.width());
+            painter.galley(text_rect.left_top(), galley, visuals.text_color());
+        }
+    }
+    if start_editing.is_some() {
+        state.editing = start_editing;
+    }
+
+    // Draw the preview of the annotation being created.
+    if let Some(range) = create_range {
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(range.start),
+            timeline.tick_to_x(range.end),
+        );
+        let c_rect = egui::Rect::from_x_y_ranges(x, rect.y_range()).shrink2([0.0, 1.0].into());
+        let visuals = ui.style().interact(&response);
+        ui.painter()
+            .rect_stroke(c_rect, visuals.rounding, visuals.fg_stroke);
+    }
+
+    if let Some(ix) = remove {
+        api.remove(ix);
+        state.editing = None;
+        response.mark_changed();
+    }
+
+    ui.data_mut(|d| d.insert_temp(id, state));
+    response
+}
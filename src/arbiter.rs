@@ -0,0 +1,155 @@
+/// The layers of interactive elements that may overlap one another.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Layer {
+    /// The ruler and other pinned track widgets.
+    Ruler,
+    /// Clip, annotation, cue and event bodies.
+    Clip,
+    /// Loop region handles.
+    Loop,
+    /// Edge, gain, keyframe and curve point handles within a track.
+    Handle,
+    /// The playhead line and label.
+    Playhead,
+}
+
+/// The default priority, from highest to lowest.
+pub const DEFAULT_PRIORITY: [Layer; 5] = [
+    Layer::Playhead,
+    Layer::Loop,
+    Layer::Handle,
+    Layer::Clip,
+    Layer::Ruler,
+];
+
+/// A rect registered for interaction during a single pass.
+#[derive(Copy, Clone, Debug)]
+struct Candidate {
+    id: egui::Id,
+    layer: Layer,
+    rect: egui::Rect,
+}
+
+/// The key of the `egui::UiStack` tag identifying the timeline that a `Ui` belongs to.
+const TAG: &str = "egui_timeline::arbiter";
+
+/// State persisted between passes, one per timeline.
+#[derive(Clone, Debug, Default)]
+struct State {
+    /// The pass during which `current` was collected.
+    pass: u64,
+    /// Candidates registered during the previous pass.
+    prev: Vec<Candidate>,
+    /// Candidates registered so far during the current pass.
+    current: Vec<Candidate>,
+}
+
+/// Layers from highest to lowest priority, shared by all timelines.
+#[derive(Clone, Debug)]
+struct Priority(Vec<Layer>);
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self(DEFAULT_PRIORITY.to_vec())
+    }
+}
+
+/// The ID under which the priority is stored.
+fn priority_id() -> egui::Id {
+    egui::Id::new(TAG).with("priority")
+}
+
+/// The ID under which the state of the timeline containing the `ui` is stored.
+///
+/// Elements outside of any timeline share a single state.
+fn state_id(ui: &egui::Ui) -> egui::Id {
+    scoped(ui, egui::Id::new(TAG))
+}
+
+/// Scope the given ID to the timeline containing the `ui`, as tagged via `tag`, so that state
+/// stored under it isn't shared between timelines.
+///
+/// Elements outside of any timeline share the unscoped ID.
+pub(crate) fn scoped(ui: &egui::Ui, id: egui::Id) -> egui::Id {
+    let timeline = ui
+        .stack()
+        .iter()
+        .find_map(|frame| frame.tags().get_downcast::<egui::Id>(TAG).copied());
+    match timeline {
+        Some(timeline) => id.with(timeline),
+        None => id,
+    }
+}
+
+/// Tag a `Ui` as belonging to the timeline with the given ID, so that elements within it only
+/// compete with elements of the same timeline.
+pub(crate) fn tag(info: egui::UiStackInfo, timeline: egui::Id) -> egui::UiStackInfo {
+    info.with_tag_value(TAG, timeline)
+}
+
+/// Override the priority of overlapping interactive elements, from highest to lowest.
+///
+/// Layers not present in `order` have the lowest priority.
+pub fn set_priority(ctx: &egui::Context, order: &[Layer]) {
+    ctx.data_mut(|d| d.insert_temp(priority_id(), Priority(order.to_vec())));
+}
+
+/// Register the interactive `rect` of the element with the given `id` on the given `layer`.
+///
+/// Returns whether or not the element may interact this pass. An element may not interact if the
+/// pointer is over an element with higher priority as of the previous pass, or if another
+/// registered element is being dragged. This allows for resolving priority deterministically,
+/// rather than depending on the order in which widgets are instantiated.
+pub(crate) fn claim(ui: &egui::Ui, layer: Layer, rect: egui::Rect, id: egui::Id) -> bool {
+    let ctx = ui.ctx();
+    let pass = ctx.cumulative_pass_nr();
+    let pointer = ctx.pointer_hover_pos();
+    let dragged = ctx.dragged_id();
+    let rect = rect.intersect(ui.clip_rect());
+    let state_id = state_id(ui);
+    ctx.data_mut(|d| {
+        let priority = d
+            .get_temp_mut_or_default::<Priority>(priority_id())
+            .0
+            .clone();
+        let state = d.get_temp_mut_or_default::<State>(state_id);
+        if state.pass != pass {
+            state.prev = std::mem::take(&mut state.current);
+            state.pass = pass;
+        }
+        state.current.push(Candidate { id, layer, rect });
+
+        // Only the dragged element may interact while a drag is in progress.
+        match dragged {
+            Some(dragged) if dragged == id => return true,
+            Some(dragged) if state.prev.iter().any(|c| c.id == dragged) => return false,
+            _ => (),
+        }
+
+        let pos = match pointer {
+            Some(pos) => pos,
+            None => return true,
+        };
+        let len = priority.len();
+        let rank = |l: Layer| priority.iter().position(|&p| p == l).unwrap_or(len);
+        let own = rank(layer);
+        !state
+            .prev
+            .iter()
+            .any(|c| c.id != id && c.rect.contains(pos) && rank(c.layer) < own)
+    })
+}
+
+/// Short-hand for selecting the sense for an element based on the result of `claim`.
+pub(crate) fn sense(
+    ui: &egui::Ui,
+    layer: Layer,
+    rect: egui::Rect,
+    id: egui::Id,
+    sense: egui::Sense,
+) -> egui::Sense {
+    match claim(ui, layer, rect, id) {
+        true => sense,
+        false => egui::Sense::hover(),
+    }
+}
@@ -0,0 +1,74 @@
+use super::{curve, TracksCtx};
+use std::hash::Hash;
+
+/// Access to the automation sub-lanes owned by a track, e.g. volume, pan and sends.
+pub trait Automation {
+    /// The total number of sub-lanes.
+    fn lane_count(&self) -> usize;
+    /// The name of the sub-lane at the given index, displayed in its indented header.
+    fn lane_name(&self, lane: usize) -> &str;
+    /// Access to the curve of the sub-lane at the given index.
+    fn lane_curve(&mut self, lane: usize) -> &mut dyn curve::Curve;
+}
+
+/// The collapsing state of the sub-lanes with the given ID.
+fn state(ctx: &egui::Context, id: egui::Id) -> egui::collapsing_header::CollapsingState {
+    let id = id.with("automation");
+    egui::collapsing_header::CollapsingState::load_with_default_open(ctx, id, false)
+}
+
+/// Show a button toggling the sub-lanes with the given `id_source`, typically within the owning
+/// track's header.
+///
+/// The `id_source` must match that given to `sub_lanes` and be unique within the timeline.
+pub fn toggle(ui: &mut egui::Ui, id_source: impl Hash) -> egui::Response {
+    let mut state = state(ui.ctx(), egui::Id::new(id_source));
+    let response = state.show_toggle_button(ui, egui::collapsing_header::paint_default_icon);
+    let hint = match state.is_open() {
+        true => "Hide automation",
+        false => "Show automation",
+    };
+    state.store(ui.ctx());
+    response.on_hover_text(hint)
+}
+
+/// Whether or not the sub-lanes with the given `id_source` are expanded.
+pub fn expanded(ctx: &egui::Context, id_source: impl Hash) -> bool {
+    state(ctx, egui::Id::new(id_source)).is_open()
+}
+
+/// Set the automation sub-lanes of the owning track while expanded, with one track per sub-lane
+/// beneath it with an indented header. Each sub-lane is a curve editor `lane` of the given height.
+///
+/// This is designed to be called from within the `Show::tracks` closure, directly after setting
+/// the owning track. Returns whether or not any of the sub-lane curves were changed.
+pub fn sub_lanes(
+    tracks: &TracksCtx,
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    height: f32,
+    api: &mut dyn Automation,
+) -> bool {
+    let id = egui::Id::new(id_source);
+    let mut changed = false;
+    if !state(ui.ctx(), id).is_open() {
+        return changed;
+    }
+    for lane in 0..api.lane_count() {
+        let name = api.lane_name(lane).to_string();
+        let curve = api.lane_curve(lane);
+        tracks
+            .next(ui)
+            .header(|ui| {
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.spacing().indent);
+                    ui.label(name);
+                });
+            })
+            .show(|timeline, ui| {
+                let lane = curve::lane(ui, id.with(lane), timeline, height, curve);
+                changed |= lane.changed();
+            });
+    }
+    changed
+}
@@ -0,0 +1,65 @@
+use super::{TimelineApi, TracksLayout, View};
+use std::hash::Hash;
+
+/// The named views stored under a single ID, in the order in which they were first saved.
+type Bookmarks = Vec<(String, View)>;
+
+/// Save the given view as a named bookmark, replacing any existing bookmark with the same name.
+///
+/// Bookmarks are stored within egui's memory under the given `id_source`. The current view is
+/// available via `TracksLayout::view`.
+pub fn save(ctx: &egui::Context, id_source: impl Hash, name: impl Into<String>, view: View) {
+    let id = egui::Id::new(id_source);
+    let name = name.into();
+    ctx.data_mut(|d| {
+        let bookmarks = d.get_temp_mut_or_default::<Bookmarks>(id);
+        match bookmarks.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = view,
+            None => bookmarks.push((name, view)),
+        }
+    });
+}
+
+/// The bookmark with the given name, if any.
+pub fn get(ctx: &egui::Context, id_source: impl Hash, name: &str) -> Option<View> {
+    let id = egui::Id::new(id_source);
+    ctx.data(|d| d.get_temp::<Bookmarks>(id))?
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, view)| view)
+}
+
+/// Remove the bookmark with the given name, returning whether or not it existed.
+pub fn remove(ctx: &egui::Context, id_source: impl Hash, name: &str) -> bool {
+    let id = egui::Id::new(id_source);
+    ctx.data_mut(|d| {
+        let bookmarks = d.get_temp_mut_or_default::<Bookmarks>(id);
+        let len = bookmarks.len();
+        bookmarks.retain(|(n, _)| n != name);
+        bookmarks.len() != len
+    })
+}
+
+/// The names of all saved bookmarks, in the order in which they were first saved.
+pub fn names(ctx: &egui::Context, id_source: impl Hash) -> Vec<String> {
+    let id = egui::Id::new(id_source);
+    ctx.data(|d| d.get_temp::<Bookmarks>(id))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Jump to the bookmark with the given name via `TracksLayout::set_view`, returning the view if
+/// the bookmark exists.
+pub fn recall(
+    ctx: &egui::Context,
+    id_source: impl Hash,
+    name: &str,
+    layout: &TracksLayout,
+    timeline: &mut dyn TimelineApi,
+) -> Option<View> {
+    let view = get(ctx, id_source, name)?;
+    layout.set_view(ctx, &view, timeline);
+    Some(view)
+}
@@ -0,0 +1,75 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+/// A tessellated mesh along with the key it was produced for.
+#[derive(Clone)]
+struct Entry {
+    key: u64,
+    mesh: egui::Mesh,
+}
+
+/// Paint the shapes produced by `shapes` via a mesh cached under `id` between frames.
+///
+/// The shapes are only produced and tessellated when `key` changes. The key should capture
+/// everything affecting the shapes, e.g. the viewport, zoom and style, while the scale factor and
+/// the size of the font atlas (whose texture coordinates text meshes depend on) are accounted for
+/// automatically.
+pub(crate) fn paint(
+    painter: &egui::Painter,
+    id: egui::Id,
+    key: impl Hash,
+    shapes: impl FnOnce() -> Vec<egui::Shape>,
+) {
+    let ctx = painter.ctx();
+    let pixels_per_point = ctx.pixels_per_point();
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    pixels_per_point.to_bits().hash(&mut hasher);
+    ctx.fonts(|f| f.font_image_size()).hash(&mut hasher);
+    let key = hasher.finish();
+    let cached = ctx
+        .data(|d| d.get_temp::<Entry>(id))
+        .filter(|entry| entry.key == key);
+    let mesh = match cached {
+        Some(entry) => entry.mesh,
+        None => {
+            let mesh = tessellate(ctx, shapes());
+            let entry = Entry {
+                key,
+                mesh: mesh.clone(),
+            };
+            ctx.data_mut(|d| d.insert_temp(id, entry));
+            mesh
+        }
+    };
+    painter.add(egui::Shape::mesh(mesh));
+}
+
+/// The bits of the given rect, for use within cache keys.
+pub(crate) fn rect_bits(rect: egui::Rect) -> [u32; 4] {
+    [
+        rect.min.x.to_bits(),
+        rect.min.y.to_bits(),
+        rect.max.x.to_bits(),
+        rect.max.y.to_bits(),
+    ]
+}
+
+/// Tessellate the given shapes into a single mesh.
+fn tessellate(ctx: &egui::Context, shapes: Vec<egui::Shape>) -> egui::Mesh {
+    let pixels_per_point = ctx.pixels_per_point();
+    let options = ctx.tessellation_options(|o| *o);
+    let (font_tex_size, prepared_discs) = ctx.fonts(|f| {
+        let discs = f.texture_atlas().lock().prepared_discs();
+        (f.font_image_size(), discs)
+    });
+    let mut tessellator =
+        egui::epaint::Tessellator::new(pixels_per_point, options, font_tex_size, prepared_discs);
+    let mut mesh = egui::Mesh::default();
+    for shape in shapes {
+        tessellator.tessellate_shape(shape, &mut mesh);
+    }
+    mesh
+}
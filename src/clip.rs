@@ -0,0 +1,1068 @@
+use super::{
+    arbiter::{self, Layer},
+    cursor::{self, Target},
+    detail::DetailLevel,
+    duplicate::{self, Duplicate},
+    format::TimeFormatter,
+    gesture::{self, Gesture},
+    hover,
+    intervals::IntervalIndex,
+    paint_hatching, progress,
+    ripple::{self, Ripple},
+    touch, waveform, TimelineCtx,
+};
+use std::ops::{Range, RangeInclusive};
+
+/// The width of the interactive area at each edge of a clip used for stretching.
+pub const EDGE_WIDTH: f32 = 4.0;
+/// The height of the bar drawn along the bottom of clips belonging to a group.
+pub const GROUP_INDICATOR_HEIGHT: f32 = 3.0;
+/// The size of the grips drawn at a hovered clip's edges and fade handles.
+pub const GRIP_SIZE: f32 = 6.0;
+/// The size of the interactive area around each fade handle, larger than its grip.
+pub const FADE_HANDLE_SIZE: f32 = 12.0;
+/// The height of the interactive area around the gain line.
+pub const GAIN_HANDLE_HEIGHT: f32 = 6.0;
+/// The width of the interactive area around each warp marker.
+pub const WARP_MARKER_WIDTH: f32 = 6.0;
+/// The distance in points within which a moved clip's edges snap to those of its neighbours.
+pub const SNAP_DISTANCE: f32 = 8.0;
+
+/// For retrieving information about a clip.
+pub trait Info {
+    /// A unique identifier for the clip, stable across frames.
+    fn id(&self) -> egui::Id;
+    /// The start and end of the clip in ticks relative to the start of the timeline.
+    fn tick_range(&self) -> Range<f32>;
+    /// The clip's gain (or velocity), displayed as a draggable line across the clip.
+    ///
+    /// Returns `None` by default, in which case no gain handle is shown.
+    fn gain(&self) -> Option<f32> {
+        None
+    }
+    /// The range of the gain handle, mapped from the bottom to the top of the clip.
+    fn gain_range(&self) -> RangeInclusive<f32> {
+        0.0..=1.0
+    }
+    /// Optional min/max peak pairs in the range `-1.0..=1.0`, spread evenly across the clip.
+    ///
+    /// The waveform is scaled by the clip's `gain`.
+    fn waveform(&self) -> Option<&[[f32; 2]]> {
+        None
+    }
+    /// The name of the clip, displayed in its top-left corner.
+    fn name(&self) -> Option<&str> {
+        None
+    }
+    /// The color of the clip. By default, uses the track's color if one was given.
+    fn color(&self) -> Option<egui::Color32> {
+        None
+    }
+    /// The color at the bottom of the clip, drawn as a vertical gradient from `color` at the top.
+    ///
+    /// Returns `None` by default, in which case the clip is filled with a solid color.
+    fn gradient_color(&self) -> Option<egui::Color32> {
+        None
+    }
+    /// Whether or not the clip is selected, drawn with a highlighted outline.
+    ///
+    /// Clips within the `selection::SelectionState` set via `Timeline::selection` are also drawn
+    /// selected.
+    fn is_selected(&self) -> bool {
+        false
+    }
+    /// Whether or not the clip is muted or disabled, drawn faded and hatched.
+    fn is_muted(&self) -> bool {
+        false
+    }
+    /// Whether or not the clip may be deleted via its context menu.
+    fn is_deletable(&self) -> bool {
+        false
+    }
+    /// Whether or not the clip is being recorded, drawn with a red tint and outline.
+    fn is_recording(&self) -> bool {
+        false
+    }
+    /// Formats the delta readout shown while the clip is being moved, e.g. `+2.1.0`.
+    ///
+    /// By default the delta is shown in ticks.
+    fn delta_formatter(&self) -> Option<&dyn TimeFormatter> {
+        None
+    }
+    /// A label for the drop target under the pointer while the clip is being moved, e.g.
+    /// `Track 5`, shown alongside the delta readout.
+    fn drop_target(&self, _pointer: egui::Pos2) -> Option<String> {
+        None
+    }
+    /// Paint custom content within the clip's `rect`, after the body and built-in waveform.
+    ///
+    /// The `detail` reflects the timeline's current zoom level, allowing content to switch
+    /// between full, simplified and solid-block rendering consistently with other clips.
+    fn paint_content(&self, _painter: &egui::Painter, _rect: egui::Rect, _detail: DetailLevel) {}
+    /// Paint custom adornments over the clip's `rect`, e.g. icons or badges.
+    ///
+    /// Called last, above the outline and name, with the painter clipped to the clip.
+    fn paint_overlay(&self, _painter: &egui::Painter, _rect: egui::Rect, _state: PaintState) {}
+    /// The progress of a long-running operation on the clip, e.g. rendering or freezing, in the
+    /// range `0.0..=1.0`.
+    ///
+    /// When provided, a `progress::paint` overlay is drawn over the clip beneath `paint_overlay`.
+    /// Hosts should keep repainting while in progress so that the stripes stay animated.
+    fn progress(&self) -> Option<f32> {
+        None
+    }
+    /// The tempo of the clip's source material in beats per minute.
+    ///
+    /// When provided, the stretched tempo is shown alongside the ratio while stretching.
+    fn tempo(&self) -> Option<f32> {
+        None
+    }
+    /// Anchor points mapping the clip's source time to timeline ticks, drawn as draggable lines
+    /// within the clip. Markers should be ordered by tick.
+    ///
+    /// Returns no markers by default.
+    fn warp_markers(&self) -> &[WarpMarker] {
+        &[]
+    }
+    /// The length of the fade in at the start of the clip in ticks.
+    ///
+    /// When provided, the fade is drawn over the clip with a handle at its top corner, shown while
+    /// the clip is hovered. Returns `None` by default.
+    fn fade_in(&self) -> Option<f32> {
+        None
+    }
+    /// The length of the fade out at the end of the clip in ticks. See `fade_in`.
+    fn fade_out(&self) -> Option<f32> {
+        None
+    }
+    /// The group to which the clip belongs, e.g. a multitrack drum recording.
+    ///
+    /// Moving or stretching one member of a group previews the same edit on all other members
+    /// drawn during the same frame, and emits it via `Interaction::group_edit` once released.
+    /// Returns `None` by default.
+    fn group(&self) -> Option<egui::Id> {
+        None
+    }
+    /// The clips sharing the track, indexed by ID.
+    ///
+    /// When provided, the clip's edges snap to those of its neighbours while moved, and the ghost
+    /// is highlighted if the clip would overlap another.
+    fn neighbours(&self) -> Option<&IntervalIndex<egui::Id>> {
+        None
+    }
+}
+
+/// For handling interaction with a clip.
+pub trait Interaction {
+    /// The gain handle was dragged to the given value.
+    fn set_gain(&mut self, _gain: f32) {}
+    /// The clip body was dragged, moving the clip to the given range of ticks.
+    fn set_tick_range(&mut self, _range: Range<f32>) {}
+    /// The gain handle is being dragged, with the initial and current gain.
+    fn gain_gesture(&mut self, _gesture: Gesture<f32>) {}
+    /// The clip is being moved, with the initial and current range of ticks.
+    fn move_gesture(&mut self, _gesture: Gesture<Range<f32>>) {}
+    /// The clip body was dragged with the duplicate modifier held, leaving the clip in place.
+    fn duplicate(&mut self, _duplicate: Duplicate<egui::Id>) {}
+    /// An edge of the clip was dragged with the stretch modifier held, stretching the clip's
+    /// content to fit the new range.
+    fn stretch(&mut self, _stretch: Stretch) {}
+    /// The clip is being stretched, with the initial and current range of ticks.
+    fn stretch_gesture(&mut self, _gesture: Gesture<Range<f32>>) {}
+    /// The clip was moved or stretched as a member of the given group, to be applied by the host to
+    /// all members of the group as a single edit, e.g. via `EditKind::apply`. Emitted instead of
+    /// `set_tick_range` or `stretch`.
+    fn group_edit(&mut self, _group: egui::Id, _edit: EditKind) {}
+    /// The clip was deleted via its context menu.
+    fn delete(&mut self) {}
+    /// The clip was deleted or its end was stretched with ripple editing enabled, shifting the
+    /// following clips. Emitted alongside `delete` or `stretch`, to be applied as a single edit.
+    fn ripple(&mut self, _ripple: Ripple) {}
+    /// The warp marker at the given index was dragged to the given tick, leaving its source time
+    /// in place.
+    fn set_warp_marker(&mut self, _index: usize, _tick: f32) {}
+    /// The warp marker at the given index is being dragged, with the initial and current tick.
+    fn warp_marker_gesture(&mut self, _index: usize, _gesture: Gesture<f32>) {}
+    /// The fade in handle was dragged, setting the fade's length in ticks.
+    fn set_fade_in(&mut self, _ticks: f32) {}
+    /// The fade out handle was dragged, setting the fade's length in ticks.
+    fn set_fade_out(&mut self, _ticks: f32) {}
+    /// The fade in handle is being dragged, with the initial and current length.
+    fn fade_in_gesture(&mut self, _gesture: Gesture<f32>) {}
+    /// The fade out handle is being dragged, with the initial and current length.
+    fn fade_out_gesture(&mut self, _gesture: Gesture<f32>) {}
+}
+
+/// The state of a clip as drawn, passed to `Info::paint_overlay`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PaintState {
+    /// Whether or not the pointer is over the clip's body.
+    pub hovered: bool,
+    /// Whether or not the clip's body is being dragged.
+    pub dragged: bool,
+    /// Whether or not the clip is selected.
+    pub selected: bool,
+    /// Whether or not the clip is muted, or its track bypassed via `TrackCtx::bypass`.
+    pub muted: bool,
+    /// Whether or not the clip is being recorded.
+    pub recording: bool,
+    /// Whether or not edits to the clip are locked.
+    pub locked: bool,
+    /// How much detail content should be drawn with at the current zoom level.
+    pub detail: DetailLevel,
+}
+
+/// An edit being made to one member of a group, shared with the other members for previewing.
+#[derive(Clone, Debug)]
+struct GroupEdit {
+    /// The member being edited.
+    source: egui::Id,
+    /// The pass during which the edit was last updated.
+    pass: u64,
+    /// The edit to preview on each member's range.
+    kind: EditKind,
+}
+
+/// An edit made to a member of a group, emitted via `Interaction::group_edit` to be applied to
+/// all members.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EditKind {
+    /// Move by the given number of ticks.
+    Move(f32),
+    /// Stretch by moving the start by the given number of ticks.
+    StretchStart(f32),
+    /// Stretch by moving the end by the given number of ticks.
+    StretchEnd(f32),
+}
+
+/// An anchor point pinning a position within a clip's source material to the timeline.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WarpMarker {
+    /// The position of the marker in ticks relative to the start of the timeline.
+    pub tick: f32,
+    /// The position within the clip's source material, e.g. in seconds or samples.
+    pub source: f64,
+}
+
+/// A time-stretch of a clip, emitted via `Interaction::stretch` when a stretch drag is released.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stretch {
+    /// The range of the clip in ticks prior to stretching.
+    pub initial: Range<f32>,
+    /// The stretched range of the clip in ticks.
+    pub range: Range<f32>,
+    /// The stretched length relative to the initial length, e.g. `2.0` for half speed.
+    pub ratio: f32,
+}
+
+/// For both providing info and handling interaction.
+pub trait Clip: Info + Interaction {}
+
+impl<T> Clip for T where T: Info + Interaction {}
+
+/// Set the clip widget within the given vertical range of the track.
+///
+/// The clip is positioned along the x axis using its `tick_range`. It does not allocate any space
+/// within the `ui`, allowing many clips to share the same track row. Dragging the clip body draws
+/// a translucent ghost at the target position along with a readout of the delta, leaving the clip
+/// in place until the drag is released, at which point it is moved via
+/// `Interaction::set_tick_range`. If the duplicate modifier is held upon release, the clip is
+/// instead left in place and `Interaction::duplicate` is called.
+///
+/// Dragging either edge with the stretch modifier held stretches the clip, drawing a ghost with a
+/// readout of the stretch ratio and tempo until released, at which point `Interaction::stretch` is
+/// called.
+///
+/// Deletable clips may be deleted via their context menu. With ripple editing enabled via
+/// `Timeline::ripple`, deleting a clip or stretching its end previews the shift of the following
+/// clips and emits it via `Interaction::ripple`.
+///
+/// Grouped clips are marked with a bar along their bottom edge colored by group. Moving or
+/// stretching one member previews the edit on the other members, and emits it once released via
+/// `Interaction::group_edit` for the host to apply to the whole group.
+///
+/// Warp markers are drawn as lines within the clip and may be dragged between their neighbours,
+/// calling `Interaction::set_warp_marker`.
+///
+/// The clip's waveform and custom content are drawn according to `TimelineCtx::detail`.
+pub fn set(
+    ui: &mut egui::Ui,
+    timeline: &TimelineCtx,
+    y: egui::Rangef,
+    api: &mut dyn Clip,
+) -> egui::Response {
+    profile_scope!("clip");
+    let id = api.id();
+    let tick_range = api.tick_range();
+    let x = egui::Rangef::new(
+        timeline.tick_to_x(tick_range.start),
+        timeline.tick_to_x(tick_range.end),
+    );
+    let rect = egui::Rect::from_x_y_ranges(x, y);
+    let sense = arbiter::sense(ui, Layer::Clip, rect, id, egui::Sense::click_and_drag());
+    let mut response = ui.interact(rect, id, sense);
+    hover::register(ui, rect, api.name(), tick_range.clone());
+    let group = api.group();
+
+    // Handle moving the clip by dragging its body, accumulating the offset until released.
+    cursor::set(&response, Target::Move);
+    let offset_id = id.with("move_offset");
+    let mut target = None;
+    if response.dragged() || response.drag_stopped() {
+        let offset: f32 = ui.data(|d| d.get_temp(offset_id)).unwrap_or(0.0);
+        let delta = response.drag_delta().x * timeline.ticks_per_point;
+        let offset = (offset + delta).max(-tick_range.start);
+        ui.data_mut(|d| d.insert_temp(offset_id, offset));
+        let mut new_range = tick_range.start + offset..tick_range.end + offset;
+        if let Some(neighbours) = api.neighbours() {
+            let snap_ticks = SNAP_DISTANCE * timeline.ticks_per_point;
+            let snap = snap_delta(neighbours, id, new_range.clone(), snap_ticks);
+            let snap = snap.max(-new_range.start);
+            new_range = new_range.start + snap..new_range.end + snap;
+        }
+        let locked =
+            timeline.is_locked(tick_range.clone()) || timeline.is_locked(new_range.clone());
+        if !locked {
+            target = Some(new_range);
+        }
+    }
+    if response.drag_stopped() {
+        ui.data_mut(|d| d.remove::<f32>(offset_id));
+        let pointer = response.interact_pointer_pos();
+        match (target.clone(), pointer) {
+            (Some(range), Some(pointer)) if duplicate::modifier_held(ui) => {
+                api.duplicate(Duplicate {
+                    sources: vec![id],
+                    delta: range.start - tick_range.start,
+                    tick: range.start,
+                    pointer,
+                });
+                response.mark_changed();
+            }
+            (Some(range), _) if range != tick_range => {
+                match group {
+                    Some(group) => {
+                        api.group_edit(group, EditKind::Move(range.start - tick_range.start))
+                    }
+                    None => api.set_tick_range(range),
+                }
+                response.mark_changed();
+            }
+            _ => (),
+        }
+    }
+    let after = match duplicate::modifier_held(ui) && response.drag_stopped() {
+        true => tick_range.clone(),
+        false => target.clone().unwrap_or_else(|| tick_range.clone()),
+    };
+    if let Some(gesture) = gesture::track(ui, &response, tick_range.clone(), after) {
+        if let Gesture::Cancelled { .. } = gesture {
+            ui.data_mut(|d| d.remove::<f32>(offset_id));
+            target = None;
+        }
+        api.move_gesture(gesture);
+    }
+    if let Some(group) = group {
+        match target.clone() {
+            Some(range) if response.dragged() && !duplicate::modifier_held(ui) => {
+                let edit = EditKind::Move(range.start - tick_range.start);
+                publish_group_edit(ui, group, id, edit);
+            }
+            _ if response.dragged() || response.drag_stopped() => {
+                withdraw_group_edit(ui, group, id)
+            }
+            _ => (),
+        }
+    }
+
+    // Handle dragging of the gain line.
+    let gain_range = api.gain_range();
+    let gain_y = |gain: f32| egui::remap_clamp(gain, gain_range.clone(), rect.y_range().flip());
+    let gain_response = api.gain().map(|gain| {
+        let y = gain_y(gain);
+        let half_h = GAIN_HANDLE_HEIGHT * 0.5;
+        let handle_rect = egui::Rect::from_x_y_ranges(rect.x_range(), y - half_h..=y + half_h);
+        let handle_rect = touch::inflate_y(ui, handle_rect);
+        let handle_id = id.with("gain");
+        let sense = arbiter::sense(
+            ui,
+            Layer::Handle,
+            handle_rect,
+            handle_id,
+            egui::Sense::drag(),
+        );
+        let handle = ui.interact(handle_rect, handle_id, sense);
+        if handle.dragged() && !timeline.is_locked(tick_range.clone()) {
+            if let Some(pt) = handle.interact_pointer_pos() {
+                let gain = egui::remap_clamp(pt.y, rect.y_range().flip(), gain_range.clone());
+                api.set_gain(gain);
+                response.mark_changed();
+            }
+        }
+        let after = api.gain().unwrap_or(gain);
+        if let Some(gesture) = gesture::track(ui, &handle, gain, after) {
+            if let Gesture::Cancelled { initial } = gesture {
+                api.set_gain(initial);
+            }
+            api.gain_gesture(gesture);
+        }
+        cursor::set(&handle, Target::VerticalHandle);
+        handle
+    });
+
+    // Handle stretching by dragging either edge with the stretch modifier held.
+    let min_len = EDGE_WIDTH * 2.0 * timeline.ticks_per_point;
+    let stretch_offset_id = id.with("stretch_offset");
+    let mut start_rect = rect;
+    start_rect.set_width(EDGE_WIDTH.min(rect.width() * 0.5));
+    let mut end_rect = rect;
+    end_rect.min.x = (rect.right() - EDGE_WIDTH).max(rect.center().x);
+    let mut stretch_target = None;
+    for (edge_rect, edge_id, is_start) in [
+        (start_rect, id.with("stretch_start"), true),
+        (end_rect, id.with("stretch_end"), false),
+    ] {
+        // Edges are only interactive while the modifier is held or a stretch is in progress.
+        let ctx = ui.ctx();
+        let stretching = ctx.is_being_dragged(edge_id) || ctx.drag_stopped_id() == Some(edge_id);
+        if !stretch_modifier_held(ui, timeline) && !stretching {
+            continue;
+        }
+        let edge_rect = touch::inflate_x(ui, edge_rect);
+        let sense = arbiter::sense(ui, Layer::Handle, edge_rect, edge_id, egui::Sense::drag());
+        let handle = ui.interact(edge_rect, edge_id, sense);
+        cursor::set(&handle, Target::Edge);
+        let mut target = None;
+        if handle.dragged() || handle.drag_stopped() {
+            let offset: f32 = ui.data(|d| d.get_temp(stretch_offset_id)).unwrap_or(0.0);
+            let offset = offset + handle.drag_delta().x * timeline.ticks_per_point;
+            ui.data_mut(|d| d.insert_temp(stretch_offset_id, offset));
+            let mut new_range = tick_range.clone();
+            match is_start {
+                true => {
+                    let start = tick_range.start + offset;
+                    new_range.start = start.min(tick_range.end - min_len).max(0.0);
+                }
+                false => {
+                    let end = tick_range.end + offset;
+                    new_range.end = end.max(tick_range.start + min_len);
+                }
+            }
+            let locked =
+                timeline.is_locked(tick_range.clone()) || timeline.is_locked(new_range.clone());
+            if !locked {
+                target = Some(new_range);
+            }
+        }
+        // Moving the end ripples the following clips when enabled.
+        let ripple = match (timeline.ripple, &target) {
+            (Some(scope), Some(range)) if !is_start && range.end != tick_range.end => {
+                Some(Ripple {
+                    scope,
+                    track: timeline.track_id,
+                    source: id,
+                    from: tick_range.end,
+                    delta: range.end - tick_range.end,
+                })
+            }
+            _ => None,
+        };
+        if handle.drag_stopped() {
+            ui.data_mut(|d| d.remove::<f32>(stretch_offset_id));
+            if let Some(range) = target.clone().filter(|r| *r != tick_range) {
+                match group {
+                    Some(group) => {
+                        api.group_edit(group, stretch_edit(is_start, tick_range.clone(), range))
+                    }
+                    None => {
+                        let ratio = (range.end - range.start) / (tick_range.end - tick_range.start);
+                        let initial = tick_range.clone();
+                        api.stretch(Stretch {
+                            initial,
+                            range,
+                            ratio,
+                        });
+                    }
+                }
+                if let Some(ripple) = ripple.clone() {
+                    api.ripple(ripple);
+                }
+                response.mark_changed();
+            }
+            ripple::clear(ui);
+        } else if let Some(ripple) = ripple.filter(|_| handle.dragged()) {
+            ripple::preview(ui, ripple);
+        }
+        let after = target.clone().unwrap_or_else(|| tick_range.clone());
+        if let Some(gesture) = gesture::track(ui, &handle, tick_range.clone(), after) {
+            if let Gesture::Cancelled { .. } = gesture {
+                ui.data_mut(|d| d.remove::<f32>(stretch_offset_id));
+                target = None;
+            }
+            api.stretch_gesture(gesture);
+        }
+        if let Some(group) = group {
+            match target.clone() {
+                Some(range) if handle.dragged() => {
+                    let edit = stretch_edit(is_start, tick_range.clone(), range);
+                    publish_group_edit(ui, group, id, edit);
+                }
+                _ if handle.dragged() || handle.drag_stopped() => {
+                    withdraw_group_edit(ui, group, id)
+                }
+                _ => (),
+            }
+        }
+        if handle.dragged() {
+            stretch_target = stretch_target.or(target);
+        }
+    }
+
+    // Handle dragging the fade handles along the top corners, never letting the fades overlap.
+    let len = tick_range.end - tick_range.start;
+    let locked = timeline.is_locked(tick_range.clone());
+    let fade_x = |fade_in: bool, fade: f32| match fade_in {
+        true => timeline.tick_to_x(tick_range.start + fade),
+        false => timeline.tick_to_x(tick_range.end - fade),
+    };
+    let mut fade_responses = vec![];
+    for fade_in in [true, false] {
+        let (fade, other) = match fade_in {
+            true => (api.fade_in(), api.fade_out()),
+            false => (api.fade_out(), api.fade_in()),
+        };
+        let Some(fade) = fade else {
+            continue;
+        };
+        let center = egui::Pos2::new(fade_x(fade_in, fade), rect.top() + GRIP_SIZE * 0.5);
+        let handle_rect = egui::Rect::from_center_size(center, egui::Vec2::splat(FADE_HANDLE_SIZE));
+        let handle_rect = touch::inflate_y(ui, touch::inflate_x(ui, handle_rect));
+        let handle_id = id.with(("fade", fade_in));
+        let sense = arbiter::sense(
+            ui,
+            Layer::Handle,
+            handle_rect,
+            handle_id,
+            egui::Sense::drag(),
+        );
+        let handle = ui.interact(handle_rect, handle_id, sense);
+        cursor::set(&handle, Target::Edge);
+        if let Some(pos) = handle
+            .interact_pointer_pos()
+            .filter(|_| handle.dragged() && !locked)
+        {
+            let tick = timeline.x_to_tick(pos.x);
+            let new = match fade_in {
+                true => tick - tick_range.start,
+                false => tick_range.end - tick,
+            };
+            let new = new.clamp(0.0, (len - other.unwrap_or(0.0)).max(0.0));
+            if new != fade {
+                match fade_in {
+                    true => api.set_fade_in(new),
+                    false => api.set_fade_out(new),
+                }
+                response.mark_changed();
+            }
+        }
+        let after = match fade_in {
+            true => api.fade_in(),
+            false => api.fade_out(),
+        };
+        if let Some(gesture) = gesture::track(ui, &handle, fade, after.unwrap_or(fade)) {
+            match (fade_in, &gesture) {
+                (true, &Gesture::Cancelled { initial }) => api.set_fade_in(initial),
+                (false, &Gesture::Cancelled { initial }) => api.set_fade_out(initial),
+                _ => (),
+            }
+            match fade_in {
+                true => api.fade_in_gesture(gesture),
+                false => api.fade_out_gesture(gesture),
+            }
+        }
+        fade_responses.push((fade_in, handle));
+    }
+
+    // Select the clip on click, or toggle it within the selection while `Shift` is held.
+    let mut selection = timeline.selection(ui.ctx());
+    if let Some(state) = selection.as_mut().filter(|_| response.clicked()) {
+        state.select_clip(id, ui.input(|i| i.modifiers.shift));
+        timeline.set_selection(ui.ctx(), state.clone());
+        response.mark_changed();
+    }
+    let selected = api.is_selected() || selection.is_some_and(|s| s.clips.contains(&id));
+
+    // Delete via the context menu, previewing the ripple while the button is hovered.
+    if api.is_deletable() {
+        let locked = timeline.is_locked(tick_range.clone());
+        let ripple = timeline.ripple.map(|scope| Ripple {
+            scope,
+            track: timeline.track_id,
+            source: id,
+            from: tick_range.end,
+            delta: tick_range.start - tick_range.end,
+        });
+        let mut deleted = false;
+        response.context_menu(|ui| {
+            let delete = ui.add_enabled(!locked, egui::Button::new("Delete"));
+            if let Some(ripple) = ripple.clone().filter(|_| delete.hovered()) {
+                ripple::preview(ui, ripple);
+            }
+            if delete.clicked() {
+                api.delete();
+                if let Some(ripple) = ripple {
+                    api.ripple(ripple);
+                }
+                ripple::clear(ui);
+                deleted = true;
+                ui.close_menu();
+            }
+        });
+        if deleted {
+            response.mark_changed();
+        }
+    }
+
+    // Preview an edit being made to another member of the group, applied by the host once
+    // released.
+    let locked = timeline.is_locked(tick_range.clone());
+    let group_target = group
+        .and_then(|group| group_edit(ui, group))
+        .filter(|edit| edit.source != id && !locked)
+        .map(|edit| edit.kind.apply(tick_range.clone(), min_len))
+        .filter(|range| *range != tick_range && !timeline.is_locked(range.clone()));
+
+    // Handle dragging of the warp markers between their neighbours and the clip's edges.
+    let marker_count = api.warp_markers().len();
+    let mut marker_responses = Vec::with_capacity(marker_count);
+    for ix in 0..marker_count {
+        let marker = api.warp_markers()[ix];
+        let x = timeline.tick_to_x(marker.tick);
+        let half_w = WARP_MARKER_WIDTH * 0.5;
+        let marker_rect = egui::Rect::from_x_y_ranges(x - half_w..=x + half_w, rect.y_range());
+        let marker_rect = touch::inflate_x(ui, marker_rect);
+        let marker_id = id.with(("warp", ix));
+        let sense = arbiter::sense(
+            ui,
+            Layer::Handle,
+            marker_rect,
+            marker_id,
+            egui::Sense::drag(),
+        );
+        let handle = ui.interact(marker_rect, marker_id, sense);
+        if handle.dragged() && !timeline.is_locked(tick_range.clone()) {
+            if let Some(pt) = handle.interact_pointer_pos() {
+                let markers = api.warp_markers();
+                let min = ix
+                    .checked_sub(1)
+                    .map_or(tick_range.start, |i| markers[i].tick);
+                let max = markers.get(ix + 1).map_or(tick_range.end, |m| m.tick);
+                let tick = timeline.x_to_tick(pt.x).clamp(min, max.max(min));
+                if tick != marker.tick {
+                    api.set_warp_marker(ix, tick);
+                    response.mark_changed();
+                }
+            }
+        }
+        let after = api.warp_markers().get(ix).map_or(marker.tick, |m| m.tick);
+        if let Some(gesture) = gesture::track(ui, &handle, marker.tick, after) {
+            if let Gesture::Cancelled { initial } = gesture {
+                api.set_warp_marker(ix, initial);
+            }
+            api.warp_marker_gesture(ix, gesture);
+        }
+        cursor::set(&handle, Target::Edge);
+        marker_responses.push(handle);
+    }
+
+    // Draw the clip body.
+    let visuals = ui.style().interact(&response);
+    let rounding = visuals.rounding;
+    let active = response.hovered() || response.dragged();
+    let recording_color = ui.visuals().error_fg_color;
+    let shade = |color: Option<egui::Color32>| {
+        let mut fill = match color {
+            Some(color) if active => color.linear_multiply(0.75),
+            Some(color) => color.linear_multiply(0.5),
+            None => visuals.bg_fill,
+        };
+        if api.is_recording() {
+            fill = lerp_color(fill, recording_color, 0.5);
+        }
+        if api.is_muted() {
+            fill = fill.linear_multiply(0.35);
+        }
+        fill
+    };
+    let fill = shade(api.color().or(timeline.track_color));
+    let mut stroke = visuals.bg_stroke;
+    if api.is_recording() {
+        stroke = egui::Stroke::new(stroke.width.max(1.0), recording_color);
+    }
+    if selected {
+        stroke = egui::Stroke::new(2.0, ui.visuals().selection.stroke.color);
+    } else if active {
+        stroke = egui::Stroke::new(stroke.width.max(1.0), visuals.fg_stroke.color);
+    }
+    ui.painter().rect_filled(rect, rounding, fill);
+    if let Some(bottom) = api.gradient_color().map(|color| shade(Some(color))) {
+        // Inset by the rounding so that the gradient doesn't cover the rounded corners.
+        let inset = rounding.sw.max(rounding.se).min(rect.height() * 0.5);
+        let gradient_rect = rect.shrink2(egui::vec2(0.0, inset));
+        let mut mesh = egui::Mesh::default();
+        mesh.colored_vertex(gradient_rect.left_top(), fill);
+        mesh.colored_vertex(gradient_rect.right_top(), fill);
+        mesh.colored_vertex(gradient_rect.left_bottom(), bottom);
+        mesh.colored_vertex(gradient_rect.right_bottom(), bottom);
+        mesh.add_triangle(0, 1, 2);
+        mesh.add_triangle(2, 1, 3);
+        let mut bottom_rect = rect;
+        bottom_rect.min.y = gradient_rect.bottom();
+        let bottom_rounding = egui::Rounding {
+            nw: 0.0,
+            ne: 0.0,
+            ..rounding
+        };
+        ui.painter().add(mesh);
+        ui.painter()
+            .rect_filled(bottom_rect, bottom_rounding, bottom);
+    }
+    if api.is_muted() {
+        let painter = ui.painter().with_clip_rect(ui.clip_rect().intersect(rect));
+        let hatch = egui::Stroke::new(1.0, visuals.fg_stroke.color.linear_multiply(0.15));
+        paint_hatching(&painter, rect, hatch);
+    }
+
+    // Draw the waveform scaled by the current gain, simplified according to the detail level.
+    let gain = api.gain().unwrap_or(1.0);
+    let painter = ui.painter().with_clip_rect(ui.clip_rect().intersect(rect));
+    if let Some(peaks) = api.waveform() {
+        let color = visuals.fg_stroke.color.linear_multiply(0.5);
+        match timeline.detail {
+            DetailLevel::Full => waveform::paint(&painter, rect, peaks, gain, color),
+            DetailLevel::Outline => {
+                waveform::paint_outline(&painter, rect, peaks, gain, 4.0, color)
+            }
+            DetailLevel::Block => (),
+        }
+    }
+    api.paint_content(&painter, rect, timeline.detail);
+
+    // Draw the name, elided to fit and hidden entirely if the clip is too narrow.
+    let style = &timeline.style;
+    if let Some(name) = api
+        .name()
+        .filter(|_| rect.width() >= style.clip_label_min_width)
+    {
+        let label_rect = rect.shrink2(style.clip_label_padding);
+        let font_id = style.clip_label_font(ui.style());
+        let color = visuals.text_color();
+        let mut job = egui::text::LayoutJob::simple_singleline(name.to_string(), font_id, color);
+        job.wrap = egui::text::TextWrapping {
+            max_width: label_rect.width(),
+            max_rows: 1,
+            break_anywhere: true,
+            overflow_character: Some('…'),
+        };
+        let galley = ui.painter().layout_job(job);
+        let painter = ui.painter().with_clip_rect(ui.clip_rect().intersect(rect));
+        painter.galley(label_rect.left_top(), galley, color);
+    }
+
+    // Draw the gain line.
+    if let Some(handle) = gain_response {
+        let handle_visuals = ui.style().interact(&handle);
+        let y = gain_y(gain);
+        let a = egui::Pos2::new(rect.left(), y);
+        let b = egui::Pos2::new(rect.right(), y);
+        ui.painter().line_segment([a, b], handle_visuals.fg_stroke);
+    }
+
+    // Draw each fade as a line from the clip's bottom corner, shading the faded area.
+    let fade_stroke = egui::Stroke::new(1.0, visuals.fg_stroke.color.linear_multiply(0.6));
+    let fade_fill = visuals.fg_stroke.color.linear_multiply(0.1);
+    let fades = [(true, api.fade_in()), (false, api.fade_out())];
+    for (fade_in, fade) in fades.iter().filter_map(|&(f, fade)| Some((f, fade?))) {
+        if fade <= 0.0 {
+            continue;
+        }
+        let (corner, bottom) = match fade_in {
+            true => (rect.left_top(), rect.left_bottom()),
+            false => (rect.right_top(), rect.right_bottom()),
+        };
+        let top = egui::Pos2::new(fade_x(fade_in, fade), rect.top());
+        let points = vec![corner, top, bottom];
+        painter.add(egui::Shape::convex_polygon(
+            points,
+            fade_fill,
+            egui::Stroke::NONE,
+        ));
+        painter.line_segment([bottom, top], fade_stroke);
+    }
+
+    // Draw grips at the edges and fade handles while hovered, hinting that they may be dragged.
+    let grabbed = fade_responses.iter().any(|(_, h)| h.dragged()) || stretch_target.is_some();
+    if !locked && (grabbed || ui.rect_contains_pointer(rect)) {
+        // The edges may only be dragged while the stretch modifier is held.
+        let edge_color = match stretch_modifier_held(ui, timeline) {
+            true => ui.visuals().widgets.active.fg_stroke.color,
+            false => visuals.fg_stroke.color.linear_multiply(0.5),
+        };
+        let grip_h = (rect.height() * 0.4).min(GRIP_SIZE * 2.0);
+        let grip_w = EDGE_WIDTH * 0.5;
+        for x in [rect.left() + grip_w, rect.right() - grip_w] {
+            let center = egui::Pos2::new(x, rect.center().y);
+            let grip = egui::Rect::from_center_size(center, egui::vec2(grip_w * 0.5, grip_h));
+            painter.rect_filled(grip, 1.0, edge_color);
+        }
+        for (fade_in, handle) in &fade_responses {
+            let fade = match fade_in {
+                true => api.fade_in(),
+                false => api.fade_out(),
+            };
+            let x = fade_x(*fade_in, fade.unwrap_or(0.0));
+            let center = egui::Pos2::new(x, rect.top() + GRIP_SIZE * 0.5);
+            let grip = egui::Rect::from_center_size(center, egui::Vec2::splat(GRIP_SIZE));
+            let handle_visuals = ui.style().interact(handle);
+            painter.rect(grip, 1.0, handle_visuals.bg_fill, handle_visuals.fg_stroke);
+        }
+    }
+
+    // Draw the warp markers, each with a small flag along the top of the clip.
+    for (marker, handle) in api.warp_markers().iter().zip(&marker_responses) {
+        let x = timeline.tick_to_x(marker.tick);
+        let stroke = ui.style().interact(handle).fg_stroke;
+        painter.vline(x, rect.y_range(), stroke);
+        let size = WARP_MARKER_WIDTH * 0.5;
+        let flag = vec![
+            egui::Pos2::new(x - size, rect.top()),
+            egui::Pos2::new(x + size, rect.top()),
+            egui::Pos2::new(x, rect.top() + size),
+        ];
+        painter.add(egui::Shape::convex_polygon(
+            flag,
+            stroke.color,
+            egui::Stroke::NONE,
+        ));
+    }
+
+    // Mark grouped clips with a bar colored by group.
+    if let Some(group) = group {
+        let mut bar = rect;
+        bar.min.y = (rect.bottom() - GROUP_INDICATOR_HEIGHT).max(rect.top());
+        painter.rect_filled(bar, 0.0, group_color(group));
+    }
+
+    // Draw the outline last so that it reflects the clip's state over its contents.
+    ui.painter().rect_stroke(rect, rounding, stroke);
+
+    // Allow the host to draw custom adornments over the clip.
+    let state = PaintState {
+        hovered: response.hovered(),
+        dragged: response.dragged(),
+        selected,
+        muted: api.is_muted() || timeline.track_bypassed,
+        recording: api.is_recording(),
+        locked: timeline.is_locked(tick_range.clone()),
+        detail: timeline.detail,
+    };
+    let painter = ui.painter().with_clip_rect(ui.clip_rect().intersect(rect));
+    if let Some(fraction) = api.progress() {
+        progress::paint(ui, &painter, rect, fraction);
+    }
+    api.paint_overlay(&painter, rect, state);
+
+    // Draw the ghost previewing an edit made to another member of the group, or a ripple.
+    let ripple_target = ripple::previewed(ui)
+        .filter(|ripple| ripple.applies_to(id, timeline.track_id, tick_range.clone()))
+        .map(|ripple| ripple.shift(tick_range.clone()));
+    if let Some(target) = group_target.or(ripple_target) {
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(target.start),
+            timeline.tick_to_x(target.end),
+        );
+        let ghost = egui::Rect::from_x_y_ranges(x, y);
+        let painter = ui.painter();
+        painter.rect_filled(ghost, rounding, fill.linear_multiply(0.5));
+        let ghost_stroke = egui::Stroke::new(1.0, group.map_or(stroke.color, group_color));
+        painter.rect_stroke(ghost, rounding, ghost_stroke);
+    }
+
+    // Draw the ghost and delta readout while moving.
+    if let Some(target) = target.filter(|_| response.dragged()) {
+        let origin = ui.input(|i| i.pointer.press_origin());
+        let pointer = response.interact_pointer_pos();
+        let dy = match (origin, pointer) {
+            (Some(origin), Some(pointer)) => pointer.y - origin.y,
+            _ => 0.0,
+        };
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(target.start),
+            timeline.tick_to_x(target.end),
+        );
+        let ghost = egui::Rect::from_x_y_ranges(x, y).translate(egui::vec2(0.0, dy));
+        let painter = ui.painter();
+        painter.rect_filled(ghost, rounding, fill.linear_multiply(0.5));
+        let overlaps = api.neighbours().is_some_and(|neighbours| {
+            neighbours
+                .query(target.clone())
+                .any(|(r, &other)| other != id && r.start < target.end && r.end > target.start)
+        });
+        let ghost_color = match overlaps {
+            true => ui.visuals().error_fg_color,
+            false => visuals.fg_stroke.color.linear_multiply(0.5),
+        };
+        let ghost_stroke = egui::Stroke::new(1.0, ghost_color);
+        painter.rect_stroke(ghost, rounding, ghost_stroke);
+
+        let delta = target.start - tick_range.start;
+        let mut text = match api.delta_formatter() {
+            Some(formatter) => formatter.format_delta(delta),
+            None => format!("{:+.0}", delta),
+        };
+        if let Some(label) = pointer.and_then(|p| api.drop_target(p)) {
+            text = format!("{}  → {}", text, label);
+        }
+        paint_readout(ui, ghost.left_top(), text);
+    }
+
+    // Draw the ghost and the ratio and tempo readout while stretching.
+    if let Some(target) = stretch_target {
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(target.start),
+            timeline.tick_to_x(target.end),
+        );
+        let ghost = egui::Rect::from_x_y_ranges(x, y);
+        let painter = ui.painter();
+        painter.rect_filled(ghost, rounding, fill.linear_multiply(0.5));
+        let ghost_stroke = egui::Stroke::new(1.0, visuals.fg_stroke.color.linear_multiply(0.5));
+        painter.rect_stroke(ghost, rounding, ghost_stroke);
+        let ratio = (target.end - target.start) / (tick_range.end - tick_range.start);
+        let mut text = format!("×{:.3}", ratio);
+        if let Some(tempo) = api.tempo() {
+            text = format!("{}  {:.2} BPM", text, tempo / ratio);
+        }
+        paint_readout(ui, ghost.left_top(), text);
+    }
+
+    response
+}
+
+/// Whether or not the stretch modifiers set via `Timeline::stretch_modifiers` are held, in which
+/// case dragging a clip's edge stretches the clip.
+pub fn stretch_modifier_held(ui: &egui::Ui, timeline: &TimelineCtx) -> bool {
+    ui.input(|i| i.modifiers.contains(timeline.stretch_modifiers))
+}
+
+impl EditKind {
+    /// The given member's range following the edit, never starting before `0.0` nor stretched to
+    /// less than `min_len` ticks, e.g. twice the `EDGE_WIDTH` in ticks as while previewing.
+    pub fn apply(self, range: Range<f32>, min_len: f32) -> Range<f32> {
+        match self {
+            EditKind::Move(delta) => {
+                let delta = delta.max(-range.start);
+                range.start + delta..range.end + delta
+            }
+            EditKind::StretchStart(delta) => {
+                let start = (range.start + delta).min(range.end - min_len).max(0.0);
+                start..range.end
+            }
+            EditKind::StretchEnd(delta) => {
+                let end = (range.end + delta).max(range.start + min_len);
+                range.start..end
+            }
+        }
+    }
+}
+
+/// The ID under which the edit shared by the members of the given group is stored, scoped to the
+/// timeline containing the `ui`.
+fn group_state_id(ui: &egui::Ui, group: egui::Id) -> egui::Id {
+    arbiter::scoped(ui, egui::Id::new("egui_timeline::clip_group").with(group))
+}
+
+/// The edit stretching a clip from its initial range to `range` by moving one edge.
+fn stretch_edit(is_start: bool, initial: Range<f32>, range: Range<f32>) -> EditKind {
+    match is_start {
+        true => EditKind::StretchStart(range.start - initial.start),
+        false => EditKind::StretchEnd(range.end - initial.end),
+    }
+}
+
+/// Share the edit being made to the `source` clip with the other members of its group.
+fn publish_group_edit(ui: &egui::Ui, group: egui::Id, source: egui::Id, kind: EditKind) {
+    let pass = ui.ctx().cumulative_pass_nr();
+    let edit = GroupEdit { source, pass, kind };
+    ui.data_mut(|d| d.insert_temp(group_state_id(ui, group), edit));
+}
+
+/// Withdraw the edit shared by the `source` clip, e.g. once released, cancelled or blocked by a
+/// lock.
+fn withdraw_group_edit(ui: &egui::Ui, group: egui::Id, source: egui::Id) {
+    let id = group_state_id(ui, group);
+    ui.data_mut(|d| {
+        if d.get_temp::<GroupEdit>(id)
+            .is_some_and(|e| e.source == source)
+        {
+            d.remove::<GroupEdit>(id);
+        }
+    });
+}
+
+/// The edit shared within the given group during this or the previous pass, if any.
+fn group_edit(ui: &egui::Ui, group: egui::Id) -> Option<GroupEdit> {
+    let pass = ui.ctx().cumulative_pass_nr();
+    ui.data(|d| d.get_temp::<GroupEdit>(group_state_id(ui, group)))
+        .filter(|edit| edit.pass + 1 >= pass)
+}
+
+/// The color of the indicator marking the members of the given group.
+fn group_color(group: egui::Id) -> egui::Color32 {
+    let hue = (group.value() % 360) as f32 / 360.0;
+    egui::ecolor::Hsva::new(hue, 0.6, 0.9, 1.0).into()
+}
+
+/// Draw a small readout above the given top-left corner of a ghost.
+fn paint_readout(ui: &egui::Ui, corner: egui::Pos2, text: String) {
+    let painter = ui.painter();
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let color = ui.visuals().strong_text_color();
+    let galley = painter.layout_no_wrap(text, font_id, color);
+    let pad = egui::vec2(3.0, 1.0);
+    let pos = corner - egui::vec2(0.0, galley.size().y + pad.y * 2.0);
+    let bg = egui::Rect::from_min_size(pos, galley.size() + pad * 2.0);
+    painter.rect_filled(bg, 2.0, ui.visuals().extreme_bg_color);
+    painter.galley(pos + pad, galley, color);
+}
+
+/// The smallest offset that would align an edge of `range` with an edge of one of the other clips
+/// within `snap_ticks`, or `0.0` if there are none.
+fn snap_delta(
+    neighbours: &IntervalIndex<egui::Id>,
+    id: egui::Id,
+    range: Range<f32>,
+    snap_ticks: f32,
+) -> f32 {
+    let search = range.start - snap_ticks..range.end + snap_ticks;
+    neighbours
+        .query(search)
+        .filter(|&(_, &other)| other != id)
+        .flat_map(|(r, _)| {
+            let (a, b) = (range.start, range.end);
+            [r.start - a, r.end - a, r.start - b, r.end - b]
+        })
+        .filter(|d| d.abs() <= snap_ticks)
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or(0.0)
+}
+
+/// Linearly interpolate between two colors in gamma space.
+fn lerp_color(a: egui::Color32, b: egui::Color32, t: f32) -> egui::Color32 {
+    let lerp = |a: u8, b: u8| egui::lerp(a as f32..=b as f32, t).round() as u8;
+    egui::Color32::from_rgba_premultiplied(
+        lerp(a.r(), b.r()),
+        lerp(a.g(), b.g()),
+        lerp(a.b(), b.b()),
+        lerp(a.a(), b.a()),
+    )
+}
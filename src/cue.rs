@@ -0,0 +1,168 @@
+use super::{
+    arbiter::{self, Layer},
+    cursor::{self, Target},
+    hover, touch, TimelineCtx,
+};
+use std::{hash::Hash, ops::Range};
+
+/// The width of the in and out handles at either end of a cue.
+pub const HANDLE_WIDTH: f32 = 4.0;
+
+/// Access to the cues (e.g. subtitles or captions) displayed within a lane.
+pub trait Cues {
+    /// The total number of cues.
+    fn cue_count(&self) -> usize;
+    /// The in and out points of the cue at the given index in ticks relative to the start of the
+    /// timeline.
+    fn cue_range(&self, index: usize) -> Range<f32>;
+    /// The text previewed within the cue at the given index.
+    fn cue_text(&self, index: usize) -> &str;
+    /// The cue at the given index was retimed by dragging its body or either of its handles.
+    fn set_cue_range(&mut self, index: usize, range: Range<f32>);
+}
+
+/// Instantiate a cue lane of the given height.
+///
+/// Drag a cue's in or out handle to retime that edge, or drag its body to move the whole cue.
+/// Overlapping cues are outlined using the warning color, with the overlapping region highlighted.
+pub fn lane(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    api: &mut dyn Cues,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let w = ui.available_width();
+    let desired_size = egui::Vec2::new(w, height);
+    let (rect, mut response) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+    let ticks_per_point = timeline.ticks_per_point;
+    let min_len = HANDLE_WIDTH * 2.0 * ticks_per_point;
+
+    // Find all overlapping cues by sweeping over them in order of their in points.
+    let count = api.cue_count();
+    let mut order: Vec<usize> = (0..count).collect();
+    order.sort_by(|&a, &b| api.cue_range(a).start.total_cmp(&api.cue_range(b).start));
+    let mut overlapping = vec![false; count];
+    let mut overlaps = vec![];
+    let mut furthest: Option<(usize, f32)> = None;
+    for &ix in &order {
+        let range = api.cue_range(ix);
+        if let Some((prev, end)) = furthest {
+            if range.start < end {
+                overlapping[prev] = true;
+                overlapping[ix] = true;
+                overlaps.push(range.start..end.min(range.end));
+            }
+            if range.end <= end {
+                continue;
+            }
+        }
+        furthest = Some((ix, range.end));
+    }
+
+    // Interact with and draw each cue.
+    for (ix, &is_overlapping) in overlapping.iter().enumerate() {
+        let range = api.cue_range(ix);
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(range.start),
+            timeline.tick_to_x(range.end),
+        );
+        let cue_rect = egui::Rect::from_x_y_ranges(x, rect.y_range()).shrink2([0.0, 1.0].into());
+        if !ui.clip_rect().intersects(cue_rect) {
+            continue;
+        }
+        let cue_id = id.with(ix);
+        let body_sense = arbiter::sense(
+            ui,
+            Layer::Clip,
+            cue_rect,
+            cue_id,
+            egui::Sense::click_and_drag(),
+        );
+        let body = ui.interact(cue_rect, cue_id, body_sense);
+        hover::register(ui, cue_rect, Some(api.cue_text(ix)), range.clone());
+        let mut in_rect = cue_rect;
+        in_rect.set_width(HANDLE_WIDTH);
+        let mut out_rect = cue_rect;
+        out_rect.min.x = cue_rect.right() - HANDLE_WIDTH;
+        let handle = |ui: &mut egui::Ui, rect: egui::Rect, id: egui::Id| {
+            let rect = touch::inflate_x(ui, rect);
+            let sense = arbiter::sense(ui, Layer::Handle, rect, id, egui::Sense::drag());
+            ui.interact(rect, id, sense)
+        };
+        let in_handle = handle(ui, in_rect, cue_id.with("in"));
+        let out_handle = handle(ui, out_rect, cue_id.with("out"));
+
+        // Retime the cue.
+        let mut new_range = range.clone();
+        if body.dragged() {
+            let delta = (body.drag_delta().x * ticks_per_point).max(-range.start);
+            new_range.start += delta;
+            new_range.end += delta;
+        }
+        if in_handle.dragged() {
+            let delta = in_handle.drag_delta().x * ticks_per_point;
+            new_range.start = (range.start + delta).min(range.end - min_len).max(0.0);
+        }
+        if out_handle.dragged() {
+            let delta = out_handle.drag_delta().x * ticks_per_point;
+            new_range.end = (range.end + delta).max(range.start + min_len);
+        }
+        let touched = range.start.min(new_range.start)..range.end.max(new_range.end);
+        if new_range != range && !timeline.is_locked(touched) {
+            api.set_cue_range(ix, new_range);
+            response.mark_changed();
+        }
+        cursor::set(&body, Target::Move);
+        cursor::set(&in_handle, Target::Edge);
+        cursor::set(&out_handle, Target::Edge);
+
+        // Draw the cue body, outlined in the warning color if it overlaps another.
+        let visuals = ui.style().interact(&body);
+        let mut stroke = visuals.bg_stroke;
+        if is_overlapping {
+            stroke.color = ui.visuals().warn_fg_color;
+            stroke.width = stroke.width.max(1.0);
+        }
+        ui.painter()
+            .rect(cue_rect, visuals.rounding, visuals.bg_fill, stroke);
+
+        // Draw the in and out handles.
+        for (handle, handle_rect) in [(&in_handle, in_rect), (&out_handle, out_rect)] {
+            let handle_visuals = ui.style().interact(handle);
+            let fill = handle_visuals.fg_stroke.color.linear_multiply(0.5);
+            ui.painter().rect_filled(handle_rect, 0.0, fill);
+        }
+
+        // Draw a single-line preview of the text.
+        let text_rect = cue_rect.shrink2([HANDLE_WIDTH * 2.0, 0.0].into());
+        if text_rect.is_positive() {
+            let painter = ui
+                .painter()
+                .with_clip_rect(ui.clip_rect().intersect(text_rect));
+            let font_id = egui::TextStyle::Small.resolve(ui.style());
+            let text = api.cue_text(ix).lines().next().unwrap_or("");
+            painter.text(
+                text_rect.left_center(),
+                egui::Align2::LEFT_CENTER,
+                text,
+                font_id,
+                visuals.text_color(),
+            );
+        }
+    }
+
+    // Highlight the overlapping regions.
+    let overlap_color = ui.visuals().warn_fg_color.linear_multiply(0.25);
+    for overlap in overlaps {
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(overlap.start),
+            timeline.tick_to_x(overlap.end),
+        );
+        let overlap_rect = egui::Rect::from_x_y_ranges(x, rect.y_range());
+        ui.painter().rect_filled(overlap_rect, 0.0, overlap_color);
+    }
+
+    response
+}
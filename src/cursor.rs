@@ -0,0 +1,42 @@
+/// The kinds of element the pointer may be over within the timeline.
+///
+/// All widgets within the crate select their cursor icon via this type so that they agree.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// A horizontally draggable edge, e.g. clip, annotation or cue edges and loop handles.
+    Edge,
+    /// A vertically draggable handle, e.g. a clip's gain line.
+    VerticalHandle,
+    /// A body that may be dragged to move it.
+    Move,
+    /// An editable label.
+    Text,
+    /// An empty area in which new content may be drawn.
+    Draw,
+    /// An area that is locked against edits.
+    Locked,
+}
+
+impl Target {
+    /// The cursor icon for the target, depending on whether or not it is being dragged.
+    pub fn icon(self, dragged: bool) -> egui::CursorIcon {
+        match self {
+            Target::Edge => egui::CursorIcon::ResizeHorizontal,
+            Target::VerticalHandle => egui::CursorIcon::ResizeVertical,
+            Target::Move if dragged => egui::CursorIcon::Grabbing,
+            Target::Move => egui::CursorIcon::Grab,
+            Target::Text => egui::CursorIcon::Text,
+            Target::Draw => egui::CursorIcon::Crosshair,
+            Target::Locked => egui::CursorIcon::NotAllowed,
+        }
+    }
+}
+
+/// Set the cursor icon for the given target if the response is hovered or dragged.
+pub fn set(response: &egui::Response, target: Target) {
+    if response.hovered() || response.dragged() {
+        response
+            .ctx
+            .set_cursor_icon(target.icon(response.dragged()));
+    }
+}
@@ -0,0 +1,382 @@
+use super::{
+    arbiter::{self, Layer},
+    cursor::{self, Target},
+    gesture::{self, Gesture},
+    zoom, TimelineCtx,
+};
+use std::{hash::Hash, ops::RangeInclusive};
+
+/// The radius of the point and tangent handles.
+pub const HANDLE_RADIUS: f32 = 4.0;
+/// The number of value axis divisions labelled along the left edge of the lane.
+pub const VALUE_AXIS_DIVISIONS: usize = 4;
+/// The height of the strip summarising a curve beneath a track, see `footer`.
+pub const FOOTER_HEIGHT: f32 = 12.0;
+
+/// A keyframe on a curve with bezier tangents.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CurvePoint {
+    /// The position of the point in ticks relative to the start of the timeline.
+    pub tick: f32,
+    /// The value of the curve at the point.
+    pub value: f32,
+    /// The incoming tangent handle as a `[ticks, value]` offset from the point.
+    pub in_tangent: [f32; 2],
+    /// The outgoing tangent handle as a `[ticks, value]` offset from the point.
+    pub out_tangent: [f32; 2],
+}
+
+/// Access to the points of a curve displayed within a curve editor lane.
+///
+/// Points are expected to be ordered by their tick.
+pub trait Curve {
+    /// The range of values displayed from the bottom to the top of the lane.
+    fn value_range(&self) -> RangeInclusive<f32>;
+    /// The total number of points.
+    fn point_count(&self) -> usize;
+    /// The point at the given index.
+    fn point(&self, index: usize) -> CurvePoint;
+    /// The point at the given index or one of its tangents was dragged.
+    fn set_point(&mut self, index: usize, point: CurvePoint);
+    /// The point at the given index or one of its tangents is being dragged, with the initial and
+    /// current point.
+    fn point_gesture(&mut self, _index: usize, _gesture: Gesture<CurvePoint>) {}
+}
+
+/// State persisted between frames.
+#[derive(Copy, Clone, Default)]
+struct State {
+    /// The point whose tangent handles are displayed.
+    active: Option<usize>,
+}
+
+/// Instantiate a curve editor lane of the given height.
+///
+/// A value axis is labelled along the left edge of the visible area. Drag a point to move it in
+/// time, between its neighbours, and value. Clicking or dragging a point reveals its tangent
+/// handles which may be dragged to shape the bezier curve. Tangents are mirrored unless `Alt` is
+/// held.
+///
+/// Scroll with `zoom::Modifiers::value` held to zoom the value axis, see `zoom::value_viewport`.
+pub fn lane(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    api: &mut dyn Curve,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let w = ui.available_width();
+    let desired_size = egui::Vec2::new(w, height);
+    let (rect, _) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+    let mut response = ui.interact(rect, id, egui::Sense::click());
+    let mut state: State = ui.data(|d| d.get_temp(id)).unwrap_or_default();
+    let alt = ui.input(|i| i.modifiers.alt);
+    if response.clicked() {
+        state.active = None;
+    }
+
+    // Transforms between ticks/values and screen space.
+    let full_range = api.value_range();
+    let value_range = zoom::value_viewport(ui, id, rect, full_range.clone(), timeline);
+    let prev_clip_rect = ui.clip_rect();
+    if value_range != full_range {
+        ui.set_clip_rect(prev_clip_rect.intersect(rect));
+    }
+    let y_range = rect.y_range().flip();
+    let to_y = |value: f32| egui::remap(value, value_range.clone(), y_range);
+    let to_value = |y: f32| egui::remap_clamp(y, y_range, value_range.clone());
+    let to_pos = |tick: f32, value: f32| egui::Pos2::new(timeline.tick_to_x(tick), to_y(value));
+    // Guard against a zero-height rect or an empty range of values.
+    let value_span = (value_range.end() - value_range.start()).max(f32::EPSILON);
+    let value_per_point = value_span / rect.height().max(f32::EPSILON);
+    let offset_to_vec = |[ticks, value]: [f32; 2]| {
+        egui::Vec2::new(ticks / timeline.ticks_per_point, -value / value_per_point)
+    };
+    let vec_to_offset = |v: egui::Vec2| [v.x * timeline.ticks_per_point, -v.y * value_per_point];
+    let visible_x = ui.clip_rect().x_range();
+
+    // Draw the value axis grid lines.
+    let visuals = ui.style().noninteractive();
+    let grid_stroke = egui::Stroke {
+        color: visuals.bg_stroke.color.linear_multiply(0.5),
+        ..visuals.bg_stroke
+    };
+    let label_x = ui.clip_rect().left().max(rect.left()) + 2.0;
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let text_color = visuals.text_color();
+    for i in 0..=VALUE_AXIS_DIVISIONS {
+        let t = i as f32 / VALUE_AXIS_DIVISIONS as f32;
+        let value = egui::lerp(value_range.clone(), t);
+        let y = to_y(value);
+        ui.painter().hline(rect.x_range(), y, grid_stroke);
+        let anchor = match i {
+            0 => egui::Align2::LEFT_BOTTOM,
+            n if n == VALUE_AXIS_DIVISIONS => egui::Align2::LEFT_TOP,
+            _ => egui::Align2::LEFT_CENTER,
+        };
+        let pos = egui::Pos2::new(label_x, y);
+        let text = format!("{:.2}", value);
+        ui.painter()
+            .text(pos, anchor, text, font_id.clone(), text_color);
+    }
+
+    // Draw the curve.
+    let curve_stroke = ui.visuals().widgets.active.fg_stroke;
+    let painter = ui.painter();
+    paint_curve(
+        painter,
+        rect,
+        timeline,
+        value_range.clone(),
+        curve_stroke,
+        api,
+    );
+
+    // Interact with and draw the point handles.
+    let handle_size = egui::Vec2::splat(HANDLE_RADIUS * 2.0);
+    for ix in 0..api.point_count() {
+        let mut point = api.point(ix);
+        let locked = timeline.is_locked(point.tick..point.tick);
+        let pos = to_pos(point.tick, point.value);
+        if !visible_x.contains(pos.x) && state.active != Some(ix) {
+            continue;
+        }
+        let point_id = id.with(ix);
+
+        // Tangent handles for the active point.
+        if state.active == Some(ix) {
+            let tangents = [
+                (point.in_tangent, point_id.with("in")),
+                (point.out_tangent, point_id.with("out")),
+            ];
+            for (i, &(tangent, tangent_id)) in tangents.iter().enumerate() {
+                let tangent_pos = pos + offset_to_vec(tangent);
+                let tangent_rect = egui::Rect::from_center_size(tangent_pos, handle_size);
+                let sense = egui::Sense::drag();
+                let sense = arbiter::sense(ui, Layer::Handle, tangent_rect, tangent_id, sense);
+                let tangent_response = ui.interact(tangent_rect, tangent_id, sense);
+                let before = point;
+                if tangent_response.dragged() && !locked {
+                    if let Some(pt) = tangent_response.interact_pointer_pos() {
+                        let offset = vec_to_offset(pt - pos);
+                        let mirrored = [-offset[0], -offset[1]];
+                        if i == 0 {
+                            point.in_tangent = offset;
+                            if !alt {
+                                point.out_tangent = mirrored;
+                            }
+                        } else {
+                            point.out_tangent = offset;
+                            if !alt {
+                                point.in_tangent = mirrored;
+                            }
+                        }
+                        api.set_point(ix, point);
+                        response.mark_changed();
+                    }
+                }
+                if let Some(gesture) = gesture::track(ui, &tangent_response, before, point) {
+                    if let Gesture::Cancelled { initial } = gesture {
+                        api.set_point(ix, initial);
+                    }
+                    api.point_gesture(ix, gesture);
+                }
+                let tangent_visuals = ui.style().interact(&tangent_response);
+                let stroke = tangent_visuals.fg_stroke;
+                ui.painter().line_segment([pos, tangent_pos], stroke);
+                ui.painter()
+                    .circle_stroke(tangent_pos, HANDLE_RADIUS * 0.75, stroke);
+            }
+        }
+
+        // The point handle itself.
+        let point_rect = egui::Rect::from_center_size(pos, handle_size);
+        let sense = egui::Sense::click_and_drag();
+        let sense = arbiter::sense(ui, Layer::Handle, point_rect, point_id, sense);
+        let point_response = ui.interact(point_rect, point_id, sense);
+        cursor::set(&point_response, Target::Move);
+        if point_response.clicked() || point_response.drag_started() {
+            state.active = Some(ix);
+        }
+        let before = point;
+        // Move the point by the drag from the press origin, keeping it between its neighbours.
+        let drag_id = point_id.with("drag");
+        if point_response.drag_started() {
+            ui.data_mut(|d| d.insert_temp(drag_id, point));
+        }
+        let initial: Option<CurvePoint> = ui.data(|d| d.get_temp(drag_id));
+        let press_origin = ui.input(|i| i.pointer.press_origin());
+        if let (Some(initial), Some(a), Some(b), true) = (
+            initial,
+            press_origin,
+            point_response.interact_pointer_pos(),
+            point_response.dragged() && !locked,
+        ) {
+            let delta = b - a;
+            let min = ix.checked_sub(1).map_or(0.0, |i| api.point(i).tick);
+            let max = match ix + 1 < api.point_count() {
+                true => api.point(ix + 1).tick,
+                false => f32::INFINITY,
+            };
+            let tick = (initial.tick + delta.x * timeline.ticks_per_point).clamp(min, max.max(min));
+            let value = to_value(to_y(initial.value) + delta.y);
+            if !timeline.is_locked(point.tick.min(tick)..point.tick.max(tick)) {
+                point.tick = tick;
+                point.value = value;
+                api.set_point(ix, point);
+                response.mark_changed();
+            }
+        }
+        if point_response.drag_stopped() {
+            ui.data_mut(|d| d.remove::<CurvePoint>(drag_id));
+        }
+        if let Some(gesture) = gesture::track(ui, &point_response, before, point) {
+            if let Gesture::Cancelled { initial } = gesture {
+                api.set_point(ix, initial);
+            }
+            api.point_gesture(ix, gesture);
+        }
+        let point_visuals = ui.style().interact(&point_response);
+        let fill = if state.active == Some(ix) {
+            ui.visuals().selection.bg_fill
+        } else {
+            point_visuals.fg_stroke.color
+        };
+        let pos = to_pos(point.tick, point.value);
+        ui.painter()
+            .circle(pos, HANDLE_RADIUS, fill, point_visuals.bg_stroke);
+    }
+
+    ui.set_clip_rect(prev_clip_rect);
+    ui.data_mut(|d| d.insert_temp(id, state));
+    response
+}
+
+/// The range of values displayed by the lane with the given `id_source` within the `ui`, or
+/// `None` if the lane hasn't been shown.
+pub fn value_viewport(ui: &egui::Ui, id_source: impl Hash) -> Option<RangeInclusive<f32>> {
+    let id = ui.make_persistent_id(id_source).with("value_viewport");
+    ui.data(|d| d.get_temp(id))
+}
+
+/// Instantiate a slim strip summarising the curve as a sparkline over its full value range,
+/// typically beneath a track's content.
+///
+/// Clicking the strip expands the full curve editor `lane` of the given height above it, and
+/// clicking again collapses it. Whether or not the lane is expanded may be read via
+/// `footer_expanded`.
+pub fn footer(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    api: &mut dyn Curve,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let expanded_id = id.with("expanded");
+    let expanded: bool = ui.data(|d| d.get_temp(expanded_id)).unwrap_or(false);
+    let lane_response = expanded.then(|| lane(ui, id.with("lane"), timeline, height, api));
+
+    // The strip itself, toggling the lane when clicked.
+    let w = ui.available_width();
+    let desired_size = egui::Vec2::new(w, FOOTER_HEIGHT * timeline.track_scale);
+    let (rect, _) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+    let mut response = ui.interact(rect, id, egui::Sense::click());
+    if response.clicked() {
+        ui.data_mut(|d| d.insert_temp(expanded_id, !expanded));
+        response.mark_changed();
+    }
+    let visuals = ui.style().interact(&response);
+    ui.painter()
+        .rect_filled(rect, 0.0, ui.visuals().faint_bg_color);
+    ui.painter().hline(
+        rect.x_range(),
+        rect.top(),
+        ui.visuals().widgets.noninteractive.bg_stroke,
+    );
+    if !expanded {
+        let stroke = egui::Stroke::new(1.0, visuals.fg_stroke.color.linear_multiply(0.75));
+        let value_range = api.value_range();
+        let curve_rect = rect.shrink2(egui::vec2(0.0, 2.0));
+        let painter = ui.painter().with_clip_rect(ui.clip_rect().intersect(rect));
+        paint_curve(&painter, curve_rect, timeline, value_range, stroke, api);
+    }
+    let icon_x = ui.clip_rect().left().max(rect.left()) + 2.0;
+    let icon = match expanded {
+        true => "⏷",
+        false => "⏵",
+    };
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let pos = egui::Pos2::new(icon_x, rect.center().y);
+    ui.painter().text(
+        pos,
+        egui::Align2::LEFT_CENTER,
+        icon,
+        font_id,
+        visuals.text_color(),
+    );
+    let hint = match expanded {
+        true => "Click to collapse",
+        false => "Click to expand",
+    };
+    let response = response.on_hover_text(hint);
+    match lane_response {
+        Some(lane) => response.union(lane),
+        None => response,
+    }
+}
+
+/// Whether or not the lane of the footer with the given `id_source` within the `ui` is expanded.
+pub fn footer_expanded(ui: &egui::Ui, id_source: impl Hash) -> bool {
+    let id = ui.make_persistent_id(id_source).with("expanded");
+    ui.data(|d| d.get_temp(id)).unwrap_or(false)
+}
+
+/// Draw the curve within `rect` over the given range of values, extending flat beyond the first
+/// and last points.
+fn paint_curve(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    timeline: &TimelineCtx,
+    value_range: RangeInclusive<f32>,
+    stroke: egui::Stroke,
+    api: &dyn Curve,
+) {
+    // Nothing can be drawn within an empty rect or over an empty range of values.
+    if rect.height() <= 0.0 || value_range.end() <= value_range.start() {
+        return;
+    }
+    let y_range = rect.y_range().flip();
+    let to_y = |value: f32| egui::remap(value, value_range.clone(), y_range);
+    let to_pos = |tick: f32, value: f32| egui::Pos2::new(timeline.tick_to_x(tick), to_y(value));
+    let value_per_point = (value_range.end() - value_range.start()) / rect.height();
+    let offset_to_vec = |[ticks, value]: [f32; 2]| {
+        egui::Vec2::new(ticks / timeline.ticks_per_point, -value / value_per_point)
+    };
+    let count = api.point_count();
+    if count > 0 {
+        let first = api.point(0);
+        let last = api.point(count - 1);
+        let first_pos = to_pos(first.tick, first.value);
+        let last_pos = to_pos(last.tick, last.value);
+        painter.hline(rect.left()..=first_pos.x, first_pos.y, stroke);
+        painter.hline(last_pos.x..=rect.right(), last_pos.y, stroke);
+    }
+    let visible_x = painter.clip_rect().x_range();
+    for ix in 0..count.saturating_sub(1) {
+        let (a, b) = (api.point(ix), api.point(ix + 1));
+        let p0 = to_pos(a.tick, a.value);
+        let p3 = to_pos(b.tick, b.value);
+        if p3.x < visible_x.min || p0.x > visible_x.max {
+            continue;
+        }
+        let p1 = p0 + offset_to_vec(a.out_tangent);
+        let p2 = p3 + offset_to_vec(b.in_tangent);
+        let points = [p0, p1, p2, p3];
+        let fill = egui::Color32::TRANSPARENT;
+        let bezier =
+            egui::epaint::CubicBezierShape::from_points_stroke(points, false, fill, stroke);
+        painter.add(bezier);
+    }
+}
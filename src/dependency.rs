@@ -0,0 +1,170 @@
+use super::TimelineCtx;
+use std::collections::{HashMap, HashSet};
+
+/// The horizontal gap left between a span and the vertical segments of an arrow.
+pub const ARROW_GAP: f32 = 6.0;
+/// The size of the arrow heads.
+pub const ARROW_HEAD_SIZE: f32 = 5.0;
+
+/// The rects of the spans laid out during the current frame, in the order they were inserted.
+///
+/// Hosts insert the rect of each span as they lay out their tracks, before drawing the
+/// dependency arrows between them with `arrows`.
+#[derive(Clone, Debug, Default)]
+pub struct Spans {
+    rects: Vec<(egui::Id, egui::Rect)>,
+    /// The index of each span within `rects`.
+    index: HashMap<egui::Id, usize>,
+}
+
+impl Spans {
+    /// Record the rect of the span with the given ID, replacing any previously recorded rect.
+    pub fn insert(&mut self, id: egui::Id, rect: egui::Rect) {
+        match self.index.get(&id) {
+            Some(&ix) => self.rects[ix].1 = rect,
+            None => {
+                self.index.insert(id, self.rects.len());
+                self.rects.push((id, rect));
+            }
+        }
+    }
+
+    /// The rect of the span with the given ID, if it was laid out this frame.
+    pub fn get(&self, id: egui::Id) -> Option<egui::Rect> {
+        self.index.get(&id).map(|&ix| self.rects[ix].1)
+    }
+
+    /// The ID of the span under the given position, if any.
+    ///
+    /// Where spans overlap, the last inserted (i.e. topmost) span wins.
+    pub fn at(&self, pos: egui::Pos2) -> Option<egui::Id> {
+        self.rects
+            .iter()
+            .rev()
+            .find(|(_, rect)| rect.contains(pos))
+            .map(|&(id, _)| id)
+    }
+}
+
+/// Draw an arrow for each `(from, to)` dependency pair between spans.
+///
+/// Arrows leave the right edge of the `from` span and enter the left edge of the `to` span,
+/// routing around the rows between them when the `to` span starts before the `from` span ends.
+/// Arrows are clipped to the visible timeline area. When a span is hovered, its entire dependency
+/// chain (both upstream and downstream) is highlighted.
+///
+/// This is designed to be called from within the `Show::tracks` closure after laying out all
+/// tracks. Returns the ID of the hovered span, if any.
+pub fn arrows(
+    ui: &mut egui::Ui,
+    timeline: &TimelineCtx,
+    spans: &Spans,
+    dependencies: &[(egui::Id, egui::Id)],
+) -> Option<egui::Id> {
+    let clip_rect = ui.clip_rect().intersect(timeline.full_rect);
+    let painter = ui.painter().with_clip_rect(clip_rect);
+    let hovered = ui
+        .input(|i| i.pointer.hover_pos())
+        .filter(|&pos| clip_rect.contains(pos))
+        .and_then(|pos| spans.at(pos));
+
+    // Collect the dependency chain upstream and downstream of the hovered span.
+    let chain = |upstream: bool| {
+        let mut set = HashSet::new();
+        let mut stack: Vec<_> = hovered.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            if !set.insert(id) {
+                continue;
+            }
+            for &(from, to) in dependencies {
+                match upstream {
+                    true if to == id => stack.push(from),
+                    false if from == id => stack.push(to),
+                    _ => (),
+                }
+            }
+        }
+        set
+    };
+    let upstream = chain(true);
+    let downstream = chain(false);
+
+    let visuals = ui.style().noninteractive();
+    let stroke = egui::Stroke::new(1.0, visuals.fg_stroke.color.linear_multiply(0.5));
+    let highlight = egui::Stroke::new(2.0, ui.visuals().selection.stroke.color);
+    let mut highlighted = vec![];
+    for &(from, to) in dependencies {
+        let (a, b) = match (spans.get(from), spans.get(to)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => continue,
+        };
+        let points = route(a, b);
+        let is_highlighted = (upstream.contains(&from) && upstream.contains(&to))
+            || (downstream.contains(&from) && downstream.contains(&to));
+        if is_highlighted {
+            highlighted.push(points);
+        } else {
+            paint_arrow(&painter, points, stroke);
+        }
+    }
+
+    // Draw the highlighted chain on top.
+    for points in highlighted {
+        paint_arrow(&painter, points, highlight);
+    }
+    for id in upstream.iter().chain(&downstream) {
+        if let Some(rect) = spans.get(*id) {
+            painter.rect_stroke(rect, 0.0, highlight);
+        }
+    }
+
+    hovered
+}
+
+/// Route an arrow from the right edge of `a` to the left edge of `b`.
+fn route(a: egui::Rect, b: egui::Rect) -> Vec<egui::Pos2> {
+    let start = a.right_center();
+    let end = b.left_center();
+    let out_x = start.x + ARROW_GAP;
+    let in_x = end.x - ARROW_GAP;
+    if in_x >= out_x {
+        vec![
+            start,
+            egui::Pos2::new(out_x, start.y),
+            egui::Pos2::new(out_x, end.y),
+            end,
+        ]
+    } else {
+        // Route through the gap just outside the target row.
+        let row_y = if end.y >= start.y {
+            b.top() - ARROW_GAP * 0.5
+        } else {
+            b.bottom() + ARROW_GAP * 0.5
+        };
+        vec![
+            start,
+            egui::Pos2::new(out_x, start.y),
+            egui::Pos2::new(out_x, row_y),
+            egui::Pos2::new(in_x, row_y),
+            egui::Pos2::new(in_x, end.y),
+            end,
+        ]
+    }
+}
+
+/// Paint the arrow path along with an arrow head at the final point.
+fn paint_arrow(painter: &egui::Painter, points: Vec<egui::Pos2>, stroke: egui::Stroke) {
+    let end = points[points.len() - 1];
+    painter.add(egui::Shape::line(points, stroke));
+    let s = ARROW_HEAD_SIZE;
+    let head = vec![
+        end,
+        end + egui::vec2(-s, -s * 0.5),
+        end + egui::vec2(-s, s * 0.5),
+    ];
+    painter.add(egui::Shape::convex_polygon(
+        head,
+        stroke.color,
+        egui::Stroke::NONE,
+    ));
+}
@@ -0,0 +1,47 @@
+/// How much detail content should be drawn with at the current zoom level.
+///
+/// Derived from the timeline's ticks per point via `Thresholds`, so that every track and clip
+/// switches representation at the same zoom level.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DetailLevel {
+    /// Zoomed far out. Content should be drawn as solid blocks.
+    Block,
+    /// Content should be drawn as a simplified outline, e.g. a waveform's envelope.
+    Outline,
+    /// Zoomed in. Content should be drawn in full.
+    #[default]
+    Full,
+}
+
+/// The ticks per point at which content switches to a coarser `DetailLevel`.
+///
+/// Set via `Timeline::detail_thresholds`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Thresholds {
+    /// Content is drawn as an outline at or above this many ticks per point.
+    pub outline: f32,
+    /// Content is drawn as solid blocks at or above this many ticks per point.
+    pub block: f32,
+}
+
+impl Thresholds {
+    /// The detail level for the given number of ticks per point.
+    pub fn level(&self, ticks_per_point: f32) -> DetailLevel {
+        if ticks_per_point >= self.block {
+            DetailLevel::Block
+        } else if ticks_per_point >= self.outline {
+            DetailLevel::Outline
+        } else {
+            DetailLevel::Full
+        }
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            outline: 64.0,
+            block: 512.0,
+        }
+    }
+}
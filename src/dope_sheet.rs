@@ -0,0 +1,111 @@
+use super::{keyframe, TracksCtx};
+use std::hash::Hash;
+
+/// Access to the tree of objects and their animated properties displayed within a dope sheet.
+pub trait DopeSheet {
+    /// The total number of objects.
+    fn object_count(&self) -> usize;
+    /// The name of the object at the given index, displayed in its header.
+    fn object_name(&self, object: usize) -> &str;
+    /// The number of animated properties belonging to the object at the given index.
+    fn property_count(&self, object: usize) -> usize;
+    /// The name of the given property, displayed in its header.
+    fn property_name(&self, object: usize, property: usize) -> &str;
+    /// Access to the keyframes of the given property.
+    fn property_keyframes(
+        &mut self,
+        object: usize,
+        property: usize,
+    ) -> &mut dyn keyframe::Keyframes;
+}
+
+/// Instantiate a dope sheet, with one track per object followed by one track per property.
+///
+/// Each object's row summarises the keyframes of all of its properties, and can be collapsed to
+/// hide the property rows. Property rows are regular keyframe lanes of the given `row_height`.
+///
+/// This is designed to be called from within the `Show::tracks` closure. Returns whether or not any
+/// of the property keyframes were changed.
+pub fn show(
+    tracks: &TracksCtx,
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    row_height: f32,
+    api: &mut dyn DopeSheet,
+) -> bool {
+    let id = ui.make_persistent_id(id_source);
+    let mut changed = false;
+    for object in 0..api.object_count() {
+        let object_id = id.with(object);
+        let mut state = egui::collapsing_header::CollapsingState::load_with_default_open(
+            ui.ctx(),
+            object_id,
+            true,
+        );
+
+        // The object header and summary row.
+        let name = api.object_name(object).to_string();
+        tracks
+            .next(ui)
+            .header(|ui| {
+                ui.horizontal(|ui| {
+                    state.show_toggle_button(ui, egui::collapsing_header::paint_default_icon);
+                    ui.strong(name);
+                });
+            })
+            .show(|timeline, ui| {
+                let w = ui.available_width();
+                let desired_size = egui::Vec2::new(w, row_height);
+                let (rect, _) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+                let fill = ui.visuals().faint_bg_color;
+                ui.painter().rect_filled(rect, 0.0, fill);
+                let size = keyframe::DIAMOND_SIZE.min(row_height) * 0.75;
+                let visible = ui.clip_rect().intersect(rect).expand2([size, 0.0].into());
+                let color = ui.visuals().widgets.noninteractive.fg_stroke.color;
+                let stroke = egui::Stroke::NONE;
+                let mut ticks = vec![];
+                for property in 0..api.property_count(object) {
+                    let keyframes = api.property_keyframes(object, property);
+                    let count = keyframes.keyframe_count();
+                    ticks.extend((0..count).map(|ix| keyframes.keyframe_tick(ix)));
+                }
+                ticks.sort_by(|a, b| a.total_cmp(b));
+                let mut last_x = f32::MIN;
+                for tick in ticks {
+                    let x = timeline.tick_to_x(tick);
+                    // Skip keyframes that would land on the same point as the last.
+                    if x - last_x < 1.0 || !visible.x_range().contains(x) {
+                        continue;
+                    }
+                    last_x = x;
+                    let center = egui::Pos2::new(x, rect.center().y);
+                    keyframe::paint_diamond(ui.painter(), center, size, color, stroke);
+                }
+            });
+        let open = state.is_open();
+        state.store(ui.ctx());
+        if !open {
+            continue;
+        }
+
+        // A row for each of the object's properties.
+        for property in 0..api.property_count(object) {
+            let name = api.property_name(object, property).to_string();
+            let keyframes = api.property_keyframes(object, property);
+            tracks
+                .next(ui)
+                .header(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.add_space(ui.spacing().indent);
+                        ui.label(name);
+                    });
+                })
+                .show(|timeline, ui| {
+                    let lane_id = object_id.with(property);
+                    let lane = keyframe::lane(ui, lane_id, timeline, row_height, keyframes);
+                    changed |= lane.changed();
+                });
+        }
+    }
+    changed
+}
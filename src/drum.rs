@@ -0,0 +1,243 @@
+use super::{
+    arbiter::{self, Layer},
+    cursor::{self, Target},
+    TimelineCtx,
+};
+use std::{hash::Hash, ops::Range};
+
+/// The velocity of cells enabled by clicking or painting.
+pub const DEFAULT_VELOCITY: u8 = 100;
+/// The minimum width of a step in points, below which cells are neither drawn nor interactive.
+pub const MIN_STEP_WIDTH: f32 = 6.0;
+/// The vertical drag distance in points over which a cell's velocity spans its full range.
+pub const VELOCITY_DRAG_HEIGHT: f32 = 100.0;
+
+/// Access to the rows and cells of a drum or step sequencer lane.
+pub trait Drums {
+    /// The total number of rows, e.g. one per drum sound.
+    fn row_count(&self) -> usize;
+    /// The name of the row at the given index, e.g. `Kick`.
+    fn row_name(&self, row: usize) -> &str;
+    /// The length of each step in ticks, e.g. the snap unit.
+    ///
+    /// By default, returns `None` in which case the grid's current subdivision is used via
+    /// `TimelineCtx::grid_step`, requiring `Show::paint_grid`. As it changes with the zoom level,
+    /// hosts indexing cells by step should convert steps to ticks with the same subdivision.
+    fn step_ticks(&self) -> Option<f32> {
+        None
+    }
+    /// The tick relative to the start of the timeline at which step `0` begins, aligning the cells
+    /// with the grid. Hosts may return the start of the project so that steps are indexed from it.
+    ///
+    /// Returns `0.0` by default.
+    fn step_origin(&self) -> f32 {
+        0.0
+    }
+    /// The velocity of the cell at the given row and step, or `None` if the cell is empty.
+    fn cell(&self, row: usize, step: i64) -> Option<u8>;
+    /// Set the velocity of the cell at the given row and step, or clear it with `None`.
+    fn set_cell(&mut self, row: usize, step: i64, velocity: Option<u8>);
+}
+
+/// The drag in progress within a lane.
+#[derive(Copy, Clone, Debug)]
+enum Drag {
+    /// Painting the given state across the cells of a row, last painted at the given step.
+    Paint {
+        row: usize,
+        velocity: Option<u8>,
+        step: i64,
+    },
+    /// Adjusting the velocity of a single cell from its initial velocity.
+    Velocity { row: usize, step: i64, initial: u8 },
+}
+
+/// Instantiate a drum lane of the given height, with one named row per drum sound.
+///
+/// Cells are aligned to `Drums::step_ticks`, or the grid's subdivision by default. Click a cell to
+/// toggle it, or drag along a row to paint every cell passed with the toggled state of the first.
+/// Drag an enabled cell vertically to adjust its velocity, which is reflected by its opacity. Cells
+/// are hidden while steps are narrower than `MIN_STEP_WIDTH`.
+pub fn lane(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    api: &mut dyn Drums,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let w = ui.available_width();
+    let desired_size = egui::Vec2::new(w, height);
+    let (rect, _) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+    let sense = arbiter::sense(ui, Layer::Clip, rect, id, egui::Sense::click_and_drag());
+    let mut response = ui.interact(rect, id, sense);
+
+    let rows = api.row_count();
+    let step_ticks = api
+        .step_ticks()
+        .or_else(|| timeline.grid_step(ui.ctx()))
+        .unwrap_or(0.0);
+    let origin = api.step_origin();
+    let row_h = rect.height() / rows.max(1) as f32;
+    let row_y = |row: usize| {
+        let top = rect.top() + row as f32 * row_h;
+        egui::Rangef::new(top, top + row_h)
+    };
+    let step_range = |step: i64| {
+        let start = origin + step as f32 * step_ticks;
+        start..start + step_ticks
+    };
+    let step_rect = |row: usize, ticks: &Range<f32>| {
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(ticks.start),
+            timeline.tick_to_x(ticks.end),
+        );
+        egui::Rect::from_x_y_ranges(x, row_y(row)).shrink(1.0)
+    };
+    let cell_at = |pos: egui::Pos2| {
+        let row = ((pos.y - rect.top()) / row_h).floor();
+        let step = ((timeline.x_to_tick(pos.x) - origin) / step_ticks).floor();
+        (row >= 0.0 && (row as usize) < rows).then_some((row as usize, step as i64))
+    };
+
+    // Shade alternate rows.
+    let visuals = ui.style().noninteractive();
+    let row_fill = visuals.bg_stroke.color.linear_multiply(0.1);
+    for row in (1..rows).step_by(2) {
+        let row_rect = egui::Rect::from_x_y_ranges(rect.x_range(), row_y(row));
+        ui.painter().rect_filled(row_rect, 0.0, row_fill);
+    }
+
+    let enabled = step_ticks > 0.0 && step_ticks / timeline.ticks_per_point >= MIN_STEP_WIDTH;
+    if enabled {
+        // Toggle or paint cells, or adjust their velocity.
+        let drag_id = id.with("drag");
+        let locked = |step: i64| timeline.is_locked(step_range(step));
+        let origin_cell = ui.input(|i| i.pointer.press_origin()).and_then(cell_at);
+        let pointer = response.interact_pointer_pos();
+        if response.clicked() {
+            if let Some((row, step)) = pointer.and_then(cell_at).filter(|&(_, s)| !locked(s)) {
+                let velocity = match api.cell(row, step) {
+                    Some(_) => None,
+                    None => Some(DEFAULT_VELOCITY),
+                };
+                api.set_cell(row, step, velocity);
+                response.mark_changed();
+            }
+        } else if response.drag_started() {
+            if let (Some((row, step)), Some(pos)) = (origin_cell, pointer) {
+                let press = ui.input(|i| i.pointer.press_origin()).unwrap_or(pos);
+                let delta = pos - press;
+                let drag = match api.cell(row, step) {
+                    Some(initial) if delta.y.abs() > delta.x.abs() => {
+                        Drag::Velocity { row, step, initial }
+                    }
+                    Some(_) => Drag::Paint {
+                        row,
+                        velocity: None,
+                        step,
+                    },
+                    None => Drag::Paint {
+                        row,
+                        velocity: Some(DEFAULT_VELOCITY),
+                        step,
+                    },
+                };
+                if let Drag::Paint { velocity, .. } = drag {
+                    if !locked(step) {
+                        api.set_cell(row, step, velocity);
+                        response.mark_changed();
+                    }
+                }
+                ui.data_mut(|d| d.insert_temp(drag_id, drag));
+            }
+        }
+        let drag: Option<Drag> = ui.data(|d| d.get_temp(drag_id));
+        match (drag, pointer) {
+            (
+                Some(Drag::Paint {
+                    row,
+                    velocity,
+                    step,
+                }),
+                Some(pos),
+            ) if response.dragged() => {
+                // Fill every step passed since the last, so that fast drags leave no gaps.
+                let last = step;
+                let step = ((timeline.x_to_tick(pos.x) - origin) / step_ticks).floor() as i64;
+                for step in last.min(step)..=last.max(step) {
+                    if api.cell(row, step) != velocity && !locked(step) {
+                        api.set_cell(row, step, velocity);
+                        response.mark_changed();
+                    }
+                }
+                let drag = Drag::Paint {
+                    row,
+                    velocity,
+                    step,
+                };
+                ui.data_mut(|d| d.insert_temp(drag_id, drag));
+            }
+            (Some(Drag::Velocity { row, step, initial }), Some(pos)) if response.dragged() => {
+                if let Some(press) = ui.input(|i| i.pointer.press_origin()) {
+                    let delta = (press.y - pos.y) / VELOCITY_DRAG_HEIGHT * 127.0;
+                    let velocity = (initial as f32 + delta).round().clamp(1.0, 127.0) as u8;
+                    if api.cell(row, step) != Some(velocity) && !locked(step) {
+                        api.set_cell(row, step, Some(velocity));
+                        response.mark_changed();
+                    }
+                }
+            }
+            _ => (),
+        }
+        if response.drag_stopped() {
+            ui.data_mut(|d| d.remove::<Drag>(drag_id));
+        }
+        let hovered = response.hover_pos().and_then(cell_at);
+        let target = match (drag, hovered) {
+            (Some(Drag::Velocity { .. }), _) => Target::VerticalHandle,
+            (_, Some((_, step))) if locked(step) => Target::Locked,
+            _ => Target::Draw,
+        };
+        cursor::set(&response, target);
+
+        // Draw the visible cells, with enabled cells' opacity reflecting their velocity.
+        let visible = ui.clip_rect().intersect(rect);
+        let first = ((timeline.x_to_tick(visible.left()) - origin) / step_ticks).floor() as i64;
+        let last = ((timeline.x_to_tick(visible.right()) - origin) / step_ticks).ceil() as i64;
+        let empty_fill = visuals.bg_stroke.color.linear_multiply(0.15);
+        let color = timeline
+            .track_color
+            .unwrap_or(ui.visuals().selection.bg_fill);
+        let hover_stroke = ui.visuals().widgets.hovered.fg_stroke;
+        for row in 0..rows {
+            for step in first..last {
+                let cell_rect = step_rect(row, &step_range(step));
+                let fill = match api.cell(row, step) {
+                    Some(velocity) => color.linear_multiply(0.3 + 0.7 * velocity as f32 / 127.0),
+                    None => empty_fill,
+                };
+                ui.painter().rect_filled(cell_rect, 1.0, fill);
+                if hovered == Some((row, step)) {
+                    ui.painter().rect_stroke(cell_rect, 1.0, hover_stroke);
+                }
+            }
+        }
+    }
+
+    // Label each row along the left edge of the visible area.
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    if row_h >= font_id.size {
+        let x = ui.clip_rect().left().max(rect.left()) + 2.0;
+        let text_color = visuals.text_color();
+        for row in 0..rows {
+            let pos = egui::Pos2::new(x, row_y(row).center());
+            let anchor = egui::Align2::LEFT_CENTER;
+            let name = api.row_name(row).to_string();
+            ui.painter()
+                .text(pos, anchor, name, font_id.clone(), text_color);
+        }
+    }
+
+    response
+}
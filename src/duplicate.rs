@@ -0,0 +1,22 @@
+/// A request to duplicate one or more items, emitted when an item is dragged while the duplicate
+/// modifier is held.
+///
+/// The sources are left in place, leaving the host to create the duplicates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Duplicate<T> {
+    /// The items to duplicate, e.g. clip or keyframe IDs.
+    pub sources: Vec<T>,
+    /// The distance in ticks from each source to its duplicate.
+    pub delta: f32,
+    /// The target tick of the dragged item relative to the start of the timeline.
+    pub tick: f32,
+    /// The position of the pointer when the drag was released.
+    ///
+    /// Useful for determining the target track, e.g. via `TracksLayout::track_at`.
+    pub pointer: egui::Pos2,
+}
+
+/// Whether or not the duplicate modifier (`Alt` or `Ctrl`/`Cmd`) is held.
+pub fn modifier_held(ui: &egui::Ui) -> bool {
+    ui.input(|i| i.modifiers.alt || i.modifiers.command)
+}
@@ -0,0 +1,135 @@
+use super::{
+    arbiter::{self, Layer},
+    hover, TimelineCtx,
+};
+#[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+use rayon::prelude::*;
+use std::{hash::Hash, ops::Range};
+
+/// The width of the interactive area around an instantaneous event.
+pub const INSTANT_WIDTH: f32 = 6.0;
+
+/// Access to the events displayed within an event lane, e.g. for profiling or telemetry.
+pub trait Events {
+    /// The total number of events.
+    fn event_count(&self) -> usize;
+    /// The range of the event at the given index in ticks relative to the start of the timeline.
+    ///
+    /// Events with an empty range are displayed as instantaneous, while all others are displayed
+    /// as bars spanning their duration.
+    fn event_range(&self, index: usize) -> Range<f32>;
+    /// The label displayed in the tooltip when hovering the event.
+    fn event_label(&self, index: usize) -> String;
+    /// An optional color for the event, otherwise the default widget color is used.
+    fn event_color(&self, _index: usize) -> Option<egui::Color32> {
+        None
+    }
+    /// The event at the given index was clicked.
+    fn event_clicked(&mut self, _index: usize) {}
+}
+
+/// Instantiate an event lane of the given height.
+///
+/// Instantaneous events are drawn as a thin line topped by a small marker, while events with a
+/// duration are drawn as bars. Hovering an event displays its label in a tooltip, or within the
+/// hover tooltip if shown via `hover::tooltip`.
+pub fn lane(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    api: &mut dyn Events,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let w = ui.available_width();
+    let desired_size = egui::Vec2::new(w, height);
+    let (rect, mut response) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+    let visible_x = ui.clip_rect().x_range().expand(INSTANT_WIDTH);
+    let bar_y = rect.y_range().shrink(rect.height() * 0.2);
+    let own_tooltip = !hover::tooltip_enabled(ui.ctx());
+
+    for ix in 0..api.event_count() {
+        let range = api.event_range(ix);
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(range.start),
+            timeline.tick_to_x(range.end),
+        );
+        if x.max < visible_x.min || x.min > visible_x.max {
+            continue;
+        }
+        let instant = range.is_empty();
+        let event_rect = if instant {
+            let x = x.min - INSTANT_WIDTH * 0.5..=x.min + INSTANT_WIDTH * 0.5;
+            egui::Rect::from_x_y_ranges(x, rect.y_range())
+        } else {
+            egui::Rect::from_x_y_ranges(x, bar_y)
+        };
+        let event_id = id.with(ix);
+        let sense = arbiter::sense(ui, Layer::Clip, event_rect, event_id, egui::Sense::click());
+        let event_response = ui.interact(event_rect, event_id, sense);
+        if event_response.clicked() {
+            api.event_clicked(ix);
+            response.mark_changed();
+        }
+
+        // Draw the event.
+        let visuals = ui.style().interact(&event_response);
+        let color = api.event_color(ix).unwrap_or(visuals.fg_stroke.color);
+        if instant {
+            let stroke = egui::Stroke::new(visuals.fg_stroke.width, color);
+            let cx = event_rect.center().x;
+            ui.painter().vline(cx, rect.y_range(), stroke);
+            let r = INSTANT_WIDTH * 0.5;
+            let top = egui::Pos2::new(cx, rect.top() + r);
+            ui.painter().circle_filled(top, r, color);
+        } else {
+            let fill = color.linear_multiply(0.5);
+            ui.painter()
+                .rect(event_rect, 0.0, fill, egui::Stroke::new(1.0, color));
+        }
+
+        if event_response.hovered() {
+            let label = api.event_label(ix);
+            hover::register(ui, event_rect, Some(&label), range);
+            if own_tooltip {
+                event_response.on_hover_text_at_pointer(label);
+            }
+        }
+    }
+
+    response
+}
+
+/// The number of events merged per parallel task while downsampling.
+#[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+const DOWNSAMPLE_CHUNK_LEN: usize = 4096;
+
+/// Downsample the given events, ordered by their start, by merging those separated by less than
+/// `min_gap` ticks into a single span.
+///
+/// Useful for reducing lanes of dense telemetry to something that may be drawn within a frame,
+/// e.g. with `min_gap` set to the number of ticks per point. With the `rayon` feature enabled,
+/// the events are merged in parallel chunks.
+pub fn downsample(events: &[Range<f32>], min_gap: f32) -> Vec<Range<f32>> {
+    #[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+    let merged: Vec<Range<f32>> = events
+        .par_chunks(DOWNSAMPLE_CHUNK_LEN)
+        .map(|chunk| merge(chunk.iter().cloned(), min_gap))
+        .flatten_iter()
+        .collect();
+    #[cfg(not(all(feature = "rayon", not(target_arch = "wasm32"))))]
+    let merged = events.to_vec();
+    merge(merged.into_iter(), min_gap)
+}
+
+/// Merge ranges ordered by their start that are separated by less than `min_gap`.
+fn merge(ranges: impl Iterator<Item = Range<f32>>, min_gap: f32) -> Vec<Range<f32>> {
+    let mut merged: Vec<Range<f32>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start - last.end < min_gap => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
@@ -0,0 +1,134 @@
+use super::{
+    playhead,
+    ruler::MusicalInfo,
+    time::{Step, TimeDomain},
+};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// The playhead position, shared between an audio thread producing it and the UI displaying it.
+///
+/// All methods are lock-free and real-time safe. Positions are in absolute ticks, i.e. from the
+/// start of the project rather than the start of the visible timeline. Share via an `Arc`.
+#[derive(Debug)]
+pub struct SharedPlayhead {
+    /// The bits of the `f64` position in ticks.
+    ticks: AtomicU64,
+    playing: AtomicBool,
+    /// The bits of the `f64` playback rate.
+    rate: AtomicU64,
+    /// The bits of an `f64` position requested by the UI, or NaN if there is none.
+    seek: AtomicU64,
+}
+
+/// A `SharedPlayhead` viewed over a time domain, implementing `Playhead` for the UI thread.
+///
+/// Setting the playhead requests a seek that the audio thread picks up via
+/// `SharedPlayhead::take_seek`.
+pub struct SharedView<'a> {
+    /// The shared playhead.
+    pub shared: &'a SharedPlayhead,
+    /// The time domain over which the timeline is laid out.
+    pub domain: &'a dyn TimeDomain,
+    /// The absolute tick at the start of the visible timeline.
+    pub start: f64,
+}
+
+impl SharedPlayhead {
+    /// A stopped playhead at the start of the project, with a playback rate of `1.0`.
+    pub fn new() -> Self {
+        Self {
+            ticks: AtomicU64::new(0f64.to_bits()),
+            playing: AtomicBool::new(false),
+            rate: AtomicU64::new(1f64.to_bits()),
+            seek: AtomicU64::new(f64::NAN.to_bits()),
+        }
+    }
+
+    /// Update the playhead, typically once per audio callback.
+    pub fn set(&self, ticks: f64, playing: bool, rate: f64) {
+        self.ticks.store(ticks.to_bits(), Ordering::Relaxed);
+        self.playing.store(playing, Ordering::Relaxed);
+        self.rate.store(rate.to_bits(), Ordering::Relaxed);
+    }
+
+    /// The position in absolute ticks.
+    pub fn ticks(&self) -> f64 {
+        f64::from_bits(self.ticks.load(Ordering::Relaxed))
+    }
+
+    /// Whether or not playback is in progress.
+    pub fn is_playing(&self) -> bool {
+        self.playing.load(Ordering::Relaxed)
+    }
+
+    /// The playback rate, where `1.0` is normal speed and negative rates play in reverse.
+    pub fn rate(&self) -> f64 {
+        f64::from_bits(self.rate.load(Ordering::Relaxed))
+    }
+
+    /// Request that the producer seek to the given position, replacing any pending request. The
+    /// position is displayed immediately.
+    pub fn request_seek(&self, ticks: f64) {
+        self.ticks.store(ticks.to_bits(), Ordering::Relaxed);
+        self.seek.store(ticks.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Take the pending seek request, if any, typically at the start of each audio callback.
+    pub fn take_seek(&self) -> Option<f64> {
+        let bits = self.seek.swap(f64::NAN.to_bits(), Ordering::Relaxed);
+        Some(f64::from_bits(bits)).filter(|ticks| !ticks.is_nan())
+    }
+
+    /// View the playhead over the given time domain, with the visible timeline starting at the
+    /// given absolute tick.
+    pub fn view<'a>(&'a self, domain: &'a dyn TimeDomain, start: f64) -> SharedView<'a> {
+        SharedView {
+            shared: self,
+            domain,
+            start,
+        }
+    }
+}
+
+impl Default for SharedPlayhead {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> TimeDomain for SharedView<'a> {
+    fn units_per_point(&self) -> f32 {
+        self.domain.units_per_point()
+    }
+    fn steps<'b>(
+        &'b self,
+        visible_len: f32,
+        min_step_gap: f32,
+    ) -> Box<dyn Iterator<Item = Step> + 'b> {
+        self.domain.steps(visible_len, min_step_gap)
+    }
+    fn label(&self, step: &Step) -> Option<String> {
+        self.domain.label(step)
+    }
+    fn snap(&self, units: f32) -> f32 {
+        self.domain.snap(units)
+    }
+    fn musical_info(&self) -> Option<&dyn MusicalInfo> {
+        self.domain.musical_info()
+    }
+}
+
+impl<'a> playhead::Info for SharedView<'a> {
+    fn playhead_ticks(&self) -> f32 {
+        (self.shared.ticks() - self.start) as f32
+    }
+    fn is_playing(&self) -> bool {
+        self.shared.is_playing()
+    }
+}
+
+impl<'a> playhead::Interaction for SharedView<'a> {
+    fn set_playhead_ticks(&mut self, ticks: f32) {
+        self.shared.request_seek(self.start + ticks as f64);
+    }
+}
@@ -0,0 +1,84 @@
+use super::TimelineCtx;
+
+/// A host-provided thumbnail representing the source media at a particular tick.
+#[derive(Copy, Clone, Debug)]
+pub struct Thumbnail {
+    /// The texture containing the thumbnail image.
+    pub texture: egui::TextureId,
+    /// The position of the source frame in ticks relative to the start of the timeline.
+    pub tick: f32,
+    /// The aspect ratio (width / height) of the thumbnail.
+    pub aspect: f32,
+}
+
+/// Instantiate a filmstrip track of the given height.
+///
+/// Thumbnails must be sorted by `tick`, and are expected to be roughly evenly spaced. Each
+/// thumbnail is drawn with its left edge at its tick. Only every nth thumbnail is drawn, where n is
+/// the smallest stride at which the widest thumbnail fits within the average spacing at the current
+/// zoom level, so that the same thumbnails are drawn while scrolling. Thumbnails are cropped at the
+/// edges of the visible area.
+pub fn track(
+    ui: &mut egui::Ui,
+    timeline: &TimelineCtx,
+    height: f32,
+    thumbnails: &[Thumbnail],
+) -> egui::Response {
+    let w = ui.available_width();
+    let desired_size = egui::Vec2::new(w, height);
+    let (rect, response) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+    let visible = ui.clip_rect().intersect(rect);
+    if !visible.is_positive() {
+        return response;
+    }
+
+    // Only draw every `stride`th thumbnail so that they don't overlap, anchored to the first.
+    let max_w = thumbnails
+        .iter()
+        .fold(0.0f32, |w, thumb| w.max(thumb.aspect * height));
+    let stride = match (thumbnails.first(), thumbnails.last()) {
+        (Some(first), Some(last)) if thumbnails.len() > 1 => {
+            let span = (last.tick - first.tick) / timeline.ticks_per_point;
+            let spacing = span / (thumbnails.len() - 1) as f32;
+            match spacing > 0.0 {
+                true => (max_w / spacing).ceil().max(1.0) as usize,
+                false => thumbnails.len(),
+            }
+        }
+        _ => 1,
+    };
+
+    // Skip the thumbnails that end before the visible area.
+    let start_tick = timeline.x_to_tick(visible.left() - max_w);
+    let start = thumbnails.partition_point(|thumb| thumb.tick < start_tick);
+    let start = start - start % stride;
+
+    for thumb in thumbnails[start..].iter().step_by(stride) {
+        let x = timeline.tick_to_x(thumb.tick);
+        if x > visible.right() {
+            break;
+        }
+        let thumb_w = thumb.aspect * height;
+        let thumb_rect =
+            egui::Rect::from_min_size(egui::Pos2::new(x, rect.top()), [thumb_w, height].into());
+
+        // Crop the thumbnail to the visible area.
+        let cropped = thumb_rect.intersect(visible);
+        if !cropped.is_positive() {
+            continue;
+        }
+        let uv_x = egui::Rangef::new(
+            egui::remap(cropped.left(), thumb_rect.x_range(), 0.0..=1.0),
+            egui::remap(cropped.right(), thumb_rect.x_range(), 0.0..=1.0),
+        );
+        let uv_y = egui::Rangef::new(
+            egui::remap(cropped.top(), thumb_rect.y_range(), 0.0..=1.0),
+            egui::remap(cropped.bottom(), thumb_rect.y_range(), 0.0..=1.0),
+        );
+        let uv = egui::Rect::from_x_y_ranges(uv_x, uv_y);
+        ui.painter()
+            .image(thumb.texture, cropped, uv, egui::Color32::WHITE);
+    }
+
+    response
+}
@@ -0,0 +1,252 @@
+use super::{
+    ruler::MusicalInfo,
+    time::{FrameRate, Frames, Samples, Seconds},
+};
+
+/// Formats positions for display, e.g. within ruler labels, playhead labels and tooltips.
+///
+/// Using a single formatter for all position displays ensures they agree.
+pub trait TimeFormatter {
+    /// Format the given position in units relative to the start of the timeline.
+    fn format(&self, units: f32) -> String;
+    /// Parse a position typed by the user into units relative to the start of the timeline.
+    ///
+    /// Returns `None` if the text could not be parsed. Returns `None` by default.
+    fn parse(&self, _text: &str) -> Option<f32> {
+        None
+    }
+    /// Format the given signed distance in units, e.g. the delta of a drag like `+2.1.000`.
+    ///
+    /// By default, formats the number of units with an explicit sign.
+    fn format_delta(&self, units: f32) -> String {
+        format!("{:+.0}", units)
+    }
+}
+
+impl<T: MusicalInfo> TimeFormatter for T {
+    fn format(&self, ticks: f32) -> String {
+        format_bbt(ticks, self)
+    }
+    fn parse(&self, text: &str) -> Option<f32> {
+        parse_bbt(text, self)
+    }
+    fn format_delta(&self, ticks: f32) -> String {
+        format_bbt_delta(ticks, self)
+    }
+}
+
+impl<'a> TimeFormatter for dyn MusicalInfo + 'a {
+    fn format(&self, ticks: f32) -> String {
+        format_bbt(ticks, self)
+    }
+    fn parse(&self, text: &str) -> Option<f32> {
+        parse_bbt(text, self)
+    }
+    fn format_delta(&self, ticks: f32) -> String {
+        format_bbt_delta(ticks, self)
+    }
+}
+
+impl TimeFormatter for Seconds {
+    fn format(&self, seconds: f32) -> String {
+        format_seconds(self.start + seconds as f64)
+    }
+    fn parse(&self, text: &str) -> Option<f32> {
+        parse_seconds(text).map(|secs| (secs - self.start) as f32)
+    }
+    fn format_delta(&self, seconds: f32) -> String {
+        let sign = if seconds < 0.0 { "-" } else { "+" };
+        format!("{}{}", sign, format_seconds(seconds.abs() as f64))
+    }
+}
+
+impl TimeFormatter for Samples {
+    fn format(&self, samples: f32) -> String {
+        format!("{}", (self.start + samples as f64).round())
+    }
+    fn parse(&self, text: &str) -> Option<f32> {
+        let sample: f64 = text.trim().parse().ok()?;
+        Some((sample - self.start) as f32)
+    }
+}
+
+impl TimeFormatter for Frames {
+    fn format(&self, frames: f32) -> String {
+        let frame = (self.start + frames as f64).round() as i64;
+        self.rate.timecode(frame)
+    }
+    fn parse(&self, text: &str) -> Option<f32> {
+        let frame = parse_timecode(text, self.rate)?;
+        Some((frame as f64 - self.start) as f32)
+    }
+    fn format_delta(&self, frames: f32) -> String {
+        let sign = if frames < 0.0 { "-" } else { "+" };
+        format!(
+            "{}{}",
+            sign,
+            self.rate.timecode(frames.abs().round() as i64)
+        )
+    }
+}
+
+/// Format the given tick as `bar.beat.tick`, e.g. `12.3.240`.
+///
+/// Bars are counted from `MusicalInfo::first_bar_number`, while beats are counted from `1`
+/// according to the bar's time signature.
+pub fn format_bbt(tick: f32, info: &dyn MusicalInfo) -> String {
+    // Walk the bars from the start of the view to the bar containing the tick, stopping at
+    // zero-length bars, which would otherwise never advance.
+    let mut bar = info.bar_at_ticks(0.0);
+    let mut bar_number = info.first_bar_number();
+    while tick >= bar.tick_range.end && bar.tick_range.end > bar.tick_range.start {
+        bar = info.bar_at_ticks(bar.tick_range.end + 0.5);
+        bar_number += 1;
+    }
+    while tick < bar.tick_range.start && bar.tick_range.end > bar.tick_range.start {
+        bar = info.bar_at_ticks(bar.tick_range.start - 0.5);
+        bar_number -= 1;
+    }
+    let beat_ticks = info.ticks_per_beat() as f32 * 4.0 / bar.time_sig.bottom as f32;
+    let in_bar = tick - bar.tick_range.start;
+    let beat = (in_bar / beat_ticks).floor();
+    let ticks = (in_bar - beat * beat_ticks).floor();
+    format!("{}.{}.{:03}", bar_number, beat as u32 + 1, ticks as u32)
+}
+
+/// Format the given signed distance in ticks as `bars.beats.ticks`, e.g. `+2.1.000`.
+///
+/// Unlike `format_bbt`, all components count from `0`. Uses the time signature of the bar at the
+/// start of the view.
+pub fn format_bbt_delta(ticks: f32, info: &dyn MusicalInfo) -> String {
+    let sign = if ticks < 0.0 { "-" } else { "+" };
+    let time_sig = info.bar_at_ticks(0.0).time_sig;
+    let beat_ticks = info.ticks_per_beat() as f32 * 4.0 / time_sig.bottom as f32;
+    let bar_ticks = beat_ticks * time_sig.top as f32;
+    let ticks = ticks.abs().round();
+    let bars = (ticks / bar_ticks).floor();
+    let beats = ((ticks - bars * bar_ticks) / beat_ticks).floor();
+    let rem = ticks - bars * bar_ticks - beats * beat_ticks;
+    format!("{}{}.{}.{:03}", sign, bars, beats, rem as u32)
+}
+
+/// Parse `bar`, `bar.beat` or `bar.beat.tick` into a tick, e.g. `33.1` or `12.3.240`.
+///
+/// The inverse of `format_bbt`.
+pub fn parse_bbt(text: &str, info: &dyn MusicalInfo) -> Option<f32> {
+    let mut parts = text.trim().split('.');
+    let bar_number: i64 = parts.next()?.trim().parse().ok()?;
+    let beat: u32 = match parts.next() {
+        Some(s) => s.trim().parse().ok().filter(|&b| b > 0)?,
+        None => 1,
+    };
+    let ticks: f32 = match parts.next() {
+        Some(s) => s.trim().parse().ok()?,
+        None => 0.0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    // Stop at zero-length bars, which would otherwise never advance.
+    let mut bar = info.bar_at_ticks(0.0);
+    let mut n = info.first_bar_number();
+    while n < bar_number && bar.tick_range.end > bar.tick_range.start {
+        bar = info.bar_at_ticks(bar.tick_range.end + 0.5);
+        n += 1;
+    }
+    while n > bar_number && bar.tick_range.end > bar.tick_range.start {
+        bar = info.bar_at_ticks(bar.tick_range.start - 0.5);
+        n -= 1;
+    }
+    if n != bar_number {
+        return None;
+    }
+    let beat_ticks = info.ticks_per_beat() as f32 * 4.0 / bar.time_sig.bottom as f32;
+    Some(bar.tick_range.start + (beat - 1) as f32 * beat_ticks + ticks)
+}
+
+/// Parse `ss[.fff]`, `m:ss[.fff]` or `h:mm:ss[.fff]` into a number of seconds, e.g. `1:02.5`.
+///
+/// The inverse of `format_seconds`.
+pub fn parse_seconds(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, text),
+    };
+    let separators = text.matches(':').count();
+    if separators > 2 {
+        return None;
+    }
+    // Seconds and minutes must be less than `60` when preceded by a larger unit.
+    let mut parts = text.rsplit(':');
+    let secs: f64 = parts.next()?.trim().parse().ok()?;
+    if separators > 0 && !(0.0..60.0).contains(&secs) {
+        return None;
+    }
+    let mut total = secs;
+    for (ix, (part, scale)) in parts.zip([60.0, 3_600.0].iter()).enumerate() {
+        let n: u64 = part.trim().parse().ok()?;
+        if ix == 0 && separators > 1 && n >= 60 {
+            return None;
+        }
+        total += n as f64 * scale;
+    }
+    if !total.is_finite() {
+        return None;
+    }
+    Some(sign * total)
+}
+
+/// Parse `hh:mm:ss:ff` timecode into a frame index, e.g. `00:00:10:12`.
+///
+/// Leading fields may be omitted, and `;` is accepted as a separator. The inverse of
+/// `FrameRate::timecode`.
+pub fn parse_timecode(text: &str, rate: FrameRate) -> Option<i64> {
+    let text = text.trim();
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, text),
+    };
+    let mut fields = [0i64; 4];
+    let parts: Vec<&str> = text.split([':', ';']).collect();
+    if parts.is_empty() || parts.len() > fields.len() {
+        return None;
+    }
+    let offset = fields.len() - parts.len();
+    for (field, part) in fields[offset..].iter_mut().zip(&parts) {
+        *field = part.trim().parse().ok().filter(|&n: &i64| n >= 0)?;
+    }
+    let [hh, mm, ss, ff] = fields;
+    let fps = rate.nominal() as i64;
+    let mut frame = ((hh * 60 + mm) * 60 + ss) * fps + ff;
+    if rate.drop_frame() {
+        let total_mins = hh * 60 + mm;
+        frame -= 2 * (total_mins - total_mins / 10);
+    }
+    Some(sign * frame)
+}
+
+/// Format the given number of seconds as `hh:mm:ss:ff` timecode at the given frame rate.
+pub fn format_timecode(seconds: f64, rate: FrameRate) -> String {
+    let frame = (seconds * rate.fps()).round() as i64;
+    rate.timecode(frame)
+}
+
+/// Format the given number of seconds as `m:ss`, followed by up to three fractional digits.
+pub fn format_seconds(seconds: f64) -> String {
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    let millis = (seconds.abs() * 1_000.0).round() as u64;
+    let (mins, secs, millis) = (millis / 60_000, (millis / 1_000) % 60, millis % 1_000);
+    if millis == 0 {
+        format!("{}{}:{:02}", sign, mins, secs)
+    } else {
+        let frac = format!("{:03}", millis);
+        format!(
+            "{}{}:{:02}.{}",
+            sign,
+            mins,
+            secs,
+            frac.trim_end_matches('0')
+        )
+    }
+}
@@ -0,0 +1,60 @@
+/// The lifecycle of an interactive gesture such as a drag.
+///
+/// Widgets continue to call their usual setters on every frame of a drag. Gestures are emitted
+/// alongside so that hosts may coalesce a whole drag into a single undo step.
+///
+/// Pressing `Escape` mid-drag cancels the gesture. Widgets restore the initial value where they
+/// can, while hosts should discard any changes made since the gesture started.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Gesture<T> {
+    /// The gesture began, with the value prior to any modification.
+    Started { initial: T },
+    /// The gesture is in progress.
+    Updated { initial: T, current: T },
+    /// The gesture ended, with the value it started and ended with.
+    Finished { initial: T, value: T },
+    /// The gesture was cancelled via `Escape`, with the value it started with.
+    Cancelled { initial: T },
+}
+
+/// Whether or not the drag driven by the given response is being cancelled via `Escape`.
+pub(crate) fn cancelled(ui: &egui::Ui, response: &egui::Response) -> bool {
+    response.dragged() && ui.input(|i| i.key_pressed(egui::Key::Escape))
+}
+
+/// Track the gesture driven by the given response.
+///
+/// The `before` value is the value prior to any modification this frame, while `after` is the
+/// value following any modification. The initial value is stored in temporary data under the
+/// response's ID until the gesture finishes. If the gesture is cancelled, the drag is stopped so
+/// that it does not commit upon release.
+pub(crate) fn track<T>(
+    ui: &egui::Ui,
+    response: &egui::Response,
+    before: T,
+    after: T,
+) -> Option<Gesture<T>>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let id = response.id.with("gesture");
+    if response.drag_started() {
+        ui.data_mut(|d| d.insert_temp(id, before.clone()));
+        return Some(Gesture::Started { initial: before });
+    }
+    let initial: T = ui.data(|d| d.get_temp(id))?;
+    if cancelled(ui, response) {
+        ui.data_mut(|d| d.remove::<T>(id));
+        ui.ctx().stop_dragging();
+        Some(Gesture::Cancelled { initial })
+    } else if response.drag_stopped() {
+        ui.data_mut(|d| d.remove::<T>(id));
+        let value = after;
+        Some(Gesture::Finished { initial, value })
+    } else if response.dragged() {
+        let current = after;
+        Some(Gesture::Updated { initial, current })
+    } else {
+        None
+    }
+}
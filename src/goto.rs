@@ -0,0 +1,193 @@
+use super::{
+    annotation::Annotation, cue::Cues, event::Events, format::TimeFormatter, highlight, history,
+    TimelineApi, TracksLayout,
+};
+use std::hash::Hash;
+
+/// A named position listed within the jump-to menu, e.g. a marker or the start of a section.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// The name displayed within the menu.
+    pub name: String,
+    /// The position in ticks relative to the start of the timeline.
+    pub tick: f32,
+}
+
+/// State persisted between frames.
+#[derive(Clone, Default)]
+struct State {
+    /// Whether or not the popup is open.
+    open: bool,
+    /// Whether or not the text field should request focus.
+    request_focus: bool,
+    /// The position of the top-left of the popup.
+    pos: egui::Pos2,
+    /// The text typed so far.
+    text: String,
+}
+
+/// Open the go-to-position popup at the given position.
+///
+/// This is typically called in response to a shortcut or double-clicking the ruler.
+pub fn open(ctx: &egui::Context, id_source: impl Hash, pos: egui::Pos2) {
+    let id = egui::Id::new(id_source);
+    let state = State {
+        open: true,
+        request_focus: true,
+        pos,
+        text: String::new(),
+    };
+    ctx.data_mut(|d| d.insert_temp(id, state));
+}
+
+/// Whether or not the go-to-position popup is open.
+pub fn is_open(ctx: &egui::Context, id_source: impl Hash) -> bool {
+    let id = egui::Id::new(id_source);
+    ctx.data(|d| d.get_temp::<State>(id))
+        .is_some_and(|s| s.open)
+}
+
+/// Show the go-to-position popup if it is open.
+///
+/// The typed position is parsed using the given `formatter`, e.g. `33.1` for musical positions,
+/// `1:02.5` for clock time or `00:00:10:12` for timecode. Text that fails to parse is shown in
+/// the error color. Pressing `Enter` closes the popup and returns the position in units relative
+/// to the start of the timeline, which the host may use to seek the playhead or scroll the view.
+/// Pressing `Escape` or clicking elsewhere closes the popup.
+pub fn popup(
+    ctx: &egui::Context,
+    id_source: impl Hash,
+    formatter: &dyn TimeFormatter,
+) -> Option<f32> {
+    let id = egui::Id::new(id_source);
+    let mut state: State = ctx.data(|d| d.get_temp(id))?;
+    if !state.open {
+        return None;
+    }
+    let mut result = None;
+    egui::Area::new(id)
+        .order(egui::Order::Foreground)
+        .fixed_pos(state.pos)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Go to");
+                    let invalid = !state.text.is_empty() && formatter.parse(&state.text).is_none();
+                    let mut edit = egui::TextEdit::singleline(&mut state.text)
+                        .hint_text(formatter.format(0.0))
+                        .desired_width(96.0);
+                    if invalid {
+                        edit = edit.text_color(ui.visuals().error_fg_color);
+                    }
+                    // Parse the text as updated by this frame's input.
+                    let response = ui.add(edit);
+                    let parsed = formatter.parse(&state.text);
+                    if state.request_focus {
+                        response.request_focus();
+                        state.request_focus = false;
+                    }
+                    if response.lost_focus() {
+                        if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            result = parsed;
+                        }
+                        state.open = false;
+                    }
+                });
+            });
+        });
+    if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+        state.open = false;
+    }
+    ctx.data_mut(|d| d.insert_temp(id, state));
+    result
+}
+
+impl Entry {
+    /// An entry at the start of each cue, e.g. markers or sections, named by its text.
+    pub fn cues(api: &dyn Cues) -> Vec<Entry> {
+        (0..api.cue_count())
+            .map(|ix| Entry {
+                name: api.cue_text(ix).to_string(),
+                tick: api.cue_range(ix).start,
+            })
+            .collect()
+    }
+
+    /// An entry at the start of each annotation, named by its text.
+    pub fn annotations(annotations: &[Annotation]) -> Vec<Entry> {
+        annotations
+            .iter()
+            .map(|a| Entry {
+                name: a.text.clone(),
+                tick: a.tick_range.start,
+            })
+            .collect()
+    }
+
+    /// An entry at the start of each event, named by its label.
+    pub fn events(api: &dyn Events) -> Vec<Entry> {
+        (0..api.event_count())
+            .map(|ix| Entry {
+                name: api.event_label(ix),
+                tick: api.event_range(ix).start,
+            })
+            .collect()
+    }
+}
+
+/// A menu button listing the given entries in order of their position, each alongside its
+/// position formatted via `formatter`.
+///
+/// Returns the position of the chosen entry, which the host may use to seek the playhead and
+/// pass to `reveal` to scroll it into view.
+pub fn menu(
+    ui: &mut egui::Ui,
+    title: impl Into<egui::WidgetText>,
+    entries: &[Entry],
+    formatter: &dyn TimeFormatter,
+) -> Option<f32> {
+    let mut order: Vec<&Entry> = entries.iter().collect();
+    order.sort_by(|a, b| a.tick.total_cmp(&b.tick));
+    let response = ui.menu_button(title, |ui| {
+        if order.is_empty() {
+            ui.weak("No markers");
+        }
+        let mut chosen = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("entries").striped(true).show(ui, |ui| {
+                for entry in &order {
+                    if ui.button(&entry.name).clicked() {
+                        chosen = Some(entry.tick);
+                        ui.close_menu();
+                    }
+                    ui.weak(formatter.format(entry.tick));
+                    ui.end_row();
+                }
+            });
+        });
+        chosen
+    });
+    response.inner.flatten()
+}
+
+/// Scroll the timeline so that the given position is visible, if it isn't already.
+///
+/// The timeline is shifted via `TimelineApi::shift_timeline_start`, leaving a
+/// `highlight::LEAD_IN` before the position. The prior view is recorded in the navigation
+/// history. Returns whether or not the timeline was scrolled.
+pub fn reveal(
+    ctx: &egui::Context,
+    layout: &TracksLayout,
+    tick: f32,
+    timeline: &mut dyn TimelineApi,
+) -> bool {
+    let visible = &layout.visible_ticks;
+    if visible.contains(&tick) {
+        return false;
+    }
+    let lead_in = (visible.end - visible.start) * highlight::LEAD_IN;
+    history::push(ctx, layout);
+    timeline.shift_timeline_start(tick - lead_in);
+    ctx.request_repaint();
+    true
+}
@@ -0,0 +1,71 @@
+use super::TracksLayout;
+
+/// Set track headers within a `ui` separate from the timeline, e.g. a different dock tab or side
+/// panel, keeping each header row-aligned with its track.
+///
+/// Rows are laid out from the top of the available rect using the track rects recorded within the
+/// `layout` via `TrackCtx::id`, so only tracks set with an ID receive a header. Headers for
+/// scrolling tracks are clipped to the scrolling area, and scrolling over the headers scrolls the
+/// timeline's tracks. When the headers are set before the timeline, pass the previous frame's
+/// layout.
+pub fn detached(
+    ui: &mut egui::Ui,
+    layout: &TracksLayout,
+    mut header: impl FnMut(egui::Id, &mut egui::Ui),
+) -> egui::Response {
+    let rect = ui.available_rect_before_wrap();
+    let response = ui.allocate_rect(rect, egui::Sense::hover());
+    let dy = rect.top() - layout.timeline_rect.top();
+    let viewport = layout.viewport.translate(egui::vec2(0.0, dy));
+    let scroll_clip = egui::Rect::from_x_y_ranges(rect.x_range(), viewport.y_range());
+    for (ix, &(id, row)) in layout.track_rects.iter().enumerate() {
+        let row = row.translate(egui::vec2(0.0, dy));
+        let row = egui::Rect::from_x_y_ranges(rect.x_range(), row.y_range());
+        let clip = match layout.scrolled_tracks.contains(&ix) {
+            true => scroll_clip,
+            false => rect,
+        };
+        let clip = ui.clip_rect().intersect(clip);
+        if !clip.intersects(row) {
+            continue;
+        }
+        let layout = *ui.layout();
+        let mut child = ui.new_child(egui::UiBuilder::new().max_rect(row).layout(layout));
+        child.set_clip_rect(clip);
+        header(id, &mut child);
+    }
+
+    // Forward scrolling over the headers to the timeline's tracks.
+    let delta = ui.input(|i| i.smooth_scroll_delta.y);
+    if response.hovered() && delta != 0.0 {
+        layout.set_scroll_offset(ui.ctx(), layout.scroll_offset - delta);
+    }
+    response
+}
+
+/// The side of the timeline on which a header panel is placed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The left edge, before the start of the timeline.
+    #[default]
+    Left,
+    /// The right edge, after the end of the visible timeline.
+    Right,
+}
+
+/// Split a panel of the given width from the given side of `rect`, shrinking `rect` to the
+/// remainder.
+pub(crate) fn split(rect: &mut egui::Rect, width: f32, side: Side) -> egui::Rect {
+    let mut panel = *rect;
+    match side {
+        Side::Left => {
+            panel.max.x = (rect.left() + width).min(rect.right());
+            rect.min.x = panel.right();
+        }
+        Side::Right => {
+            panel.min.x = (rect.right() - width).max(rect.left());
+            rect.max.x = panel.left();
+        }
+    }
+    panel
+}
@@ -0,0 +1,102 @@
+use super::{history, TimelineApi, TracksLayout};
+use std::ops::Range;
+
+/// The number of times per second that highlights pulse.
+pub const PULSE_HZ: f32 = 1.0;
+/// The proportion of the visible ticks shown before a highlight scrolled to via `scroll_to_next`.
+pub const LEAD_IN: f32 = 0.1;
+
+/// A highlighted region of the timeline, e.g. a search result for a clip name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Highlight {
+    /// The track to highlight, as set via `TrackCtx::id`. If `None`, the highlight spans all
+    /// tracks.
+    pub track: Option<egui::Id>,
+    /// The range of ticks relative to the start of the timeline to highlight. If `None`, the
+    /// highlight spans the whole track.
+    pub ticks: Option<Range<f32>>,
+}
+
+/// Draw a pulsing outline around each of the given highlights over the tracks.
+///
+/// Highlights for tracks that were not set with an ID are skipped. Requests a repaint while any
+/// highlight is visible in order to animate the pulse.
+pub fn paint(ui: &egui::Ui, layout: &TracksLayout, highlights: &[Highlight]) {
+    let painter = ui.painter().with_clip_rect(layout.timeline_rect);
+    let t = ui.input(|i| i.time) as f32;
+    let pulse = 0.5 + 0.5 * (t * PULSE_HZ * std::f32::consts::TAU).sin();
+    let color = ui.visuals().selection.stroke.color;
+    let stroke = egui::Stroke::new(1.0 + pulse, color.linear_multiply(0.4 + 0.6 * pulse));
+    let mut visible = false;
+    for rect in highlights.iter().filter_map(|h| rect(layout, h)) {
+        if painter.clip_rect().intersects(rect) {
+            painter.rect_stroke(rect.shrink(1.0), 2.0, stroke);
+            visible = true;
+        }
+    }
+    if visible {
+        ui.ctx().request_repaint();
+    }
+}
+
+/// Scroll to the first highlight starting after the beginning of the visible area, wrapping
+/// around to the earliest highlight if there are none.
+///
+/// The timeline is shifted via `TimelineApi::shift_timeline_start`, leaving a `LEAD_IN` before the
+/// highlight, and the tracks are scrolled vertically to reveal the highlighted track. Returns the
+/// index of the highlight scrolled to. The prior view is recorded in the navigation history.
+pub fn scroll_to_next(
+    ctx: &egui::Context,
+    layout: &TracksLayout,
+    highlights: &[Highlight],
+    timeline: &mut dyn TimelineApi,
+) -> Option<usize> {
+    let lead_in = (layout.visible_ticks.end - layout.visible_ticks.start) * LEAD_IN;
+    let start = |h: &Highlight| h.ticks.as_ref().map(|r| r.start);
+    let earliest = |a: &(usize, f32), b: &(usize, f32)| a.1.total_cmp(&b.1);
+    let starts = highlights
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, h)| start(h).map(|s| (ix, s)));
+    let (ix, tick) = starts
+        .clone()
+        .filter(|&(_, s)| s > lead_in + 0.5)
+        .min_by(earliest)
+        .or_else(|| starts.min_by(earliest))
+        .or_else(|| highlights.first().map(|_| (0, lead_in)))?;
+    history::push(ctx, layout);
+    timeline.shift_timeline_start(tick - lead_in);
+
+    // Reveal the highlighted track.
+    let track_rect = highlights[ix].track.and_then(|id| layout.track_rect(id));
+    if let Some(track_rect) = track_rect {
+        let viewport = layout.viewport;
+        let dy = if track_rect.top() < viewport.top() {
+            track_rect.top() - viewport.top()
+        } else if track_rect.bottom() > viewport.bottom() {
+            (track_rect.bottom() - viewport.bottom()).min(track_rect.top() - viewport.top())
+        } else {
+            0.0
+        };
+        layout.set_scroll_offset(ctx, layout.scroll_offset + dy);
+    }
+    ctx.request_repaint();
+    Some(ix)
+}
+
+/// The screen space rect covered by the given highlight.
+fn rect(layout: &TracksLayout, highlight: &Highlight) -> Option<egui::Rect> {
+    let y = match highlight.track {
+        None => egui::Rangef::new(layout.timeline_rect.top(), layout.tracks_bottom),
+        Some(id) => layout.track_rect(id)?.y_range(),
+    };
+    let x = match highlight.ticks {
+        None => layout.timeline_rect.x_range(),
+        Some(ref ticks) => {
+            let left = layout.timeline_rect.left();
+            let tpp = layout.ticks_per_point;
+            egui::Rangef::new(left + ticks.start / tpp, left + ticks.end / tpp)
+        }
+    };
+    Some(egui::Rect::from_x_y_ranges(x, y))
+}
@@ -0,0 +1,92 @@
+use super::{TimelineApi, TracksLayout, View};
+
+/// The maximum number of views remembered in either direction.
+pub const MAX_LEN: usize = 100;
+
+/// The views visited before and after the current one.
+#[derive(Clone, Default)]
+struct State {
+    back: Vec<View>,
+    forward: Vec<View>,
+}
+
+/// Record the current view before a significant jump, e.g. zooming to fit, going to a marker or
+/// recalling a bookmark, clearing the forward history.
+///
+/// Called by `TracksLayout::set_view`, so hosts only need to call this for jumps made via their
+/// own model.
+pub fn push(ctx: &egui::Context, layout: &TracksLayout) {
+    let view = layout.view();
+    update(ctx, layout, |state| {
+        if state.back.last() != Some(&view) {
+            state.back.push(view);
+            truncate(&mut state.back);
+        }
+        state.forward.clear();
+    });
+}
+
+/// Return to the view prior to the last jump, returning it if there was one.
+pub fn back(
+    ctx: &egui::Context,
+    layout: &TracksLayout,
+    timeline: &mut dyn TimelineApi,
+) -> Option<View> {
+    let current = layout.view();
+    let view = update(ctx, layout, |state| {
+        let view = state.back.pop()?;
+        state.forward.push(current);
+        truncate(&mut state.forward);
+        Some(view)
+    })?;
+    layout.apply_view(ctx, &view, timeline);
+    Some(view)
+}
+
+/// Redo the last jump undone via `back`, returning the view if there was one.
+pub fn forward(
+    ctx: &egui::Context,
+    layout: &TracksLayout,
+    timeline: &mut dyn TimelineApi,
+) -> Option<View> {
+    let current = layout.view();
+    let view = update(ctx, layout, |state| {
+        let view = state.forward.pop()?;
+        state.back.push(current);
+        truncate(&mut state.back);
+        Some(view)
+    })?;
+    layout.apply_view(ctx, &view, timeline);
+    Some(view)
+}
+
+/// Whether or not there is a view to go `back` to.
+pub fn can_go_back(ctx: &egui::Context, layout: &TracksLayout) -> bool {
+    let id = id(layout);
+    ctx.data(|d| d.get_temp::<State>(id))
+        .is_some_and(|s| !s.back.is_empty())
+}
+
+/// Whether or not there is a view to go `forward` to.
+pub fn can_go_forward(ctx: &egui::Context, layout: &TracksLayout) -> bool {
+    let id = id(layout);
+    ctx.data(|d| d.get_temp::<State>(id))
+        .is_some_and(|s| !s.forward.is_empty())
+}
+
+/// The ID under which the history for the given layout's timeline is stored.
+fn id(layout: &TracksLayout) -> egui::Id {
+    layout.scroll_id.with("history")
+}
+
+/// Modify the history stored for the given layout's timeline.
+fn update<T>(ctx: &egui::Context, layout: &TracksLayout, f: impl FnOnce(&mut State) -> T) -> T {
+    let id = id(layout);
+    ctx.data_mut(|d| f(d.get_temp_mut_or_default::<State>(id)))
+}
+
+/// Drop the oldest views beyond `MAX_LEN`.
+fn truncate(views: &mut Vec<View>) {
+    let excess = views.len().saturating_sub(MAX_LEN);
+    views.drain(..excess);
+}
@@ -0,0 +1,102 @@
+use super::{format::TimeFormatter, TracksLayout};
+use std::ops::Range;
+
+/// An item under the pointer, registered by a lane for display within the hover tooltip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Item {
+    /// The name of the item, e.g. a clip's name or a marker's label.
+    pub name: Option<String>,
+    /// The range of the item in ticks relative to the start of the timeline.
+    pub tick_range: Range<f32>,
+}
+
+/// State persisted between passes.
+#[derive(Clone, Debug, Default)]
+struct State {
+    /// The pass during which `items` were registered.
+    pass: u64,
+    /// The items under the pointer registered so far during the current pass.
+    items: Vec<Item>,
+    /// The last pass during which the tooltip was requested.
+    tooltip_pass: Option<u64>,
+}
+
+/// The ID under which the hover state is stored.
+fn state_id() -> egui::Id {
+    egui::Id::new("egui_timeline::hover")
+}
+
+/// Register an item occupying `rect` for display within the hover tooltip, if under the pointer.
+///
+/// Called by the clip, cue, annotation and event lanes. Custom lanes may call this so that their
+/// items are described consistently.
+pub fn register(ui: &egui::Ui, rect: egui::Rect, name: Option<&str>, tick_range: Range<f32>) {
+    let ctx = ui.ctx();
+    let hovered = ctx
+        .pointer_hover_pos()
+        .is_some_and(|pos| ui.clip_rect().intersect(rect).contains(pos));
+    if !hovered {
+        return;
+    }
+    let pass = ctx.cumulative_pass_nr();
+    let name = name.map(str::to_string);
+    ctx.data_mut(|d| {
+        let state = d.get_temp_mut_or_default::<State>(state_id());
+        if state.pass != pass {
+            state.items.clear();
+            state.pass = pass;
+        }
+        state.items.push(Item { name, tick_range });
+    });
+}
+
+/// Whether or not the hover tooltip was shown during this or the previous pass.
+///
+/// Lanes with their own tooltips defer to the hover tooltip while it is in use.
+pub fn tooltip_enabled(ctx: &egui::Context) -> bool {
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data(|d| d.get_temp::<State>(state_id()))
+        .and_then(|state| state.tooltip_pass)
+        .is_some_and(|p| p + 1 >= pass)
+}
+
+/// Show a tooltip near the pointer while hovering the timeline, describing the position under the
+/// pointer along with the name and range of any hovered items, each formatted via `formatter`.
+///
+/// The tooltip never captures the pointer and is hidden while dragging. Call this after setting
+/// all tracks so that the items they register are included.
+pub fn tooltip(ui: &egui::Ui, layout: &TracksLayout, formatter: &dyn TimeFormatter) {
+    let ctx = ui.ctx();
+    let pass = ctx.cumulative_pass_nr();
+    let state = ctx.data_mut(|d| {
+        let state = d.get_temp_mut_or_default::<State>(state_id());
+        state.tooltip_pass = Some(pass);
+        state.clone()
+    });
+    let rect = layout.timeline_rect;
+    let pos = match ctx.pointer_hover_pos() {
+        Some(pos) if rect.contains(pos) && ctx.dragged_id().is_none() => pos,
+        _ => return,
+    };
+    let tick = (pos.x - rect.left()) * layout.ticks_per_point;
+    let items = match state.pass == pass {
+        true => state.items,
+        false => vec![],
+    };
+    let id = egui::Id::new("egui_timeline::hover_tooltip");
+    egui::show_tooltip_at_pointer(ctx, ui.layer_id(), id, |ui| {
+        ui.label(formatter.format(tick));
+        for item in &items {
+            let (start, end) = (item.tick_range.start, item.tick_range.end);
+            let range = match item.tick_range.is_empty() {
+                true => formatter.format(start),
+                false => format!("{} – {}", formatter.format(start), formatter.format(end)),
+            };
+            ui.separator();
+            if let Some(name) = &item.name {
+                ui.strong(name);
+            }
+            ui.label(range);
+        }
+    });
+}
@@ -0,0 +1,169 @@
+use super::ZOOM_DELTA_SCALE;
+
+/// The sensitivity and direction of scrolling for panning and zooming, set via
+/// `Timeline::scroll_input`.
+///
+/// Trackpad and mouse wheel users often prefer very different gains, and some platforms report
+/// natural scrolling deltas.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Scroll {
+    /// Panning the timeline horizontally.
+    pub pan: Axis,
+    /// Zooming the time axis, track heights and lane values, including pinch gestures.
+    pub zoom: Axis,
+    /// How scroll deltas are read from egui's input.
+    pub deltas: Deltas,
+}
+
+/// The sensitivity and direction of scrolling for a single action.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Axis {
+    /// The multiplier applied to scroll deltas. By default, `1.0`.
+    pub speed: f32,
+    /// Whether or not to reverse the direction.
+    pub invert: bool,
+}
+
+/// How scroll deltas are read from egui's input.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Deltas {
+    /// egui's smoothed deltas, which ease each step of a mouse wheel over several frames.
+    ///
+    /// egui discards wheel deltas while both `Ctrl` and `Shift` are held, in which case the
+    /// unsmoothed delta is used instead.
+    #[default]
+    Smooth,
+    /// The deltas of this frame's mouse wheel events without smoothing, converted to points per
+    /// their unit using the given calibration.
+    Raw(Calibration),
+}
+
+/// The number of points scrolled per unit of a mouse wheel event, used by `Deltas::Raw`.
+///
+/// Wheel mice on Windows and Linux typically report lines, while trackpads report points.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Calibration {
+    /// The multiplier for deltas reported in points, e.g. by trackpads. By default, `1.0`.
+    pub point: f32,
+    /// The number of points per line, e.g. for each notch of a wheel mouse. By default, egui's
+    /// `Options::line_scroll_speed`.
+    pub line: Option<f32>,
+}
+
+impl Scroll {
+    /// The horizontal pan delta in points for this frame.
+    pub fn pan_delta(&self, ui: &egui::Ui) -> f32 {
+        let delta = match self.deltas {
+            Deltas::Smooth => ui.input(|i| i.smooth_scroll_delta.x),
+            Deltas::Raw(calibration) => {
+                // Like egui, treat vertical scrolling with `Shift` held as horizontal.
+                let shift = ui.input(|i| i.modifiers.shift);
+                let delta = calibration.wheel_delta(ui);
+                if shift {
+                    delta.x + delta.y
+                } else {
+                    delta.x
+                }
+            }
+        };
+        self.pan.apply(delta)
+    }
+
+    /// The factor by which to zoom this frame, combining scrolling and pinch gestures.
+    ///
+    /// Use this for zooming while modifiers are held, e.g. for a value axis.
+    pub fn zoom_factor(&self, ui: &egui::Ui) -> f32 {
+        let delta = match self.deltas {
+            Deltas::Smooth => ui.input(|i| {
+                let smooth = i.smooth_scroll_delta;
+                let delta = i.zoom_delta().ln() * ZOOM_DELTA_SCALE + smooth.y - smooth.x;
+                match delta == 0.0 && i.modifiers.shift {
+                    true => i.raw_scroll_delta.x + i.raw_scroll_delta.y,
+                    false => delta,
+                }
+            }),
+            Deltas::Raw(calibration) => {
+                let wheel = calibration.wheel_delta(ui);
+                pinch_delta(ui) + wheel.y - wheel.x
+            }
+        };
+        (self.zoom.apply(delta) / ZOOM_DELTA_SCALE).exp()
+    }
+
+    /// The factor by which to zoom this frame due to gestures alone, e.g. pinching or scrolling
+    /// with egui's zoom modifier held.
+    pub fn pinch_factor(&self, ui: &egui::Ui) -> f32 {
+        let delta = match self.deltas {
+            Deltas::Smooth => ui.input(|i| i.zoom_delta().ln() * ZOOM_DELTA_SCALE),
+            Deltas::Raw(_) => pinch_delta(ui),
+        };
+        (self.zoom.apply(delta) / ZOOM_DELTA_SCALE).exp()
+    }
+}
+
+impl Axis {
+    /// Apply the speed and direction to the given delta.
+    pub fn apply(&self, delta: f32) -> f32 {
+        match self.invert {
+            true => -delta * self.speed,
+            false => delta * self.speed,
+        }
+    }
+}
+
+impl Calibration {
+    /// The total delta in points of this frame's mouse wheel events.
+    pub fn wheel_delta(&self, ui: &egui::Ui) -> egui::Vec2 {
+        let line = self
+            .line
+            .unwrap_or_else(|| ui.ctx().options(|o| o.line_scroll_speed));
+        ui.input(|i| {
+            let page = i.screen_rect().height();
+            i.events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::MouseWheel { unit, delta, .. } => Some(match unit {
+                        egui::MouseWheelUnit::Point => *delta * self.point,
+                        egui::MouseWheelUnit::Line => *delta * line,
+                        egui::MouseWheelUnit::Page => *delta * page,
+                    }),
+                    _ => None,
+                })
+                .fold(egui::Vec2::ZERO, |a, b| a + b)
+        })
+    }
+}
+
+impl Default for Axis {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            invert: false,
+        }
+    }
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self {
+            point: 1.0,
+            line: None,
+        }
+    }
+}
+
+/// The zoom of this frame's pinch gestures without smoothing, as an equivalent scroll delta.
+fn pinch_delta(ui: &egui::Ui) -> f32 {
+    ui.input(|i| {
+        let touch = i.multi_touch().map_or(1.0, |touch| touch.zoom_delta);
+        let pinch: f32 = i
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                egui::Event::Zoom(factor) => Some(*factor),
+                _ => None,
+            })
+            .product();
+        (touch * pinch).ln() * ZOOM_DELTA_SCALE
+    })
+}
@@ -0,0 +1,134 @@
+use super::VisibleWindow;
+use std::{cmp::Ordering, ops::Range};
+
+/// A position type that may be used to index intervals, implemented for `f32` and `f64` ticks.
+pub trait Tick: Copy + PartialOrd + std::ops::Sub<Output = Self> {
+    /// The zero value.
+    const ZERO: Self;
+    /// A total ordering, so that entries may be sorted.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+impl Tick for f32 {
+    const ZERO: Self = 0.0;
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f32::total_cmp(self, other)
+    }
+}
+
+impl Tick for f64 {
+    const ZERO: Self = 0.0;
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+}
+
+/// An index of items by their range in ticks, for querying only those overlapping a range such as
+/// the `VisibleWindow` of a track.
+///
+/// Entries are sorted by their start and queried via binary search, bounded by the length of the
+/// longest entry. Queries are fast for content with similar lengths like clips, notes or events,
+/// regardless of the total number of entries. Ticks are `f32` by default, though `f64` may be used
+/// for long projects at high resolution.
+///
+/// Unlike an interval tree, a single very long entry widens every query, and `insert` and `remove`
+/// shift the entries after the given position. This keeps the index compact and fast to build and
+/// query, suiting content that is rebuilt or edited far less often than it is drawn.
+#[derive(Clone, Debug)]
+pub struct IntervalIndex<T, K = f32> {
+    entries: Vec<(Range<K>, T)>,
+    max_len: K,
+}
+
+impl<T, K: Tick> IntervalIndex<T, K> {
+    /// Build an index from the given items and their ranges.
+    pub fn new(items: impl IntoIterator<Item = (Range<K>, T)>) -> Self {
+        let mut entries: Vec<_> = items.into_iter().collect();
+        entries.sort_by(|a, b| a.0.start.total_cmp(&b.0.start));
+        let max_len = max_len(&entries);
+        Self { entries, max_len }
+    }
+
+    /// The total number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether or not the index contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Insert the given item with its range.
+    pub fn insert(&mut self, range: Range<K>, item: T) {
+        let ix = self
+            .entries
+            .partition_point(|(r, _)| r.start <= range.start);
+        let len = range.end - range.start;
+        if len > self.max_len {
+            self.max_len = len;
+        }
+        self.entries.insert(ix, (range, item));
+    }
+
+    /// Remove the given item previously inserted with the given range, returning whether or not
+    /// it was found.
+    pub fn remove(&mut self, range: Range<K>, item: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        let lo = self.entries.partition_point(|(r, _)| r.start < range.start);
+        let found = self.entries[lo..]
+            .iter()
+            .take_while(|(r, _)| r.start <= range.start)
+            .position(|(r, t)| *r == range && t == item);
+        let ix = match found {
+            Some(ix) => lo + ix,
+            None => return false,
+        };
+        self.entries.remove(ix);
+        if range.end - range.start >= self.max_len {
+            self.max_len = max_len(&self.entries);
+        }
+        true
+    }
+
+    /// All entries overlapping the given range, inclusive of their bounds, ordered by start.
+    pub fn query(&self, range: Range<K>) -> impl Iterator<Item = (&Range<K>, &T)> {
+        let lo = self
+            .entries
+            .partition_point(|(r, _)| r.start < range.start - self.max_len);
+        let hi = self.entries.partition_point(|(r, _)| r.start <= range.end);
+        let entries = self.entries.get(lo..hi).unwrap_or(&[]);
+        entries
+            .iter()
+            .filter(move |(r, _)| r.end >= range.start)
+            .map(|(r, t)| (r, t))
+    }
+
+    /// All entries overlapping the given visible window.
+    pub fn visible(&self, window: &VisibleWindow) -> impl Iterator<Item = (&Range<K>, &T)>
+    where
+        K: From<f32>,
+    {
+        let ticks = K::from(window.ticks.start)..K::from(window.ticks.end);
+        self.query(ticks)
+    }
+}
+
+impl<T, K: Tick> std::iter::FromIterator<(Range<K>, T)> for IntervalIndex<T, K> {
+    fn from_iter<I: IntoIterator<Item = (Range<K>, T)>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+/// The length of the longest of the given entries.
+fn max_len<T, K: Tick>(entries: &[(Range<K>, T)]) -> K {
+    entries.iter().fold(K::ZERO, |max, (r, _)| {
+        let len = r.end - r.start;
+        match len > max {
+            true => len,
+            false => max,
+        }
+    })
+}
@@ -0,0 +1,363 @@
+use super::{
+    arbiter::{self, Layer},
+    cursor::{self, Target},
+    duplicate::{self, Duplicate},
+    gesture::{self, Gesture},
+    TimelineCtx,
+};
+use std::hash::Hash;
+
+/// The default size of the keyframe diamonds.
+pub const DIAMOND_SIZE: f32 = 10.0;
+
+/// The interpolation used between a keyframe and the next.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// The value holds until the next keyframe.
+    Hold,
+    /// The value changes linearly toward the next keyframe.
+    Linear,
+    /// The value eases smoothly toward the next keyframe.
+    Smooth,
+}
+
+/// Access to the keyframes displayed within a lane.
+///
+/// Keyframes are expected to be ordered by their tick.
+pub trait Keyframes {
+    /// The total number of keyframes.
+    fn keyframe_count(&self) -> usize;
+    /// A stable ID for the keyframe at the given index, unique within the lane, used to track its
+    /// drag and selection as keyframes are added, removed or reordered.
+    fn keyframe_id(&self, index: usize) -> egui::Id;
+    /// The position of the keyframe at the given index in ticks relative to the start of the
+    /// timeline.
+    fn keyframe_tick(&self, index: usize) -> f32;
+    /// An optional value to display alongside the keyframe.
+    fn keyframe_value(&self, _index: usize) -> Option<f32> {
+        None
+    }
+    /// The interpolation between the keyframe at the given index and the next.
+    ///
+    /// By default, returns `None` in which case no segment is displayed.
+    fn interpolation(&self, _index: usize) -> Option<Interpolation> {
+        None
+    }
+    /// Whether or not the keyframe at the given index is selected.
+    fn is_selected(&self, index: usize) -> bool;
+    /// Select or deselect the keyframe at the given index.
+    fn set_selected(&mut self, index: usize, selected: bool);
+    /// The keyframe at the given index was dragged to the given tick.
+    fn set_keyframe_tick(&mut self, index: usize, tick: f32);
+    /// The keyframe at the given index is being dragged along with the rest of the selection,
+    /// with its initial and current tick.
+    fn keyframe_gesture(&mut self, _index: usize, _gesture: Gesture<f32>) {}
+    /// The selected keyframes were dragged with the duplicate modifier held, leaving them in place.
+    ///
+    /// The sources are identified by `Keyframes::keyframe_id`.
+    fn duplicate_keyframes(&mut self, _duplicate: Duplicate<egui::Id>) {}
+}
+
+/// Paint a keyframe diamond of the given size centered at the given position.
+pub fn paint_diamond(
+    painter: &egui::Painter,
+    center: egui::Pos2,
+    size: f32,
+    fill: egui::Color32,
+    stroke: egui::Stroke,
+) {
+    let r = size * 0.5;
+    let points = vec![
+        center + egui::vec2(0.0, -r),
+        center + egui::vec2(r, 0.0),
+        center + egui::vec2(0.0, r),
+        center + egui::vec2(-r, 0.0),
+    ];
+    painter.add(egui::Shape::convex_polygon(points, fill, stroke));
+}
+
+/// Instantiate a keyframe lane of the given height.
+///
+/// - Click a keyframe to select it, or `Shift`+click to toggle its selection.
+/// - Drag a keyframe to move all selected keyframes, or `Alt`/`Ctrl`+drag to duplicate them via
+///   `Keyframes::duplicate_keyframes`.
+/// - Drag over an empty area to box-select keyframes, holding `Shift` to add to the selection.
+/// - Click an empty area to clear the selection.
+///
+/// While a `selection::SelectionState` is set via `Timeline::selection`, the lane's keyframes are
+/// selected from it, and changes are written back to it.
+pub fn lane(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    api: &mut dyn Keyframes,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let w = ui.available_width();
+    let desired_size = egui::Vec2::new(w, height);
+    let (rect, _) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+    let mut response = ui.interact(rect, id, egui::Sense::click_and_drag());
+    let shift = ui.input(|i| i.modifiers.shift);
+    let count = api.keyframe_count();
+    let center_y = rect.center().y;
+    let size = DIAMOND_SIZE.min(height);
+    let keyframe_rect = |tick: f32| {
+        let center = egui::Pos2::new(timeline.tick_to_x(tick), center_y);
+        egui::Rect::from_center_size(center, egui::Vec2::splat(size))
+    };
+
+    // Apply the selection set via `Timeline::selection`, if any.
+    let selection = timeline.selection(ui.ctx());
+    if let Some(state) = &selection {
+        for ix in 0..count {
+            let selected = state.keyframes.contains(&(id, api.keyframe_id(ix)));
+            if api.is_selected(ix) != selected {
+                api.set_selected(ix, selected);
+            }
+        }
+    }
+
+    // Clear the selection when clicking an empty area.
+    if response.clicked() && !shift {
+        (0..count).for_each(|ix| api.set_selected(ix, false));
+        response.mark_changed();
+    }
+
+    // Box-select keyframes when dragging over an empty area.
+    let press_origin = ui.input(|i| i.pointer.press_origin());
+    let mut marquee = match (press_origin, response.interact_pointer_pos()) {
+        (Some(a), Some(b)) if response.dragged() => Some(egui::Rect::from_two_pos(a, b)),
+        _ => None,
+    };
+    let prior_selection_id = id.with("prior_selection");
+    if response.drag_started() {
+        let prior: Vec<egui::Id> = (0..count)
+            .filter(|&ix| api.is_selected(ix))
+            .map(|ix| api.keyframe_id(ix))
+            .collect();
+        ui.data_mut(|d| d.insert_temp(prior_selection_id, prior));
+    } else if response.drag_stopped() {
+        ui.data_mut(|d| d.remove::<Vec<egui::Id>>(prior_selection_id));
+    }
+    if gesture::cancelled(ui, &response) {
+        let prior: Vec<egui::Id> = ui
+            .data_mut(|d| d.remove_temp(prior_selection_id))
+            .unwrap_or_default();
+        for ix in 0..count {
+            let selected = prior.contains(&api.keyframe_id(ix));
+            api.set_selected(ix, selected);
+        }
+        ui.ctx().stop_dragging();
+        response.mark_changed();
+        marquee = None;
+    }
+    if let Some(marquee) = marquee {
+        if response.drag_started() && !shift {
+            (0..count).for_each(|ix| api.set_selected(ix, false));
+        }
+        for ix in 0..count {
+            let center = keyframe_rect(api.keyframe_tick(ix)).center();
+            if marquee.contains(center) && !api.is_selected(ix) {
+                api.set_selected(ix, true);
+                response.mark_changed();
+            }
+        }
+    }
+
+    // Only keyframes within the visible area need to be drawn.
+    let visible = ui.clip_rect().intersect(rect).expand2([size, 0.0].into());
+    let visible_ticks = timeline.x_to_tick(visible.left())..timeline.x_to_tick(visible.right());
+
+    // Draw the interpolation segments between keyframes.
+    let stroke = ui.visuals().widgets.noninteractive.fg_stroke;
+    for ix in 0..count.saturating_sub(1) {
+        let (a, b) = (api.keyframe_tick(ix), api.keyframe_tick(ix + 1));
+        if b < visible_ticks.start || a > visible_ticks.end {
+            continue;
+        }
+        let interp = match api.interpolation(ix) {
+            None => continue,
+            Some(interp) => interp,
+        };
+        let a = egui::Pos2::new(timeline.tick_to_x(a) + size * 0.5, center_y);
+        let b = egui::Pos2::new(timeline.tick_to_x(b) - size * 0.5, center_y);
+        if b.x <= a.x {
+            continue;
+        }
+        let mut stroke = stroke;
+        stroke.color = stroke.color.linear_multiply(0.5);
+        match interp {
+            Interpolation::Hold => {
+                let shapes = egui::Shape::dashed_line(&[a, b], stroke, 4.0, 4.0);
+                ui.painter().extend(shapes);
+            }
+            Interpolation::Linear => {
+                ui.painter().line_segment([a, b], stroke);
+            }
+            Interpolation::Smooth => {
+                stroke.width *= 2.0;
+                ui.painter().line_segment([a, b], stroke);
+            }
+        }
+    }
+
+    // Interact with and draw each keyframe.
+    let duplicate_id = id.with("duplicate");
+    let mut duplicate_offset: Option<f32> = ui.data(|d| d.get_temp(duplicate_id));
+    let mut drag_delta = None;
+    let mut grabbed = None;
+    for ix in 0..count {
+        // Always interact with a dragged keyframe so that its gesture ends, even out of view.
+        let tick = api.keyframe_tick(ix);
+        let k_id = id.with(api.keyframe_id(ix));
+        if !visible_ticks.contains(&tick) && !ui.ctx().is_being_dragged(k_id) {
+            continue;
+        }
+        let k_rect = keyframe_rect(tick);
+        let sense = egui::Sense::click_and_drag();
+        let sense = arbiter::sense(ui, Layer::Handle, k_rect, k_id, sense);
+        let k_response = ui.interact(k_rect, k_id, sense);
+        cursor::set(&k_response, Target::Move);
+        if k_response.clicked() {
+            if shift {
+                api.set_selected(ix, !api.is_selected(ix));
+            } else {
+                (0..count).for_each(|i| api.set_selected(i, i == ix));
+            }
+            response.mark_changed();
+        }
+        if k_response.drag_started() && !api.is_selected(ix) {
+            if !shift {
+                (0..count).for_each(|i| api.set_selected(i, false));
+            }
+            api.set_selected(ix, true);
+            response.mark_changed();
+        }
+        if k_response.drag_started() && duplicate::modifier_held(ui) {
+            duplicate_offset = Some(0.0);
+        }
+        if k_response.dragged() {
+            drag_delta = Some(k_response.drag_delta().x * timeline.ticks_per_point);
+        }
+        if k_response.dragged() || k_response.drag_stopped() {
+            grabbed = Some((ix, tick, k_response.clone()));
+        }
+
+        // Draw the diamond.
+        let visuals = ui.style().interact(&k_response);
+        let fill = if api.is_selected(ix) {
+            ui.visuals().selection.bg_fill
+        } else {
+            visuals.fg_stroke.color
+        };
+        paint_diamond(ui.painter(), k_rect.center(), size, fill, visuals.bg_stroke);
+
+        // Draw the value if there is one.
+        if let Some(value) = api.keyframe_value(ix) {
+            let pos = k_rect.right_center() + egui::vec2(2.0, 0.0);
+            let font_id = egui::TextStyle::Small.resolve(ui.style());
+            let text = format!("{:.2}", value);
+            let color = visuals.text_color();
+            ui.painter()
+                .text(pos, egui::Align2::LEFT_CENTER, text, font_id, color);
+        }
+    }
+
+    // When duplicating, accumulate the offset and draw ghosts rather than moving the keyframes.
+    if let Some(offset) = duplicate_offset {
+        let selected: Vec<usize> = (0..count).filter(|&ix| api.is_selected(ix)).collect();
+        let min_tick = selected
+            .iter()
+            .map(|&ix| api.keyframe_tick(ix))
+            .fold(f32::INFINITY, f32::min);
+        let offset = (offset + drag_delta.unwrap_or(0.0)).max(-min_tick);
+        let fill = ui.visuals().selection.bg_fill.linear_multiply(0.5);
+        let stroke = egui::Stroke::new(1.0, fill);
+        for &ix in &selected {
+            let center = keyframe_rect(api.keyframe_tick(ix) + offset).center();
+            paint_diamond(ui.painter(), center, size, fill, stroke);
+        }
+        match grabbed {
+            Some((_, _, k_response)) if gesture::cancelled(ui, &k_response) => {
+                ui.data_mut(|d| d.remove::<f32>(duplicate_id));
+                ui.ctx().stop_dragging();
+            }
+            Some((_, tick, k_response)) if k_response.drag_stopped() => {
+                ui.data_mut(|d| d.remove::<f32>(duplicate_id));
+                let locked = selected.iter().any(|&ix| {
+                    let target = api.keyframe_tick(ix) + offset;
+                    timeline.is_locked(target..target)
+                });
+                let pointer = k_response.interact_pointer_pos();
+                if let Some(pointer) = pointer.filter(|_| offset != 0.0 && !locked) {
+                    api.duplicate_keyframes(Duplicate {
+                        sources: selected.iter().map(|&ix| api.keyframe_id(ix)).collect(),
+                        delta: offset,
+                        tick: tick + offset,
+                        pointer,
+                    });
+                    response.mark_changed();
+                }
+            }
+            Some(_) => ui.data_mut(|d| d.insert_temp(duplicate_id, offset)),
+            None => ui.data_mut(|d| d.remove::<f32>(duplicate_id)),
+        }
+    } else {
+        // Move all selected keyframes by the drag delta, unless any would move from or into a
+        // locked range.
+        let moved_tick = |ix: usize| (api.keyframe_tick(ix) + drag_delta.unwrap_or(0.0)).max(0.0);
+        let locked = (0..count).filter(|&ix| api.is_selected(ix)).any(|ix| {
+            let (a, b) = (api.keyframe_tick(ix), moved_tick(ix));
+            timeline.is_locked(a.min(b)..a.max(b))
+        });
+        if let Some(delta) = drag_delta.filter(|_| !locked) {
+            if delta != 0.0 {
+                for ix in 0..count {
+                    if api.is_selected(ix) {
+                        let tick = (api.keyframe_tick(ix) + delta).max(0.0);
+                        api.set_keyframe_tick(ix, tick);
+                    }
+                }
+                response.mark_changed();
+            }
+        }
+        if let Some((ix, before, k_response)) = grabbed {
+            if let Some(gesture) = gesture::track(ui, &k_response, before, api.keyframe_tick(ix)) {
+                // Restore the whole selection by the grabbed keyframe's offset.
+                if let Gesture::Cancelled { initial } = gesture {
+                    let delta = initial - api.keyframe_tick(ix);
+                    for ix in 0..count {
+                        if api.is_selected(ix) {
+                            let tick = api.keyframe_tick(ix) + delta;
+                            api.set_keyframe_tick(ix, tick);
+                        }
+                    }
+                }
+                api.keyframe_gesture(ix, gesture);
+            }
+        }
+    }
+
+    // Draw the marquee.
+    if let Some(marquee) = marquee {
+        let fill = ui.visuals().selection.bg_fill.linear_multiply(0.25);
+        let stroke = ui.visuals().selection.stroke;
+        ui.painter().rect(marquee, 0.0, fill, stroke);
+    }
+
+    // Write any changes back to the selection.
+    if let Some(mut state) = selection {
+        let prev = state.clone();
+        state.keyframes.retain(|&(lane, _)| lane != id);
+        let selected = (0..count).filter(|&ix| api.is_selected(ix));
+        state
+            .keyframes
+            .extend(selected.map(|ix| (id, api.keyframe_id(ix))));
+        if state != prev {
+            timeline.set_selection(ui.ctx(), state);
+        }
+    }
+
+    response
+}
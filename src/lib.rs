@@ -1,25 +1,111 @@
 use egui_plot as plot;
 use std::{
+    cell::{Cell, RefCell},
     hash::Hash,
     ops::{Range, RangeInclusive},
 };
 
+pub use clip::Clip;
+pub use detail::DetailLevel;
 pub use playhead::Playhead;
 pub use ruler::MusicalRuler;
+pub use style::TimelineStyle;
+pub use time::TimeDomain;
 
+/// Open a `puffin` profiling scope for the rest of the enclosing block when the `profile` feature
+/// is enabled.
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(all(feature = "profile", not(target_arch = "wasm32")))]
+        puffin::profile_scope!($name);
+    };
+}
+
+pub mod annotation;
+pub mod arbiter;
+pub mod automation;
+pub mod bookmark;
+mod cache;
+pub mod clip;
+pub mod cue;
+pub mod cursor;
+pub mod curve;
+pub mod dependency;
+pub mod detail;
+pub mod dope_sheet;
+pub mod drum;
+pub mod duplicate;
+pub mod event;
+pub mod feed;
+pub mod filmstrip;
+pub mod format;
+pub mod gesture;
+pub mod goto;
+pub mod header;
+pub mod highlight;
+pub mod history;
+pub mod hover;
+pub mod input;
+pub mod intervals;
+pub mod keyframe;
+pub mod loop_region;
+#[cfg(feature = "midi")]
+pub mod midi;
+pub mod note;
+pub mod piano_roll;
 pub mod playhead;
+pub mod progress;
+pub mod readout;
+pub mod recording;
+pub mod repaint;
+pub mod ripple;
 pub mod ruler;
+pub mod scrub;
+pub mod selection;
+pub mod source;
+pub mod style;
+pub mod tempo;
+pub mod time;
+pub mod time_edit;
+pub mod touch;
+pub mod view;
+pub mod waveform;
+pub mod zoom;
 
 pub const MIN_STEP_GAP: f32 = 4.0;
+/// The default maximum number of steps drawn by the grid or a ruler per frame.
+pub const MAX_STEPS: usize = 2048;
+/// Converts a zoom factor from a pinch gesture or `Ctrl` + scroll into an equivalent
+/// `TimelineApi::zoom` scroll delta, matching egui's default scroll zoom speed.
+pub const ZOOM_DELTA_SCALE: f32 = 200.0;
+/// The width of the accent stripe drawn along the left of a colored track's header.
+pub const TRACK_COLOR_STRIPE_WIDTH: f32 = 3.0;
 
 /// The implementation required to instantiate a timeline widget.
 pub trait TimelineApi {
-    /// Access to the ruler info.
-    fn musical_ruler_info(&self) -> &dyn ruler::MusicalInfo;
-    /// Shift the timeline start by the given number of ticks due to a scroll event.
+    /// Access to the time domain over which the timeline is laid out.
+    ///
+    /// For musical timelines, this is typically the `ruler::MusicalInfo` implementation.
+    fn time_domain(&self) -> &dyn TimeDomain;
+    /// Shift the timeline start by the given number of units (e.g. ticks) due to a scroll event.
     fn shift_timeline_start(&mut self, ticks: f32);
     /// The timeline was scrolled with with `Ctrl` held down to zoom in/out.
+    ///
+    /// Unless the `ZoomAnchor` is `Start`, the view is then shifted via `shift_timeline_start` to
+    /// keep the anchor fixed on screen.
     fn zoom(&mut self, y_delta: f32);
+    /// The absolute tick at the start (left) of the visible area, reported via
+    /// `TracksLayout::view_changed`.
+    ///
+    /// Returns `None` by default.
+    fn timeline_start(&self) -> Option<f32> {
+        None
+    }
+    /// Set the number of ticks per point, e.g. to preserve the visible range when the timeline is
+    /// resized with `Resize::PreserveRange`.
+    ///
+    /// Does nothing by default.
+    fn set_ticks_per_point(&mut self, _ticks_per_point: f32) {}
 }
 
 #[derive(Clone, Debug)]
@@ -30,7 +116,7 @@ pub struct Bar {
     pub time_sig: TimeSig,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct TimeSig {
     pub top: u16,
     pub bottom: u16,
@@ -41,21 +127,154 @@ impl TimeSig {
     pub fn beats_per_bar(&self) -> f32 {
         4.0 * self.top as f32 / self.bottom as f32
     }
+
+    /// Whether or not this is a compound meter (e.g. 6/8, 9/8, 12/8), where beats are grouped
+    /// into threes.
+    pub fn is_compound(&self) -> bool {
+        self.top > 3 && self.top % 3 == 0
+    }
+
+    /// The metric strength of the position `ticks_in_bar` from the start of the bar.
+    ///
+    /// Returns `0` for the downbeat, `1` for a strong beat mid-bar (e.g. beat 3 of 4/4 or the
+    /// third dotted-quarter of 12/8), `2` for all other beats and `3` for subdivisions.
+    pub fn metric_level(&self, ticks_per_beat: f32, ticks_in_bar: f32) -> u8 {
+        let unit_ticks = ticks_per_beat * 4.0 / self.bottom as f32;
+        let (beat_ticks, beats) = match self.is_compound() {
+            true => (unit_ticks * 3.0, self.top / 3),
+            false => (unit_ticks, self.top),
+        };
+        let beat = ticks_in_bar / beat_ticks;
+        let index = beat.round();
+        if (beat - index).abs() > 1e-3 {
+            return 3;
+        }
+        match index as u16 {
+            0 => 0,
+            i if beats > 2 && beats % 2 == 0 && i == beats / 2 => 1,
+            _ => 2,
+        }
+    }
 }
 
 /// The top-level timeline widget.
 pub struct Timeline {
+    /// Distinguishes the state of this timeline from others within the same parent `ui`.
+    id_source: Option<egui::Id>,
     /// A optional side panel with track headers.
     ///
     /// Can be useful for labelling tracks or providing convenient volume, mute, solo, etc style
     /// widgets.
     header: Option<f32>,
+    /// The side on which the `header` panel is placed.
+    header_side: header::Side,
+    /// An optional additional header panel on the right, e.g. for meters.
+    header_right: Option<f32>,
+    /// Which parts of the timeline are locked against interaction.
+    lock: Lock,
+    /// Ranges in ticks that may not be edited.
+    locked_ranges: Vec<Range<f32>>,
+    /// Whether deleting or trimming a clip shifts the following clips, and which.
+    ripple: Option<ripple::Scope>,
+    /// The selection owned by the host, if the timeline should read and update it.
+    selection: Option<selection::SelectionState>,
+    /// Visual configuration for the timeline.
+    style: TimelineStyle,
+    /// Shade alternating groups of this many bars within `Show::paint_grid`.
+    shade_bars: Option<u32>,
+    /// The zoom levels at which content switches to a coarser `DetailLevel`.
+    detail_thresholds: detail::Thresholds,
+    /// How the view responds to changes in the width of the timeline.
+    resize: Resize,
+    /// The position that remains fixed on screen while zooming.
+    zoom_anchor: ZoomAnchor,
+    /// Discrete zoom levels stepped through with the `+` and `-` keys.
+    zoom_levels: Option<zoom::Ladder>,
+    /// Scaling the height of all tracks with a modifier + scroll.
+    track_zoom: Option<TrackZoom>,
+    /// The modifiers held while scrolling to zoom the time and value axes.
+    zoom_modifiers: zoom::Modifiers,
+    /// The modifiers held while dragging a clip's edge to stretch it.
+    stretch_modifiers: egui::Modifiers,
+    /// The sensitivity and direction of scrolling.
+    scroll_input: input::Scroll,
+    /// The height to allocate, or all available height if `None`.
+    desired_height: Option<f32>,
+    /// The minimum width to allocate, even if less is available.
+    min_width: f32,
+}
+
+/// Describes which parts of the timeline are locked against interaction.
+///
+/// Locked elements are still drawn as usual, but never mutate the host's state. Useful for
+/// viewer-only contexts like a master clock display or a bounced arrangement preview.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Lock {
+    /// Clicking the ruler. Applied by rulers whose API forwards it via `interactive`.
+    pub ruler: bool,
+    /// Dragging, clicking or editing the playhead.
+    pub playhead: bool,
+    /// Zooming with `Ctrl` + scroll.
+    pub zoom: bool,
+    /// Scrolling the timeline horizontally.
+    pub scroll: bool,
+}
+
+/// How the view responds when the width of the timeline changes, e.g. due to a resized panel or
+/// window.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Resize {
+    /// Preserve the zoom level, revealing or hiding ticks at the end of the view.
+    #[default]
+    PreserveZoom,
+    /// Preserve the range of visible ticks by rescaling the ticks per point via
+    /// `TimelineApi::set_ticks_per_point`.
+    ///
+    /// Has no effect unless the host implements `TimelineApi::set_ticks_per_point`, which does
+    /// nothing by default.
+    PreserveRange,
+}
+
+/// The position that remains fixed on screen while zooming with `Ctrl` + scroll or a pinch.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum ZoomAnchor {
+    /// Keep the start of the view fixed, revealing or hiding ticks at the end.
+    #[default]
+    Start,
+    /// Keep the tick under the pointer fixed.
+    Pointer,
+    /// Keep the given tick relative to the start of the timeline fixed, e.g. the playhead so that
+    /// it stays put while zooming during playback.
+    Tick(f32),
+}
+
+/// Configuration for scaling the height of all tracks, via `Timeline::track_zoom`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackZoom {
+    /// The modifiers that must be held while scrolling or pinching. By default, `Ctrl` + `Shift`.
+    pub modifiers: egui::Modifiers,
+    /// The range within which the scale is clamped. By default, `0.25..=4.0`.
+    pub scale_range: RangeInclusive<f32>,
 }
 
 /// The result of setting the timeline, ready to start laying out tracks.
 pub struct Show {
+    /// The ID of the timeline, under which its state is stored between frames.
+    id: egui::Id,
     tracks: TracksCtx,
     ui: egui::Ui,
+    shade_bars: Option<u32>,
+    /// The height reserved for tracks pinned to the bottom.
+    bottom_height: f32,
+    /// Whether or not the view was scrolled or zoomed this frame.
+    view_changed: bool,
+    /// The absolute tick at the start of the visible area, if provided by the `TimelineApi`.
+    timeline_start: Option<f32>,
+    /// The ratio by which the track heights were scaled this frame, along with the pointer's y
+    /// position that should remain over the same row.
+    track_scale_change: Option<(f32, f32)>,
+    /// The selection set via `Timeline::selection`, compared against once tracks are laid out.
+    selection: Option<selection::SelectionState>,
 }
 
 /// A context for instantiating tracks, either pinned or unpinned.
@@ -63,29 +282,179 @@ pub struct TracksCtx {
     /// The rectangle encompassing the entire widget area including both header and timeline and
     /// both pinned and unpinned track areas.
     pub full_rect: egui::Rect,
-    /// The rect encompassing the left-hand-side track headers including pinned and unpinned.
+    /// The rect encompassing the track headers including pinned and unpinned.
     pub header_full_rect: Option<egui::Rect>,
+    /// The rect encompassing the right-hand-side track headers set via `Timeline::header_right`.
+    pub header_right_full_rect: Option<egui::Rect>,
     /// Context specific to the timeline (non-header) area.
     pub timeline: TimelineCtx,
+    /// The rects of tracks set with an ID during the current frame.
+    track_rects: RefCell<Vec<(egui::Id, egui::Rect)>>,
+    /// Placeholders for dimming inaudible tracks, filled once all tracks have been set.
+    audibility: RefCell<Vec<TrackAudibility>>,
+}
+
+/// The mute and solo state of a track, along with a placeholder for its overlay.
+struct TrackAudibility {
+    painter: egui::Painter,
+    overlay: egui::layers::ShapeIdx,
+    rect: egui::Rect,
+    muted: bool,
+    soloed: bool,
 }
 
 /// Some context for the timeline, providing short-hand for setting some useful widgets.
+#[derive(Clone, Debug)]
 pub struct TimelineCtx {
     /// The total visible rect of the timeline area including pinned and unpinned tracks.
     pub full_rect: egui::Rect,
     /// The total number of ticks visible on the timeline area.
     pub visible_ticks: f32,
+    /// The number of ticks per point along the x axis.
+    pub ticks_per_point: f32,
+    /// The absolute tick at the start of the visible area, if provided via
+    /// `TimelineApi::timeline_start`.
+    pub timeline_start: Option<f32>,
+    /// Which parts of the timeline are locked against interaction.
+    pub lock: Lock,
+    /// Ranges in ticks relative to the start of the timeline that may not be edited.
+    pub locked_ranges: Vec<Range<f32>>,
+    /// Whether or not the track currently being set is locked against edits.
+    pub track_locked: bool,
+    /// The base color of the track currently being set, used as the default clip color.
+    pub track_color: Option<egui::Color32>,
+    /// Whether or not the track currently being set is bypassed via `TrackCtx::bypass`.
+    pub track_bypassed: bool,
+    /// The ID of the track currently being set, if set via `TrackCtx::id`.
+    pub track_id: Option<egui::Id>,
+    /// The clips shifted when a clip is deleted or its end is stretched, if ripple editing is
+    /// enabled via `Timeline::ripple`.
+    pub ripple: Option<ripple::Scope>,
+    /// The ID under which the selection set via `Timeline::selection` is stored while laid out,
+    /// if any. See `TimelineCtx::selection`.
+    pub selection_id: Option<egui::Id>,
+    /// The ID under which the finest step drawn by `Show::paint_grid` is stored. See
+    /// `TimelineCtx::grid_step`.
+    pub grid_id: egui::Id,
+    /// Visual configuration for the timeline.
+    pub style: TimelineStyle,
+    /// How much detail content should be drawn with at the current zoom level.
+    pub detail: DetailLevel,
+    /// The factor by which tracks should scale their height, set via `Timeline::track_zoom`.
+    /// Otherwise `1.0`.
+    pub track_scale: f32,
+    /// The modifiers held while scrolling to zoom the time and value axes.
+    pub zoom_modifiers: zoom::Modifiers,
+    /// The modifiers held while dragging a clip's edge to stretch it, set via
+    /// `Timeline::stretch_modifiers`.
+    pub stretch_modifiers: egui::Modifiers,
+    /// The sensitivity and direction of scrolling.
+    pub scroll_input: input::Scroll,
+}
+
+/// The portion of the timeline visible within a track, returned by `TimelineCtx::visible_window`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VisibleWindow {
+    /// The visible range in ticks relative to the start of the timeline.
+    pub ticks: Range<f32>,
+    /// The visible area in screen space.
+    pub rect: egui::Rect,
 }
 
-/// Context for instantiating the playhead after all tracks have been set.
-pub struct SetPlayhead {
-    timeline_rect: egui::Rect,
+/// The position and scale of the view, reported via `TracksLayout::view_changed`.
+///
+/// Useful for mirroring the view to other components such as a video scrubber or an external
+/// control surface.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct View {
+    /// The absolute tick at the start of the visible area, if provided via
+    /// `TimelineApi::timeline_start`.
+    pub start_tick: Option<f32>,
+    /// The number of ticks per point along the x axis.
+    pub ticks_per_point: f32,
+    /// The vertical scroll offset of the track area.
+    pub scroll_offset: f32,
+}
+
+/// Layout information about the track area, returned after all tracks have been set.
+///
+/// Useful for positioning the playhead and other overlays relative to the actual content.
+#[derive(Clone, Debug)]
+pub struct TracksLayout {
+    /// The ID of the timeline, under which its state is stored between frames.
+    pub id: egui::Id,
+    /// The total visible rect of the timeline area including pinned and unpinned tracks.
+    pub timeline_rect: egui::Rect,
+    /// The visible rect of the scrollable track area in screen space.
+    pub viewport: egui::Rect,
+    /// The range of ticks visible across the width of the timeline.
+    pub visible_ticks: Range<f32>,
+    /// The number of ticks per point along the x axis.
+    pub ticks_per_point: f32,
+    /// The total height of the laid out tracks, including those scrolled out of view.
+    pub content_height: f32,
+    /// The vertical scroll offset of the track area.
+    pub scroll_offset: f32,
+    /// The absolute tick at the start of the visible area, if provided via
+    /// `TimelineApi::timeline_start`.
+    pub timeline_start: Option<f32>,
+    /// The y position of the bottom of the laid out tracks, clamped to the viewport.
+    pub tracks_bottom: f32,
+    /// The screen space rect of each track that was given an ID via `TrackCtx::id`, in the order
+    /// in which they were set.
+    pub track_rects: Vec<(egui::Id, egui::Rect)>,
+    /// The indices within `track_rects` of the tracks set within the scrolling area.
+    pub scrolled_tracks: Range<usize>,
+    /// The ID of the scroll area containing the scrolling tracks.
+    pub scroll_id: egui::Id,
+    /// Which parts of the timeline are locked against interaction.
+    pub lock: Lock,
+    /// The new view if it was scrolled, zoomed or scrolled vertically since the last frame,
+    /// including the first frame it was shown.
+    pub view_changed: Option<View>,
+    /// The new selection if it was changed by the widgets within the tracks, e.g. by clicking a
+    /// clip. Only reported if set via `Timeline::selection`.
+    pub selection_changed: Option<selection::SelectionState>,
+    /// What changed during this frame, updated as overlays like the playhead are set.
+    repaint: Cell<repaint::Repaint>,
+    /// Visual configuration for the timeline, shared with overlays like the playhead.
+    style: TimelineStyle,
 }
 
 impl Timeline {
     /// Begin building the timeline widget.
     pub fn new() -> Self {
-        Self { header: None }
+        Self {
+            id_source: None,
+            header: None,
+            header_side: header::Side::Left,
+            header_right: None,
+            lock: Lock::default(),
+            locked_ranges: vec![],
+            ripple: None,
+            selection: None,
+            style: TimelineStyle::default(),
+            shade_bars: None,
+            detail_thresholds: detail::Thresholds::default(),
+            resize: Resize::default(),
+            zoom_anchor: ZoomAnchor::default(),
+            zoom_levels: None,
+            track_zoom: None,
+            zoom_modifiers: zoom::Modifiers::default(),
+            stretch_modifiers: egui::Modifiers::ALT,
+            scroll_input: input::Scroll::default(),
+            desired_height: None,
+            min_width: 0.0,
+        }
+    }
+
+    /// A source for the ID under which the timeline's state is stored between frames, e.g. its
+    /// scroll position and track scale. By default, the ID of the parent `ui` is used.
+    ///
+    /// Required to distinguish multiple timelines shown within the same parent `ui`.
+    pub fn id_source(mut self, id_source: impl Hash) -> Self {
+        self.id_source = Some(egui::Id::new(id_source));
+        self
     }
 
     /// A optional track header side panel.
@@ -97,35 +466,283 @@ impl Timeline {
         self
     }
 
-    /// Set the timeline within the currently available rect.
+    /// The side of the timeline on which the `header` panel is placed. By default, `Side::Left`.
+    ///
+    /// If placed on the right alongside a `header_right` panel, the `header` panel is outermost.
+    pub fn header_side(mut self, side: header::Side) -> Self {
+        self.header_side = side;
+        self
+    }
+
+    /// An additional track header panel on the right of the timeline, set for each track via
+    /// `TrackCtx::header_right`.
+    ///
+    /// Useful for mixer-style layouts, e.g. names on the left with meters on the right.
+    pub fn header_right(mut self, width: f32) -> Self {
+        self.header_right = Some(width);
+        self
+    }
+
+    /// Whether or not the timeline may be interacted with. By default, this is `true`.
+    ///
+    /// Passing `false` locks the ruler, playhead, zoom and scroll while still rendering them.
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        let locked = !interactive;
+        self.lock = Lock {
+            ruler: locked,
+            playhead: locked,
+            zoom: locked,
+            scroll: locked,
+        };
+        self
+    }
+
+    /// Visual configuration for the timeline.
+    pub fn style(mut self, style: TimelineStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Shade alternating groups of `every` bars across the track area within `Show::paint_grid`,
+    /// e.g. `1` shades every other bar. The color is `TimelineStyle::bar_shading`.
+    ///
+    /// Only applies to musical time domains.
+    pub fn shade_bars(mut self, every: u32) -> Self {
+        self.shade_bars = Some(every.max(1));
+        self
+    }
+
+    /// The ticks per point at which track and clip content switches to a coarser `DetailLevel`.
+    ///
+    /// The resulting level is available via `TimelineCtx::detail`.
+    pub fn detail_thresholds(mut self, thresholds: detail::Thresholds) -> Self {
+        self.detail_thresholds = thresholds;
+        self
+    }
+
+    /// How the view responds when the width of the timeline changes. By default, the zoom level
+    /// is preserved.
+    ///
+    /// `Resize::PreserveRange` requires implementing `TimelineApi::set_ticks_per_point`.
+    pub fn resize(mut self, resize: Resize) -> Self {
+        self.resize = resize;
+        self
+    }
+
+    /// The position that remains fixed on screen while zooming. By default, the start of the view.
+    pub fn zoom_anchor(mut self, anchor: ZoomAnchor) -> Self {
+        self.zoom_anchor = anchor;
+        self
+    }
+
+    /// Step through the given discrete zoom levels with the `+` and `-` keys while the pointer is
+    /// over the timeline, zooming about the `zoom_anchor`. By default, keyboard zoom is disabled.
+    pub fn zoom_levels(mut self, ladder: zoom::Ladder) -> Self {
+        self.zoom_levels = Some(ladder);
+        self
+    }
+
+    /// The modifiers held while scrolling to zoom the time axis, or the value axis of lanes using
+    /// `zoom::value_viewport`. By default, `Ctrl` and `Ctrl` + `Shift` respectively.
+    pub fn zoom_modifiers(mut self, modifiers: zoom::Modifiers) -> Self {
+        self.zoom_modifiers = modifiers;
+        self
+    }
+
+    /// The modifiers held while dragging a clip's edge to stretch it, e.g. `Alt` also matches
+    /// `Alt` + `Shift`. By default, `Alt`.
+    pub fn stretch_modifiers(mut self, modifiers: egui::Modifiers) -> Self {
+        self.stretch_modifiers = modifiers;
+        self
+    }
+
+    /// The sensitivity and direction of scrolling to pan and zoom. By default, deltas are used as
+    /// reported by egui.
+    pub fn scroll_input(mut self, scroll: input::Scroll) -> Self {
+        self.scroll_input = scroll;
+        self
+    }
+
+    /// Scale the height of all tracks by scrolling or pinching with the given modifiers held over
+    /// the timeline, keeping the row under the pointer in place. By default, disabled.
+    ///
+    /// Tracks are expected to multiply their heights by `TimelineCtx::track_scale`.
+    pub fn track_zoom(mut self, track_zoom: TrackZoom) -> Self {
+        self.track_zoom = Some(track_zoom);
+        self
+    }
+
+    /// The height to allocate for the whole widget. By default, all available height is used.
+    ///
+    /// Useful when composing the timeline within grids, windows or split panes.
+    pub fn desired_height(mut self, height: f32) -> Self {
+        self.desired_height = Some(height);
+        self
+    }
+
+    /// The minimum width to allocate for the whole widget, even if less is available.
+    pub fn min_width(mut self, width: f32) -> Self {
+        self.min_width = width;
+        self
+    }
+
+    /// Lock specific parts of the timeline against interaction.
+    pub fn lock(mut self, lock: Lock) -> Self {
+        self.lock = lock;
+        self
+    }
+
+    /// Ranges in ticks relative to the start of the timeline that may not be edited.
+    ///
+    /// Widgets refuse drags and edits that would modify content within these ranges, which are
+    /// drawn with a hatched lock pattern over each track. Useful for collaborative editing or
+    /// frozen regions.
+    pub fn locked_ranges(mut self, ranges: impl IntoIterator<Item = Range<f32>>) -> Self {
+        self.locked_ranges = ranges.into_iter().collect();
+        self
+    }
+
+    /// Enable ripple editing, in which deleting a clip or stretching its end shifts the following
+    /// clips within the given scope. By default, disabled.
+    ///
+    /// Clips have no separate trim gesture, so stretching the end stands in for trimming it. The
+    /// shift is previewed during the edit and emitted via `clip::Interaction::ripple`.
+    pub fn ripple(mut self, scope: Option<ripple::Scope>) -> Self {
+        self.ripple = scope;
+        self
+    }
+
+    /// The selection owned by the host, read and updated by clips and keyframe lanes while laid
+    /// out. By default, selection is left entirely to the host.
+    ///
+    /// The updated selection is reported via `TracksLayout::selection_changed`. Pass a different
+    /// state to change the selection programmatically, e.g. via a host command.
+    pub fn selection(mut self, state: selection::SelectionState) -> Self {
+        self.selection = Some(state);
+        self
+    }
+
+    /// Set the timeline within the currently available rect, or the size given via
+    /// `desired_height` and `min_width`.
+    ///
+    /// The rect is allocated within the `ui` so that widgets added afterwards are laid out after
+    /// the timeline.
     pub fn show(self, ui: &mut egui::Ui, timeline: &mut dyn TimelineApi) -> Show {
-        // The full area including both headers and timeline.
-        let full_rect = ui.available_rect_before_wrap();
+        profile_scope!("timeline");
+        let id = match self.id_source {
+            Some(id_source) => ui.make_persistent_id(id_source),
+            None => ui.id(),
+        };
+        // The full area including both headers and timeline, allocated within the parent `ui`.
+        let available = ui.available_rect_before_wrap();
+        let size = egui::vec2(
+            available.width().max(self.min_width),
+            self.desired_height.unwrap_or(available.height()),
+        );
+        let full_rect = egui::Rect::from_min_size(available.min, size);
+        ui.advance_cursor_after_rect(full_rect);
         // The area occupied by the timeline.
         let mut timeline_rect = full_rect;
-        // The area occupied by track headers.
-        let header_rect = self.header.map(|header_w| {
-            let mut r = full_rect;
-            r.set_width(header_w);
-            timeline_rect.min.x = r.right();
-            r
-        });
+        // The areas occupied by track headers.
+        let header_rect = self
+            .header
+            .map(|w| header::split(&mut timeline_rect, w, self.header_side));
+        let header_right_rect = self
+            .header_right
+            .map(|w| header::split(&mut timeline_rect, w, header::Side::Right));
+
+        // Lanes zooming their value axis take precedence over zooming the timeline.
+        let value_mods = self.zoom_modifiers.value;
+        let value_zooming =
+            ui.input(|i| i.modifiers.matches_exact(value_mods)) && zoom::over_value_lane(ui);
+
+        // Scale the track heights if the track zoom modifiers are held.
+        let track_scale_id = id.with("track_scale");
+        let mut track_scale: f32 = ui.data(|d| d.get_temp(track_scale_id)).unwrap_or(1.0);
+        let mut track_scale_change = None;
+        let track_zoom = self
+            .track_zoom
+            .as_ref()
+            .filter(|z| ui.input(|i| i.modifiers.matches_exact(z.modifiers)))
+            .filter(|_| !value_zooming);
+        if let Some(track_zoom) = track_zoom.filter(|_| ui.rect_contains_pointer(full_rect)) {
+            let factor = self.scroll_input.zoom_factor(ui);
+            let pointer = ui.input(|i| i.pointer.hover_pos());
+            let (min, max) = (
+                *track_zoom.scale_range.start(),
+                *track_zoom.scale_range.end(),
+            );
+            let scale = (track_scale * factor).clamp(min, max);
+            if let Some(pos) = pointer.filter(|_| scale != track_scale) {
+                track_scale_change = Some((scale / track_scale, pos.y));
+                track_scale = scale;
+                ui.data_mut(|d| d.insert_temp(track_scale_id, scale));
+            }
+        }
 
         // Check whether or not we should scroll the timeline or zoom.
-        if ui.rect_contains_pointer(timeline_rect) {
-            let delta = ui.input(|i| i.smooth_scroll_delta);
-            if ui.input(|i| i.raw.modifiers.ctrl) {
-                if delta.x != 0.0 || delta.y != 0.0 {
-                    timeline.zoom(delta.y - delta.x);
-                }
+        let mut view_changed = false;
+        if !value_zooming && track_zoom.is_none() && ui.rect_contains_pointer(timeline_rect) {
+            // The anchor's offset in points from the start of the view.
+            let anchor_x = match self.zoom_anchor {
+                ZoomAnchor::Start => None,
+                ZoomAnchor::Pointer => ui
+                    .input(|i| i.pointer.hover_pos())
+                    .map(|pos| pos.x - timeline_rect.left()),
+                ZoomAnchor::Tick(tick) => Some(tick / timeline.time_domain().units_per_point()),
+            };
+            // Pinch gestures and `Ctrl` + scroll, including on the web, are reported by egui as a
+            // zoom factor rather than a scroll delta, so they zoom with or without modifiers.
+            let scroll = self.scroll_input;
+            let time_mods = self.zoom_modifiers.time;
+            let factor = if ui.input(|i| i.modifiers.contains(time_mods)) {
+                scroll.zoom_factor(ui)
             } else {
-                if delta.x != 0.0 {
-                    let ticks_per_point = timeline.musical_ruler_info().ticks_per_point();
-                    timeline.shift_timeline_start(delta.x * ticks_per_point);
+                let pan_delta = scroll.pan_delta(ui);
+                if !self.lock.scroll && pan_delta != 0.0 {
+                    let ticks_per_point = timeline.time_domain().units_per_point();
+                    timeline.shift_timeline_start(pan_delta * ticks_per_point);
+                    view_changed = true;
+                }
+                scroll.pinch_factor(ui)
+            };
+            if !self.lock.zoom && factor != 1.0 {
+                let y_delta = factor.ln() * ZOOM_DELTA_SCALE;
+                zoom::anchored(timeline, anchor_x, |t| t.zoom(y_delta));
+                view_changed = true;
+            }
+
+            // Step through the discrete zoom levels with the keyboard.
+            let ladder = self.zoom_levels.filter(|_| !self.lock.zoom);
+            if let Some(ladder) = ladder.filter(|_| !ui.ctx().wants_keyboard_input()) {
+                let steps = ui.input_mut(|i| {
+                    let none = egui::Modifiers::NONE;
+                    let zoom_in = i.count_and_consume_key(none, egui::Key::Plus)
+                        + i.count_and_consume_key(none, egui::Key::Equals);
+                    let zoom_out = i.count_and_consume_key(none, egui::Key::Minus);
+                    zoom_out as i32 - zoom_in as i32
+                });
+                if steps != 0 {
+                    let tpp = ladder.step(timeline.time_domain().units_per_point(), steps);
+                    zoom::set(timeline, tpp, anchor_x);
+                    view_changed = true;
                 }
             }
         }
 
+        // Preserve the visible range if the timeline was resized since the last frame.
+        let resize_id = id.with("resize");
+        let width = timeline_rect.width();
+        let prev: Option<(f32, f32)> = ui.data(|d| d.get_temp(resize_id));
+        if let Some((prev_width, prev_visible_ticks)) = prev {
+            if self.resize == Resize::PreserveRange && prev_width != width && width > 0.0 {
+                timeline.set_ticks_per_point(prev_visible_ticks / width);
+                view_changed = true;
+            }
+        }
+        let visible_ticks = timeline.time_domain().units_per_point() * width;
+        ui.data_mut(|d| d.insert_temp(resize_id, (width, visible_ticks)));
+
         // Draw the background.
         let vis = ui.style().noninteractive();
         let bg_stroke = egui::Stroke {
@@ -136,28 +753,114 @@ impl Timeline {
 
         // The child widgets.
         let layout = egui::Layout::top_down(egui::Align::Min);
-        let info = timeline.musical_ruler_info();
-        let visible_ticks = info.ticks_per_point() * timeline_rect.width();
+        let ticks_per_point = timeline.time_domain().units_per_point();
+        let visible_ticks = ticks_per_point * timeline_rect.width();
+        let timeline_start = timeline.timeline_start();
+        let selection_id = id.with("selection");
+        if let Some(state) = self.selection.clone() {
+            selection::store(ui.ctx(), selection_id, state);
+        }
         let timeline = TimelineCtx {
             full_rect: timeline_rect,
             visible_ticks,
+            ticks_per_point,
+            timeline_start,
+            lock: self.lock,
+            locked_ranges: self.locked_ranges,
+            track_locked: false,
+            track_color: None,
+            track_bypassed: false,
+            track_id: None,
+            ripple: self.ripple,
+            selection_id: self.selection.is_some().then_some(selection_id),
+            grid_id: id.with("grid_step"),
+            style: self.style,
+            detail: self.detail_thresholds.level(ticks_per_point),
+            track_scale,
+            zoom_modifiers: self.zoom_modifiers,
+            stretch_modifiers: self.stretch_modifiers,
+            scroll_input: self.scroll_input,
         };
         let tracks = TracksCtx {
             full_rect,
             header_full_rect: header_rect,
+            header_right_full_rect: header_right_rect,
             timeline,
+            track_rects: Default::default(),
+            audibility: Default::default(),
         };
-        let ui = ui.new_child(egui::UiBuilder::new().max_rect(full_rect).layout(layout));
-        Show { tracks, ui }
+        let stack_info = style::tag(arbiter::tag(Default::default(), id), &tracks.timeline.style);
+        let ui = ui.new_child(
+            egui::UiBuilder::new()
+                .id_salt(id)
+                .max_rect(full_rect)
+                .layout(layout)
+                .ui_stack_info(stack_info),
+        );
+        Show {
+            id,
+            tracks,
+            ui,
+            shade_bars: self.shade_bars,
+            bottom_height: 0.0,
+            view_changed,
+            timeline_start,
+            track_scale_change,
+            selection: self.selection,
+        }
+    }
+}
+
+impl Default for TrackZoom {
+    fn default() -> Self {
+        Self {
+            modifiers: egui::Modifiers::CTRL | egui::Modifiers::SHIFT,
+            scale_range: 0.25..=4.0,
+        }
     }
 }
 
 /// Relevant information for displaying a background for the timeline.
 pub struct BackgroundCtx<'a> {
     pub header_full_rect: Option<egui::Rect>,
+    pub header_right_full_rect: Option<egui::Rect>,
     pub timeline: &'a TimelineCtx,
 }
 
+impl<'a> BackgroundCtx<'a> {
+    /// The visible area of the timeline, accounting for the `ui`'s clip rect.
+    pub fn visible_rect(&self, ui: &egui::Ui) -> egui::Rect {
+        ui.clip_rect().intersect(self.timeline.full_rect)
+    }
+
+    /// Fill the given range of ticks relative to the start of the timeline across its full height.
+    pub fn paint_tick_range(&self, ui: &egui::Ui, range: Range<f32>, color: egui::Color32) {
+        let visible = self.visible_rect(ui);
+        let x = egui::Rangef::new(
+            self.timeline.tick_to_x(range.start),
+            self.timeline.tick_to_x(range.end),
+        );
+        let rect = egui::Rect::from_x_y_ranges(x, visible.y_range()).intersect(visible);
+        if rect.is_positive() {
+            ui.painter().rect_filled(rect, 0.0, color);
+        }
+    }
+
+    /// Fill every other bar, starting from the second bar, with the given color.
+    pub fn paint_every_other_bar(
+        &self,
+        ui: &egui::Ui,
+        info: &dyn ruler::MusicalInfo,
+        color: egui::Color32,
+    ) {
+        for (number, bar) in ruler::bars(info, self.timeline.visible_ticks) {
+            if (number - 1).rem_euclid(2) == 1 {
+                self.paint_tick_range(ui, bar.tick_range, color);
+            }
+        }
+    }
+}
+
 impl Show {
     /// Allows for drawing some widgets in the background before showing the grid.
     ///
@@ -166,9 +869,11 @@ impl Show {
         let Show {
             ref mut ui,
             ref tracks,
+            ..
         } = self;
         let bg = BackgroundCtx {
             header_full_rect: tracks.header_full_rect,
+            header_right_full_rect: tracks.header_right_full_rect,
             timeline: &tracks.timeline,
         };
         background(&bg, ui);
@@ -178,26 +883,66 @@ impl Show {
     /// Paints the grid over the timeline `Rect`.
     ///
     /// If using a custom `background`, you may wish to call this after.
-    pub fn paint_grid(self, info: &dyn ruler::MusicalInfo) -> Self {
+    pub fn paint_grid(self, domain: &dyn TimeDomain) -> Self {
+        profile_scope!("paint_grid");
         let vis = self.ui.style().noninteractive();
         let mut stroke = vis.bg_stroke;
-        let bar_color = stroke.color.linear_multiply(0.5);
-        let step_even_color = stroke.color.linear_multiply(0.25);
-        let step_odd_color = stroke.color.linear_multiply(0.125);
+        let grid_width = self.tracks.timeline.style.grid_stroke_width;
+        stroke.width = style::snap_stroke_width(self.ui.ctx(), grid_width);
+        let level_colors = [
+            stroke.color.linear_multiply(0.5),
+            stroke.color.linear_multiply(0.25),
+            stroke.color.linear_multiply(0.125),
+            stroke.color.linear_multiply(0.0625),
+        ];
         let tl_rect = self.tracks.timeline.full_rect;
         let visible_len = tl_rect.width();
-        let mut steps = ruler::Steps::new(info, visible_len, MIN_STEP_GAP);
-        while let Some(step) = steps.next(info) {
-            stroke.color = match step.index_in_bar {
-                0 => bar_color,
-                n if n % 2 == 0 => step_even_color,
-                _ => step_odd_color,
+
+        // Shade alternating groups of bars.
+        if let (Some(every), Some(info)) = (self.shade_bars, domain.musical_info()) {
+            let fill = self.tracks.timeline.style.bar_shading(self.ui.visuals());
+            let every = every as i64;
+            let bg = BackgroundCtx {
+                header_full_rect: self.tracks.header_full_rect,
+                header_right_full_rect: self.tracks.header_right_full_rect,
+                timeline: &self.tracks.timeline,
             };
-            let x = tl_rect.left() + step.x;
-            let a = egui::Pos2::new(x, tl_rect.top());
-            let b = egui::Pos2::new(x, tl_rect.bottom());
-            self.ui.painter().line_segment([a, b], stroke);
+            for (number, bar) in ruler::bars(info, self.tracks.timeline.visible_ticks) {
+                if (number - 1).div_euclid(every) % 2 == 1 {
+                    bg.paint_tick_range(&self.ui, bar.tick_range, fill);
+                }
+            }
         }
+
+        // Draw the step lines, reusing the previous frame's mesh while the steps are unchanged.
+        profile_scope!("grid_steps");
+        let max_steps = self.tracks.timeline.style.max_steps;
+        let steps = time::limit_steps(domain.steps(visible_len, MIN_STEP_GAP), max_steps);
+        store_grid_step(self.ui.ctx(), self.tracks.timeline.grid_id, &steps);
+        let steps: Vec<(u32, u8)> = steps
+            .iter()
+            .map(|step| (step.x.to_bits(), step.level))
+            .collect();
+        let key = (
+            cache::rect_bits(tl_rect),
+            stroke.width.to_bits(),
+            level_colors,
+            &steps,
+        );
+        let id = self.id.with("grid");
+        cache::paint(self.ui.painter(), id, key, || {
+            steps
+                .iter()
+                .map(|&(x, level)| {
+                    let level = (level as usize).min(level_colors.len() - 1);
+                    stroke.color = level_colors[level];
+                    let x = tl_rect.left() + f32::from_bits(x);
+                    let a = egui::Pos2::new(x, tl_rect.top());
+                    let b = egui::Pos2::new(x, tl_rect.bottom());
+                    egui::Shape::line_segment([a, b], stroke)
+                })
+                .collect()
+        });
         self
     }
 
@@ -208,6 +953,7 @@ impl Show {
         let Self {
             ref mut ui,
             ref tracks,
+            ..
         } = self;
 
         // Use no spacing by default so we can get exact position for line separator.
@@ -217,7 +963,7 @@ impl Show {
         let remaining = ui.available_rect_before_wrap();
         let a = remaining.left_top();
         let b = remaining.right_top();
-        let stroke = ui.style().visuals.noninteractive().bg_stroke;
+        let stroke = tracks.timeline.style.separator_stroke(ui.visuals());
         ui.painter().line_segment([a, b], stroke);
 
         // Add the exact space so the UI is aware.
@@ -229,34 +975,385 @@ impl Show {
         self
     }
 
+    /// Set some tracks that should be pinned to the bottom, e.g. a mirrored ruler.
+    ///
+    /// Should be called after `pinned_tracks` and before `tracks`. The height of the pinned
+    /// bottom area is measured and remembered between frames.
+    pub fn pinned_bottom_tracks(
+        mut self,
+        tracks_fn: impl FnOnce(&TracksCtx, &mut egui::Ui),
+    ) -> Self {
+        let Self {
+            id,
+            ref mut ui,
+            ref tracks,
+            ref mut bottom_height,
+            ..
+        } = self;
+        let id = id.with("pinned_bottom_height");
+        let default_h = ui.spacing().interact_size.y;
+        let prev_h: f32 = ui.data(|d| d.get_temp(id)).unwrap_or(default_h);
+        let remaining = ui.available_rect_before_wrap();
+        let mut rect = remaining;
+        rect.min.y = (remaining.bottom() - prev_h).max(remaining.top());
+
+        // Draw a line to mark the start of the pinned bottom tracks.
+        let stroke = tracks.timeline.style.separator_stroke(ui.visuals());
+        ui.painter()
+            .line_segment([rect.left_top(), rect.right_top()], stroke);
+
+        // Lay out the tracks beneath the line.
+        let mut child_rect = rect;
+        child_rect.min.y += stroke.width;
+        let layout = *ui.layout();
+        let mut child = ui.new_child(egui::UiBuilder::new().max_rect(child_rect).layout(layout));
+        tracks_fn(tracks, &mut child);
+        let h = child.min_rect().height() + stroke.width;
+        if h != prev_h {
+            ui.data_mut(|d| d.insert_temp(id, h));
+            ui.ctx().request_repaint();
+        }
+        *bottom_height = rect.height();
+        self
+    }
+
     /// Set all remaining tracks for the timeline.
     ///
     /// These tracks will become vertically scrollable in the case that there are two many to fit
     /// on the view. The given `egui::Rect` is the viewport (visible area) relative to the
     /// timeline.
+    ///
+    /// Returns the resulting layout of the track area, from which the playhead may be set.
     pub fn tracks(
         mut self,
         tracks_fn: impl FnOnce(&TracksCtx, egui::Rect, &mut egui::Ui),
-    ) -> SetPlayhead {
+    ) -> TracksLayout {
+        profile_scope!("tracks");
         let Self {
             ref mut ui,
             ref tracks,
+            ..
         } = self;
         let rect = ui.available_rect_before_wrap();
         let enable_scrolling = !ui.input(|i| i.modifiers.ctrl);
-        egui::ScrollArea::vertical()
-            .max_height(rect.height())
-            .enable_scrolling(enable_scrolling)
-            .show_viewport(ui, |ui, view| tracks_fn(tracks, view, ui));
-        let timeline_rect = tracks.timeline.full_rect;
-        SetPlayhead { timeline_rect }
+        let scrolled_start = tracks.track_rects.borrow().len();
+        let mut scroll_area = egui::ScrollArea::vertical()
+            .max_height(rect.height() - self.bottom_height)
+            .enable_scrolling(enable_scrolling);
+
+        // Keep the row under the pointer in place while the track heights are scaled.
+        let scroll_id_id = self.id.with("scroll_id");
+        let prev_scroll_id: Option<egui::Id> = ui.data(|d| d.get_temp(scroll_id_id));
+        let prev_state = prev_scroll_id.and_then(|id| egui::scroll_area::State::load(ui.ctx(), id));
+        if let (Some((ratio, pointer_y)), Some(state)) = (self.track_scale_change, prev_state) {
+            let pointer_y = pointer_y - rect.top();
+            let offset = (state.offset.y + pointer_y) * ratio - pointer_y;
+            scroll_area = scroll_area.vertical_scroll_offset(offset.max(0.0));
+        }
+
+        let output = scroll_area.show_viewport(ui, |ui, view| tracks_fn(tracks, view, ui));
+        ui.data_mut(|d| d.insert_temp(scroll_id_id, output.id));
+        // The bottom of the laid out tracks in screen space.
+        let content_bottom =
+            output.inner_rect.top() + output.content_size.y - output.state.offset.y;
+        let tracks_bottom = content_bottom.min(output.inner_rect.bottom());
+        paint_frozen_edges(ui, tracks, &output);
+        paint_inaudible(ui, tracks);
+
+        // Determine whether the view changed since the last frame or is being interacted with.
+        let full_rect = tracks.timeline.full_rect;
+        let view = (
+            full_rect,
+            tracks.timeline.ticks_per_point,
+            output.state.offset.y,
+            output.content_size.y,
+        );
+        let view_id = self.id.with("repaint_view");
+        let interacting = ui.ctx().dragged_id().is_some()
+            || (ui.rect_contains_pointer(full_rect)
+                && ui.input(|i| {
+                    i.pointer.is_moving() || i.pointer.any_down() || i.pointer.any_released()
+                }));
+        let view_changed = repaint::changed(ui, view_id, view);
+        let repaint = match self.view_changed || view_changed || interacting {
+            true => repaint::Repaint::Full,
+            false => repaint::Repaint::Idle,
+        };
+
+        // Report the view to the host whenever it changes.
+        let view = View {
+            start_tick: self.timeline_start,
+            ticks_per_point: tracks.timeline.ticks_per_point,
+            scroll_offset: output.state.offset.y,
+        };
+        let view_changed = repaint::changed(ui, self.id.with("view"), view).then_some(view);
+
+        // Report the selection if it was changed by the tracks' widgets.
+        let selection_changed = match (&self.selection, tracks.timeline.selection_id) {
+            (Some(initial), Some(id)) => selection::load(ui.ctx(), id).filter(|s| s != initial),
+            _ => None,
+        };
+
+        let scrolled_tracks = scrolled_start..tracks.track_rects.borrow().len();
+        TracksLayout {
+            id: self.id,
+            timeline_rect: tracks.timeline.full_rect,
+            viewport: output.inner_rect,
+            visible_ticks: 0.0..tracks.timeline.visible_ticks,
+            ticks_per_point: tracks.timeline.ticks_per_point,
+            content_height: output.content_size.y,
+            scroll_offset: output.state.offset.y,
+            timeline_start: self.timeline_start,
+            tracks_bottom,
+            scrolled_tracks,
+            scroll_id: output.id,
+            track_rects: tracks.track_rects.take(),
+            lock: tracks.timeline.lock,
+            view_changed,
+            selection_changed,
+            repaint: Cell::new(repaint),
+            style: tracks.timeline.style.clone(),
+        }
     }
 }
 
-impl SetPlayhead {
+impl TracksLayout {
     /// Instantiate the playhead over the top of the whole timeline.
-    pub fn playhead(&self, ui: &mut egui::Ui, info: &mut dyn Playhead) -> egui::Response {
-        playhead::set(ui, self.timeline_rect, info)
+    ///
+    /// Use `playhead::Config::default()` for the default appearance. The playhead is made
+    /// non-interactive if `Lock::playhead` is set.
+    pub fn playhead(
+        &self,
+        ui: &mut egui::Ui,
+        config: &playhead::Config,
+        info: &mut dyn Playhead,
+    ) -> egui::Response {
+        let config = playhead::Config {
+            interactive: config.interactive && !self.lock.playhead,
+            ..config.clone()
+        };
+        // Arbitrate the playhead against the elements of this timeline, sharing its style.
+        let (rect, bottom, start) = (self.timeline_rect, self.tracks_bottom, self.timeline_start);
+        let stack_info = style::tag(arbiter::tag(Default::default(), self.id), &self.style);
+        let builder = egui::UiBuilder::new()
+            .id_salt(self.id)
+            .ui_stack_info(stack_info);
+        let response = ui
+            .scope_builder(builder, |ui| {
+                playhead::set(ui, rect, bottom, start, &config, info)
+            })
+            .inner;
+
+        // Dirty the area painted by the playhead during both this frame and the last if it moved.
+        let painted = playhead::painted_rect(ui, &response);
+        let prev_id = response.id.with("prev_painted");
+        let prev = ui
+            .data(|d| d.get_temp::<Option<egui::Rect>>(prev_id))
+            .flatten();
+        if repaint::changed(ui, prev_id, painted) {
+            let dirty = prev
+                .into_iter()
+                .chain(painted)
+                .fold(egui::Rect::NOTHING, |a, b| a.union(b));
+            self.repaint.set(self.repaint.get().with_playhead(dirty));
+        }
+        response
+    }
+
+    /// A hint as to what changed during this frame, for hosts that wish to avoid redundant
+    /// repaints.
+    ///
+    /// Call this after setting the playhead and any other overlays.
+    pub fn repaint(&self) -> repaint::Repaint {
+        self.repaint.get()
+    }
+
+    /// Draw the region captured so far over each armed track while recording.
+    ///
+    /// See `recording::paint` for details. Call this before setting the playhead so that the
+    /// playhead is drawn on top.
+    pub fn recording(&self, ui: &egui::Ui, api: &dyn recording::Recording) {
+        let rects = recording::paint(ui, self, api);
+
+        // Dirty the area covered by the region during both this frame and the last if it grew.
+        let prev_id = self.id.with("recording_painted");
+        let prev = ui
+            .data(|d| d.get_temp::<Vec<egui::Rect>>(prev_id))
+            .unwrap_or_default();
+        if repaint::changed(ui, prev_id, rects.clone()) {
+            let dirty = prev
+                .into_iter()
+                .chain(rects)
+                .fold(egui::Rect::NOTHING, |a, b| a.union(b));
+            self.repaint.set(self.repaint.get().with_playhead(dirty));
+        }
+    }
+
+    /// Draw a progress overlay over each track with a long-running operation in progress.
+    ///
+    /// See `progress::paint` for details. The overlaid tracks are reported as dirty every frame so
+    /// that hosts repainting per `TracksLayout::repaint` keep the stripes animated.
+    pub fn progress(&self, ui: &egui::Ui, api: &dyn progress::Progress) {
+        let rects = progress::paint_tracks(ui, self, api);
+
+        // Dirty the overlays while shown, along with any that finished since the last frame.
+        let prev_id = self.id.with("progress_painted");
+        let prev = ui
+            .data(|d| d.get_temp::<Vec<egui::Rect>>(prev_id))
+            .unwrap_or_default();
+        let changed = repaint::changed(ui, prev_id, rects.clone());
+        if changed || !rects.is_empty() {
+            let dirty = prev
+                .into_iter()
+                .chain(rects)
+                .fold(egui::Rect::NOTHING, |a, b| a.union(b));
+            self.repaint.set(self.repaint.get().with_playhead(dirty));
+        }
+    }
+
+    /// Highlight the time selection, if any, over the whole timeline.
+    pub fn time_selection(&self, ui: &egui::Ui, api: &dyn selection::TimeSelectionApi) {
+        if let Some(range) = api.time_selection() {
+            let rect = self.timeline_rect;
+            selection::paint_time_selection(ui, rect, self.ticks_per_point, range);
+        }
+    }
+
+    /// Nudge the selection with the arrow keys while the timeline has focus.
+    ///
+    /// See `selection::nudge` for details.
+    pub fn nudge(
+        &self,
+        ui: &egui::Ui,
+        id_source: impl Hash,
+        api: &mut dyn selection::NudgeApi,
+    ) -> bool {
+        selection::nudge(ui, id_source, self.timeline_rect, api)
+    }
+
+    /// Instantiate the insert/delete time overlay over the whole timeline.
+    ///
+    /// See `time_edit::overlay` for details. Returns the final tick range once the gesture ends.
+    pub fn time_edit(
+        &self,
+        ui: &mut egui::Ui,
+        id_source: impl Hash,
+        mode: time_edit::Mode,
+    ) -> Option<Range<f32>> {
+        let rect = self.timeline_rect;
+        time_edit::overlay(ui, id_source, rect, self.ticks_per_point, mode)
+    }
+
+    /// Render the loop region as a translucent column over the whole timeline.
+    ///
+    /// See `loop_region::column` for details.
+    pub fn loop_column(
+        &self,
+        ui: &mut egui::Ui,
+        id_source: impl Hash,
+        modifiers: egui::Modifiers,
+        api: &mut dyn loop_region::LoopRegion,
+    ) -> Option<egui::Response> {
+        let rect = self.timeline_rect;
+        loop_region::column(ui, id_source, rect, self.ticks_per_point, modifiers, api)
+    }
+
+    /// Draw a pulsing outline around each of the given highlights, e.g. search results.
+    ///
+    /// See `highlight::paint` for details.
+    pub fn highlights(&self, ui: &egui::Ui, highlights: &[highlight::Highlight]) {
+        highlight::paint(ui, self, highlights);
+    }
+
+    /// Show a tooltip near the pointer with the position and any hovered clips or markers.
+    ///
+    /// See `hover::tooltip` for details.
+    pub fn hover_tooltip(&self, ui: &egui::Ui, formatter: &dyn format::TimeFormatter) {
+        hover::tooltip(ui, self, formatter);
+    }
+
+    /// The current position and scale of the view.
+    pub fn view(&self) -> View {
+        View {
+            start_tick: self.timeline_start,
+            ticks_per_point: self.ticks_per_point,
+            scroll_offset: self.scroll_offset,
+        }
+    }
+
+    /// Jump to the given view, e.g. a bookmark, recording the current view in the navigation
+    /// history.
+    ///
+    /// The zoom is set via `TimelineApi::set_ticks_per_point`, and the start is shifted via
+    /// `TimelineApi::shift_timeline_start` if both the current and given views have a start tick.
+    pub fn set_view(&self, ctx: &egui::Context, view: &View, timeline: &mut dyn TimelineApi) {
+        history::push(ctx, self);
+        self.apply_view(ctx, view, timeline);
+    }
+
+    /// Return to the view prior to the last jump. Returns `false` if there was none.
+    ///
+    /// See `history::back` for details.
+    pub fn back(&self, ctx: &egui::Context, timeline: &mut dyn TimelineApi) -> bool {
+        history::back(ctx, self, timeline).is_some()
+    }
+
+    /// Redo the last jump undone via `back`. Returns `false` if there was none.
+    ///
+    /// See `history::forward` for details.
+    pub fn forward(&self, ctx: &egui::Context, timeline: &mut dyn TimelineApi) -> bool {
+        history::forward(ctx, self, timeline).is_some()
+    }
+
+    /// Jump to the given view without recording it in the navigation history.
+    pub(crate) fn apply_view(
+        &self,
+        ctx: &egui::Context,
+        view: &View,
+        timeline: &mut dyn TimelineApi,
+    ) {
+        timeline.set_ticks_per_point(view.ticks_per_point);
+        if let (Some(current), Some(start)) = (self.timeline_start, view.start_tick) {
+            timeline.shift_timeline_start(start - current);
+        }
+        self.set_scroll_offset(ctx, view.scroll_offset);
+    }
+
+    /// Scroll the tracks vertically to the given offset, applied during the next frame.
+    pub fn set_scroll_offset(&self, ctx: &egui::Context, offset: f32) {
+        if let Some(mut state) = egui::scroll_area::State::load(ctx, self.scroll_id) {
+            let max = (self.content_height - self.viewport.height()).max(0.0);
+            state.offset.y = offset.clamp(0.0, max);
+            state.store(ctx, self.scroll_id);
+            ctx.request_repaint();
+        }
+    }
+
+    /// Set track headers within a `ui` separate from the timeline, row-aligned with the tracks.
+    ///
+    /// See `header::detached` for details.
+    pub fn detached_headers(
+        &self,
+        ui: &mut egui::Ui,
+        header: impl FnMut(egui::Id, &mut egui::Ui),
+    ) -> egui::Response {
+        header::detached(ui, self, header)
+    }
+
+    /// The rect of the track with the given ID, if it was set via `TrackCtx::id`.
+    pub fn track_rect(&self, id: egui::Id) -> Option<egui::Rect> {
+        self.track_rects
+            .iter()
+            .find(|(track_id, _)| *track_id == id)
+            .map(|&(_, rect)| rect)
+    }
+
+    /// The ID of the track containing the given position, if it was set via `TrackCtx::id`.
+    pub fn track_at(&self, pos: egui::Pos2) -> Option<egui::Id> {
+        self.track_rects
+            .iter()
+            .find(|(_, rect)| rect.y_range().contains(pos.y))
+            .map(|&(id, _)| id)
     }
 }
 
@@ -266,18 +1363,51 @@ pub struct TrackCtx<'a> {
     ui: &'a mut egui::Ui,
     available_rect: egui::Rect,
     header_height: f32,
+    id: Option<egui::Id>,
+    locked: bool,
+    color: Option<egui::Color32>,
+    muted: bool,
+    soloed: bool,
+    bypass: Option<&'a mut bool>,
+    /// A placeholder for the row background, painted once the track height is known.
+    background: egui::layers::ShapeIdx,
 }
 
 impl<'a> TrackCtx<'a> {
+    /// Identify the track so that its rect is recorded within the returned `TracksLayout`.
+    pub fn id(mut self, id_source: impl Hash) -> Self {
+        self.id = Some(egui::Id::new(id_source));
+        self
+    }
+
     /// UI for the track's header.
     pub fn header(mut self, header: impl FnOnce(&mut egui::Ui)) -> Self {
-        let header_h = self
-            .tracks
-            .header_full_rect
+        let rect = self.tracks.header_full_rect;
+        self.set_header(rect, "header", header);
+        self
+    }
+
+    /// UI for the track's right-hand-side header, if enabled via `Timeline::header_right`.
+    pub fn header_right(mut self, header: impl FnOnce(&mut egui::Ui)) -> Self {
+        let rect = self.tracks.header_right_full_rect;
+        self.set_header(rect, "header_right", header);
+        self
+    }
+
+    /// Set a header within the given panel, growing the header height to fit.
+    fn set_header(
+        &mut self,
+        rect: Option<egui::Rect>,
+        panel: &str,
+        header: impl FnOnce(&mut egui::Ui),
+    ) {
+        let salt = self.id_salt().with(panel);
+        let header_h = rect
             .map(|mut rect| {
                 rect.min.y = self.available_rect.min.y;
                 let ui = &mut self.ui.new_child(
                     egui::UiBuilder::new()
+                        .id_salt(salt)
                         .max_rect(rect)
                         .layout(*self.ui.layout()),
                 );
@@ -285,28 +1415,182 @@ impl<'a> TrackCtx<'a> {
                 ui.min_rect().height()
             })
             .unwrap_or(0.0);
-        self.header_height = header_h;
+        self.header_height = self.header_height.max(header_h);
+    }
+
+    /// Lock the track against edits. The track is still drawn, but covered by a lock pattern.
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Whether or not the track is muted, dimming its content with
+    /// `TimelineStyle::inaudible_overlay`.
+    pub fn muted(mut self, muted: bool) -> Self {
+        self.muted = muted;
+        self
+    }
+
+    /// Whether or not the track is soloed. While any track is soloed, the content of all others
+    /// is dimmed with `TimelineStyle::inaudible_overlay`.
+    pub fn soloed(mut self, soloed: bool) -> Self {
+        self.soloed = soloed;
         self
     }
 
+    /// Allow bypassing the whole track, e.g. while sketching arrangements.
+    ///
+    /// Double-clicking an empty area of the track toggles the flag, as may a header toggle bound
+    /// to the same flag. While bypassed, the track is covered by a hatched overlay and
+    /// `TimelineCtx::track_bypassed` is set.
+    pub fn bypass(mut self, bypassed: &'a mut bool) -> Self {
+        self.bypass = Some(bypassed);
+        self
+    }
+
+    /// A base color for the track, e.g. chosen by the user.
+    ///
+    /// Used to tint the row background, as an accent stripe along the header and as the default
+    /// color for clips within the track.
+    pub fn color(mut self, color: egui::Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Distinguishes the UIs of this track from those of other tracks, by its ID if set, otherwise
+    /// by its index within the timeline.
+    fn id_salt(&self) -> egui::Id {
+        match self.id {
+            Some(id) => id,
+            None => egui::Id::new(("track", self.tracks.audibility.borrow().len())),
+        }
+    }
+
     /// Set the track, with a function for instantiating contents for the timeline.
-    pub fn show(self, track: impl FnOnce(&TimelineCtx, &mut egui::Ui)) {
+    ///
+    /// Use `TimelineCtx::visible_window` within `track` to query only the visible content, and
+    /// `TimelineCtx::detail` to choose how to draw it.
+    pub fn show(mut self, track: impl FnOnce(&TimelineCtx, &mut egui::Ui)) {
+        profile_scope!("track");
+        let bypassed = self.bypass.as_deref().is_some_and(|&b| b);
+        let track_timeline;
+        let custom = self.locked || self.color.is_some() || bypassed || self.id.is_some();
+        let timeline = match custom {
+            false => &self.tracks.timeline,
+            true => {
+                track_timeline = TimelineCtx {
+                    track_locked: self.locked,
+                    track_color: self.color,
+                    track_bypassed: bypassed,
+                    track_id: self.id,
+                    ..self.tracks.timeline.clone()
+                };
+                &track_timeline
+            }
+        };
+
+        // Sense double-clicks beneath the track's content, sized by its height last frame.
+        let salt = self.id_salt();
+        let bypass_id = self.ui.id().with(salt).with("bypass");
+        let bypass_response = self.bypass.is_some().then(|| {
+            let h: f32 = self.ui.data(|d| d.get_temp(bypass_id)).unwrap_or(0.0);
+            let mut rect = timeline.full_rect;
+            rect.min.y = self.available_rect.min.y;
+            rect.set_height(h);
+            self.ui.interact(rect, bypass_id, egui::Sense::click())
+        });
+
         // The UI and area for the track timeline.
-        let track_h = {
-            let mut rect = self.tracks.timeline.full_rect;
+        let (track_rect, track_h) = {
+            let mut rect = timeline.full_rect;
             rect.min.y = self.available_rect.min.y;
             let ui = &mut self.ui.new_child(
                 egui::UiBuilder::new()
+                    .id_salt(salt)
                     .max_rect(rect)
                     .layout(*self.ui.layout()),
             );
-            track(&self.tracks.timeline, ui);
-            ui.min_rect().height()
+            track(timeline, ui);
+            let h = ui.min_rect().height();
+            rect.set_height(h);
+            (rect, h)
         };
+
+        // Toggle the bypass on double-click, covering the track while bypassed.
+        if let Some(bypass) = self.bypass.as_deref_mut() {
+            self.ui.data_mut(|d| d.insert_temp(bypass_id, track_h));
+            if bypass_response.is_some_and(|r| r.double_clicked()) {
+                *bypass = !*bypass;
+            }
+            if *bypass {
+                paint_bypassed(self.ui, track_rect);
+            }
+        }
+
+        // Cover the locked parts of the track.
+        if timeline.track_locked {
+            paint_locked(self.ui, track_rect);
+        } else {
+            for range in &timeline.locked_ranges {
+                let x = egui::Rangef::new(
+                    timeline.tick_to_x(range.start),
+                    timeline.tick_to_x(range.end),
+                );
+                let rect = egui::Rect::from_x_y_ranges(x, track_rect.y_range());
+                paint_locked(self.ui, rect.intersect(track_rect));
+            }
+        }
+
+        // Reserve the overlay dimming the track if it turns out to be inaudible.
+        self.tracks.audibility.borrow_mut().push(TrackAudibility {
+            painter: self.ui.painter().clone(),
+            overlay: self.ui.painter().add(egui::Shape::Noop),
+            rect: track_rect,
+            muted: self.muted,
+            soloed: self.soloed,
+        });
+
         // Manually add space occuppied by the child UIs, otherwise `ScrollArea` won't consider the
         // space occuppied. TODO: Is there a better way to handle this?
         let w = self.tracks.full_rect.width();
         let h = self.header_height.max(track_h);
+
+        // Tint the row background and draw the header accent stripe.
+        if let Some(color) = self.color {
+            let row_rect = egui::Rect::from_min_size(self.available_rect.min, egui::vec2(w, h));
+            let tint = egui::Shape::rect_filled(row_rect, 0.0, color.linear_multiply(0.08));
+            self.ui.painter().set(self.background, tint);
+            if let Some(header_rect) = self.tracks.header_full_rect {
+                // Draw the stripe along the outer edge of the header.
+                let x = match header_rect.right() < self.tracks.full_rect.right() {
+                    true => header_rect.left()..=header_rect.left() + TRACK_COLOR_STRIPE_WIDTH,
+                    false => header_rect.right() - TRACK_COLOR_STRIPE_WIDTH..=header_rect.right(),
+                };
+                let stripe = egui::Rect::from_x_y_ranges(x, row_rect.y_range());
+                self.ui.painter().rect_filled(stripe, 0.0, color);
+            }
+        }
+        if let Some(id) = self.id {
+            let min = self.available_rect.min;
+            let rect = egui::Rect::from_min_size(min, egui::Vec2::new(w, h));
+            self.tracks.track_rects.borrow_mut().push((id, rect));
+
+            // Highlight the headers of selected tracks.
+            let selected = timeline
+                .selection(self.ui.ctx())
+                .is_some_and(|s| s.tracks.contains(&id));
+            if selected {
+                let fill = self.ui.visuals().selection.bg_fill.linear_multiply(0.2);
+                let headers = [
+                    self.tracks.header_full_rect,
+                    self.tracks.header_right_full_rect,
+                ];
+                for header_rect in headers.iter().flatten() {
+                    let rect = egui::Rect::from_x_y_ranges(header_rect.x_range(), rect.y_range());
+                    self.ui.painter().rect_filled(rect, 0.0, fill);
+                }
+            }
+        }
         self.ui.scope(|ui| {
             ui.spacing_mut().item_spacing.y = 0.0;
             ui.spacing_mut().interact_size.y = 0.0;
@@ -320,21 +1604,196 @@ impl TracksCtx {
     /// Begin showing the next `Track`.
     pub fn next<'a>(&'a self, ui: &'a mut egui::Ui) -> TrackCtx<'a> {
         let available_rect = ui.available_rect_before_wrap();
+        let background = ui.painter().add(egui::Shape::Noop);
         TrackCtx {
             tracks: self,
             ui,
             available_rect,
             header_height: 0.0,
+            id: None,
+            locked: false,
+            color: None,
+            muted: false,
+            soloed: false,
+            bypass: None,
+            background,
+        }
+    }
+}
+
+/// Draw the separators and shadows where the header panels and pinned tracks meet the scrolling
+/// tracks.
+fn paint_frozen_edges<R>(
+    ui: &egui::Ui,
+    tracks: &TracksCtx,
+    output: &egui::scroll_area::ScrollAreaOutput<R>,
+) {
+    let style = &tracks.timeline.style;
+    let painter = ui.painter().with_clip_rect(tracks.full_rect);
+    let stroke = style.separator_stroke(ui.visuals());
+    let color = style.frozen_shadow_color(ui.visuals());
+    let shadow_w = style.frozen_shadow_width;
+    let timeline_rect = tracks.timeline.full_rect;
+
+    // Content scrolls horizontally beneath the header panels.
+    let headers = [tracks.header_full_rect, tracks.header_right_full_rect];
+    for header in headers.iter().flatten() {
+        let (x, edge) = match header.right() <= timeline_rect.left() {
+            true => (header.right(), egui::Direction::LeftToRight),
+            false => (header.left(), egui::Direction::RightToLeft),
+        };
+        let y = header.y_range();
+        let shadow_x = match edge {
+            egui::Direction::LeftToRight => egui::Rangef::new(x, x + shadow_w),
+            _ => egui::Rangef::new(x - shadow_w, x),
+        };
+        let shadow = egui::Rect::from_x_y_ranges(shadow_x, y);
+        style::paint_shadow(&painter, shadow.intersect(timeline_rect), edge, color);
+        painter.vline(x, y, stroke);
+    }
+
+    // Tracks scrolled beneath the pinned tracks above or below.
+    let viewport = output.inner_rect;
+    let x = tracks.full_rect.x_range();
+    let max_offset = output.content_size.y - viewport.height();
+    if output.state.offset.y > 0.0 {
+        let y = egui::Rangef::new(viewport.top(), viewport.top() + shadow_w);
+        let shadow = egui::Rect::from_x_y_ranges(x, y);
+        style::paint_shadow(&painter, shadow, egui::Direction::TopDown, color);
+    }
+    if output.state.offset.y < max_offset {
+        let y = egui::Rangef::new(viewport.bottom() - shadow_w, viewport.bottom());
+        let shadow = egui::Rect::from_x_y_ranges(x, y);
+        style::paint_shadow(&painter, shadow, egui::Direction::BottomUp, color);
+    }
+}
+
+/// Store the smallest spacing between the given grid steps for `TimelineCtx::grid_step`.
+fn store_grid_step(ctx: &egui::Context, id: egui::Id, steps: &[time::Step]) {
+    let mut units: Vec<f32> = steps.iter().map(|step| step.units).collect();
+    units.sort_by(f32::total_cmp);
+    let step = units
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .filter(|&gap| gap > 0.0)
+        .min_by(f32::total_cmp);
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data_mut(|d| match step {
+        Some(step) => d.insert_temp(id, (pass, step)),
+        None => d.remove::<(u64, f32)>(id),
+    });
+}
+
+/// Dim the content of muted tracks, or of all but the soloed tracks if any are soloed.
+fn paint_inaudible(ui: &egui::Ui, tracks: &TracksCtx) {
+    let audibility = tracks.audibility.take();
+    let any_soloed = audibility.iter().any(|a| a.soloed);
+    let color = tracks.timeline.style.inaudible_overlay(ui.visuals());
+    for a in audibility {
+        if a.muted || (any_soloed && !a.soloed) {
+            let shape = egui::Shape::rect_filled(a.rect, 0.0, color);
+            a.painter.set(a.overlay, shape);
         }
     }
 }
 
+/// Dim and hatch the given rect of a bypassed track in the warning color.
+fn paint_bypassed(ui: &egui::Ui, rect: egui::Rect) {
+    if !rect.is_positive() || !ui.clip_rect().intersects(rect) {
+        return;
+    }
+    let painter = ui.painter().with_clip_rect(ui.clip_rect().intersect(rect));
+    let color = ui.visuals().warn_fg_color.linear_multiply(0.2);
+    painter.rect_filled(rect, 0.0, ui.visuals().panel_fill.gamma_multiply(0.4));
+    paint_hatching(&painter, rect, egui::Stroke::new(1.0, color));
+}
+
+/// Draw a hatched lock pattern over the given rect, showing a "not allowed" cursor on hover.
+fn paint_locked(ui: &egui::Ui, rect: egui::Rect) {
+    if !rect.is_positive() || !ui.clip_rect().intersects(rect) {
+        return;
+    }
+    let painter = ui.painter().with_clip_rect(ui.clip_rect().intersect(rect));
+    let color = ui.visuals().weak_text_color().linear_multiply(0.25);
+    paint_hatching(&painter, rect, egui::Stroke::new(1.0, color));
+    if ui.rect_contains_pointer(rect) {
+        ui.ctx().set_cursor_icon(cursor::Target::Locked.icon(false));
+    }
+}
+
+/// Draw diagonal hatching over the given rect, clipped by the painter.
+pub(crate) fn paint_hatching(painter: &egui::Painter, rect: egui::Rect, stroke: egui::Stroke) {
+    let spacing = 8.0;
+    let h = rect.height();
+    let mut x = rect.left() - h;
+    while x < rect.right() {
+        let a = egui::Pos2::new(x, rect.bottom());
+        let b = egui::Pos2::new(x + h, rect.top());
+        painter.line_segment([a, b], stroke);
+        x += spacing;
+    }
+}
+
 impl TimelineCtx {
+    /// Whether or not edits touching the given range of ticks are locked, either because the
+    /// current track is locked or because the range overlaps one of the `locked_ranges`.
+    pub fn is_locked(&self, range: Range<f32>) -> bool {
+        self.track_locked
+            || self
+                .locked_ranges
+                .iter()
+                .any(|r| range.start <= r.end && r.start <= range.end)
+    }
+
+    /// The current selection, if set via `Timeline::selection`, including changes made by the
+    /// widgets laid out so far this frame.
+    pub fn selection(&self, ctx: &egui::Context) -> Option<selection::SelectionState> {
+        self.selection_id.and_then(|id| selection::load(ctx, id))
+    }
+
+    /// Update the selection, e.g. from a ruler's time selection, if set via `Timeline::selection`.
+    ///
+    /// The change is reported via `TracksLayout::selection_changed`.
+    pub fn set_selection(&self, ctx: &egui::Context, state: selection::SelectionState) {
+        if let Some(id) = self.selection_id {
+            selection::store(ctx, id, state);
+        }
+    }
+
+    /// The spacing in ticks between the finest lines drawn by `Show::paint_grid` during this pass,
+    /// if any, i.e. the grid's current subdivision. Changes with the zoom level.
+    pub fn grid_step(&self, ctx: &egui::Context) -> Option<f32> {
+        let pass = ctx.cumulative_pass_nr();
+        ctx.data(|d| d.get_temp::<(u64, f32)>(self.grid_id))
+            .filter(|&(stored, _)| stored == pass)
+            .map(|(_, step)| step)
+    }
+
     /// The number of visible ticks across the width of the timeline.
     pub fn visible_ticks(&self) -> f32 {
         self.visible_ticks
     }
 
+    /// The precise range of the timeline visible within the given `ui`, typically a track's.
+    ///
+    /// Hosts with large amounts of content should only query the content overlapping this window,
+    /// e.g. via `intervals::IntervalIndex`.
+    pub fn visible_window(&self, ui: &egui::Ui) -> VisibleWindow {
+        let rect = ui.clip_rect().intersect(self.full_rect);
+        let ticks = self.x_to_tick(rect.left()).max(0.0)..self.x_to_tick(rect.right()).max(0.0);
+        VisibleWindow { ticks, rect }
+    }
+
+    /// Convert the given tick offset from the start of the timeline to an x position.
+    pub fn tick_to_x(&self, tick: f32) -> f32 {
+        self.full_rect.left() + tick / self.ticks_per_point
+    }
+
+    /// Convert the given x position to a tick offset from the start of the timeline.
+    pub fn x_to_tick(&self, x: f32) -> f32 {
+        (x - self.full_rect.left()) * self.ticks_per_point
+    }
+
     /// Short-hand for drawing a plot within the timeline UI.
     ///
     /// The same as `egui::plot::Plot::new`, but sets some useful defaults before returning.
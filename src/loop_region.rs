@@ -0,0 +1,214 @@
+use super::{
+    arbiter::{self, Layer},
+    cursor::{self, Target},
+    gesture::{self, Gesture},
+    touch, TimelineCtx,
+};
+use std::{hash::Hash, ops::Range};
+
+/// The width of the interactive area at either end of the loop brace.
+pub const HANDLE_WIDTH: f32 = 6.0;
+
+/// Access to the loop region.
+pub trait LoopRegion {
+    /// The loop range in ticks relative to the start of the timeline, if any.
+    fn loop_range(&self) -> Option<Range<f32>>;
+    /// The loop range was created, moved or resized.
+    fn set_loop_range(&mut self, range: Range<f32>);
+    /// The loop range is being dragged, with the initial and current range.
+    fn loop_gesture(&mut self, _gesture: Gesture<Range<f32>>) {}
+}
+
+/// Instantiate a loop brace lane of the given height, typically pinned beneath the ruler.
+///
+/// Drag over the lane to create a new loop range, drag either end of the brace to resize it, or
+/// drag the brace itself to move it.
+pub fn brace(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    api: &mut dyn LoopRegion,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let w = ui.available_width();
+    let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(w, height), egui::Sense::hover());
+    let sense = arbiter::sense(ui, Layer::Loop, rect, id, egui::Sense::click_and_drag());
+    let mut response = ui.interact(rect, id, sense);
+    let ticks_per_point = timeline.ticks_per_point;
+    let before = api.loop_range();
+
+    // Create a new loop range by dragging over the lane.
+    let press_origin = ui.input(|i| i.pointer.press_origin());
+    if let (Some(a), Some(b), true) = (
+        press_origin,
+        response.interact_pointer_pos(),
+        response.dragged(),
+    ) {
+        let (l, r) = (a.x.min(b.x), a.x.max(b.x));
+        let range = timeline.x_to_tick(l).max(0.0)..timeline.x_to_tick(r).max(0.0);
+        if !range.is_empty() {
+            api.set_loop_range(range);
+            response.mark_changed();
+        }
+    }
+    let after = api.loop_range();
+    if let (Some(before), Some(after)) = (before.clone(), after) {
+        if let Some(gesture) = gesture::track(ui, &response, before, after) {
+            if let Gesture::Cancelled { initial } = &gesture {
+                api.set_loop_range(initial.clone());
+            }
+            api.loop_gesture(gesture);
+        }
+    }
+
+    // Interact with the existing loop range.
+    let range = match api.loop_range() {
+        Some(range) => range,
+        None => return response,
+    };
+    let x = egui::Rangef::new(
+        timeline.tick_to_x(range.start),
+        timeline.tick_to_x(range.end),
+    );
+    let brace_rect = egui::Rect::from_x_y_ranges(x, rect.y_range());
+    let handle = |ui: &mut egui::Ui, rect: egui::Rect, id: egui::Id, sense: egui::Sense| {
+        let sense = arbiter::sense(ui, Layer::Loop, rect, id, sense);
+        ui.interact(rect, id, sense)
+    };
+    let body = handle(ui, brace_rect, id.with("body"), egui::Sense::drag());
+    let mut start_rect = brace_rect;
+    start_rect.set_width(HANDLE_WIDTH);
+    let mut end_rect = brace_rect;
+    end_rect.min.x = brace_rect.right() - HANDLE_WIDTH;
+    let (start_rect, end_rect) = (
+        touch::inflate_x(ui, start_rect),
+        touch::inflate_x(ui, end_rect),
+    );
+    let start = handle(ui, start_rect, id.with("start"), egui::Sense::drag());
+    let end = handle(ui, end_rect, id.with("end"), egui::Sense::drag());
+    cursor::set(&body, Target::Move);
+    cursor::set(&start, Target::Edge);
+    cursor::set(&end, Target::Edge);
+
+    // Move or resize.
+    let min_len = HANDLE_WIDTH * 2.0 * ticks_per_point;
+    let mut new_range = range.clone();
+    if body.dragged() {
+        let delta = (body.drag_delta().x * ticks_per_point).max(-range.start);
+        new_range.start += delta;
+        new_range.end += delta;
+    }
+    if start.dragged() {
+        let delta = start.drag_delta().x * ticks_per_point;
+        new_range.start = (range.start + delta).min(range.end - min_len).max(0.0);
+    }
+    if end.dragged() {
+        let delta = end.drag_delta().x * ticks_per_point;
+        new_range.end = (range.end + delta).max(range.start + min_len);
+    }
+    if new_range != range {
+        api.set_loop_range(new_range.clone());
+        response.mark_changed();
+    }
+    for r in [&body, &start, &end] {
+        if let Some(gesture) = gesture::track(ui, r, range.clone(), new_range.clone()) {
+            if let Gesture::Cancelled { initial } = &gesture {
+                api.set_loop_range(initial.clone());
+                new_range = initial.clone();
+            }
+            api.loop_gesture(gesture);
+        }
+    }
+
+    // Draw the brace.
+    let x = egui::Rangef::new(
+        timeline.tick_to_x(new_range.start),
+        timeline.tick_to_x(new_range.end),
+    );
+    let brace_rect = egui::Rect::from_x_y_ranges(x, rect.y_range()).shrink2([0.0, 2.0].into());
+    let color = ui.visuals().selection.bg_fill;
+    let stroke = egui::Stroke::new(2.0, color);
+    ui.painter()
+        .rect_filled(brace_rect, 0.0, color.linear_multiply(0.25));
+    let top = brace_rect.top();
+    let points = vec![
+        brace_rect.left_bottom(),
+        egui::Pos2::new(brace_rect.left(), top),
+        egui::Pos2::new(brace_rect.right(), top),
+        brace_rect.right_bottom(),
+    ];
+    ui.painter().add(egui::Shape::line(points, stroke));
+
+    response
+}
+
+/// Paint the loop region as a translucent column over the given `rect`, typically the whole
+/// track area.
+///
+/// While the given `modifiers` are held, the loop boundaries may be dragged from anywhere within
+/// the column, moving whichever boundary is nearest to where the drag began.
+pub fn column(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    rect: egui::Rect,
+    ticks_per_point: f32,
+    modifiers: egui::Modifiers,
+    api: &mut dyn LoopRegion,
+) -> Option<egui::Response> {
+    let range = api.loop_range()?;
+    let id = ui.make_persistent_id(id_source);
+    let to_x = |tick: f32| rect.left() + tick / ticks_per_point;
+    let x = egui::Rangef::new(to_x(range.start), to_x(range.end));
+    let column_rect = egui::Rect::from_x_y_ranges(x, rect.y_range()).intersect(rect);
+    let held = ui.input(|i| i.modifiers.matches_logically(modifiers));
+    let dragging = ui.ctx().dragged_id() == Some(id);
+    let sense = match held || dragging {
+        true => arbiter::sense(ui, Layer::Loop, column_rect, id, egui::Sense::drag()),
+        false => egui::Sense::hover(),
+    };
+    let mut response = ui.interact(column_rect, id, sense);
+    if sense.drag {
+        cursor::set(&response, Target::Edge);
+    }
+
+    // Move whichever boundary is nearest to the press origin.
+    let end_id = id.with("end");
+    if response.drag_started() {
+        let origin = ui.input(|i| i.pointer.press_origin());
+        let is_end = origin.is_some_and(|p| (p.x - x.max).abs() < (p.x - x.min).abs());
+        ui.data_mut(|d| d.insert_temp(end_id, is_end));
+    }
+    let mut new_range = range.clone();
+    if response.dragged() {
+        let is_end: bool = ui.data(|d| d.get_temp(end_id)).unwrap_or(false);
+        let delta = response.drag_delta().x * ticks_per_point;
+        match is_end {
+            true => new_range.end = (range.end + delta).max(range.start),
+            false => new_range.start = (range.start + delta).clamp(0.0, range.end),
+        }
+    }
+    if new_range != range {
+        api.set_loop_range(new_range.clone());
+        response.mark_changed();
+    }
+    if let Some(gesture) = gesture::track(ui, &response, range, new_range.clone()) {
+        if let Gesture::Cancelled { initial } = &gesture {
+            api.set_loop_range(initial.clone());
+            new_range = initial.clone();
+        }
+        api.loop_gesture(gesture);
+    }
+
+    // Draw the column.
+    let x = egui::Rangef::new(to_x(new_range.start), to_x(new_range.end));
+    let column_rect = egui::Rect::from_x_y_ranges(x, rect.y_range()).intersect(rect);
+    let color = ui.visuals().selection.bg_fill;
+    let painter = ui.painter().with_clip_rect(ui.clip_rect().intersect(rect));
+    painter.rect_filled(column_rect, 0.0, color.linear_multiply(0.1));
+    let stroke = egui::Stroke::new(1.0, color.linear_multiply(0.5));
+    painter.vline(x.min, column_rect.y_range(), stroke);
+    painter.vline(x.max, column_rect.y_range(), stroke);
+
+    Some(response)
+}
@@ -0,0 +1,313 @@
+use super::{note::Note, ruler::MusicalInfo, Bar, TimeSig};
+use midly::{MetaMessage, MidiMessage, Smf, Timing, TrackEventKind};
+use std::{fmt, ops::Range};
+
+/// The tempo assumed when a file doesn't specify one.
+pub const DEFAULT_TEMPO: f64 = 120.0;
+
+/// The musical content of a Standard MIDI File.
+///
+/// All positions are in absolute ticks from the start of the file, using the file's resolution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Song {
+    /// The number of ticks per quarter note beat.
+    pub ticks_per_beat: u32,
+    /// Tempo changes sorted by tick. Always contains at least one change at tick `0`.
+    pub tempos: Vec<TempoChange>,
+    /// Time signature changes sorted by tick. Always contains at least one change at tick `0`.
+    pub time_sigs: Vec<TimeSigChange>,
+    /// The tracks containing notes or a name, in file order.
+    pub tracks: Vec<Track>,
+}
+
+/// A change in tempo.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TempoChange {
+    /// The position of the change in ticks.
+    pub tick: f32,
+    /// The new tempo in beats per minute.
+    pub bpm: f64,
+}
+
+/// A change in time signature, assumed to fall on a bar line.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TimeSigChange {
+    /// The position of the change in ticks.
+    pub tick: f32,
+    /// The new time signature.
+    pub time_sig: TimeSig,
+}
+
+/// A track of notes, typically displayed as a single clip.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Track {
+    /// The name of the track, if any.
+    pub name: Option<String>,
+    /// The notes sorted by start tick.
+    pub notes: Vec<Note>,
+}
+
+/// A `Song` viewed from the given start tick, implementing `MusicalInfo`.
+#[derive(Copy, Clone, Debug)]
+pub struct SongView<'a> {
+    /// The song being viewed.
+    pub song: &'a Song,
+    /// The absolute tick at the start of the visible timeline.
+    pub start: f32,
+    /// The number of ticks per point along the x axis.
+    pub ticks_per_point: f32,
+}
+
+/// Errors that may occur while loading a MIDI file.
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be parsed.
+    Parse(midly::Error),
+    /// The file uses SMPTE timecode rather than musical timing, which isn't supported.
+    Timecode,
+}
+
+/// Load a Standard MIDI File from the given bytes.
+///
+/// Notes are paired by channel and key, with notes left held at the end of a track ending at the
+/// track's last event. Tracks without a name or notes, e.g. a dedicated tempo track, are skipped.
+pub fn load(bytes: &[u8]) -> Result<Song, Error> {
+    let smf = Smf::parse(bytes).map_err(Error::Parse)?;
+    let ticks_per_beat = match smf.header.timing {
+        Timing::Metrical(t) => t.as_int() as u32,
+        Timing::Timecode(..) => return Err(Error::Timecode),
+    };
+    let mut tempos = vec![];
+    let mut time_sigs = vec![];
+    let mut tracks = vec![];
+    for events in &smf.tracks {
+        let mut tick = 0u64;
+        let mut track = Track::default();
+        // The channel, key, start tick and velocity of each held note.
+        let mut held: Vec<(u8, u8, u64, u8)> = vec![];
+        for event in events {
+            tick += event.delta.as_int() as u64;
+            match event.kind {
+                TrackEventKind::Meta(MetaMessage::Tempo(us_per_beat)) => {
+                    let bpm = 60_000_000.0 / us_per_beat.as_int().max(1) as f64;
+                    let tick = tick as f32;
+                    tempos.push(TempoChange { tick, bpm });
+                }
+                TrackEventKind::Meta(MetaMessage::TimeSignature(top, pow, _, _)) => {
+                    let time_sig = TimeSig {
+                        top: (top as u16).max(1),
+                        bottom: 1 << pow.min(15),
+                    };
+                    let tick = tick as f32;
+                    time_sigs.push(TimeSigChange { tick, time_sig });
+                }
+                TrackEventKind::Meta(MetaMessage::TrackName(name)) => {
+                    track.name = Some(String::from_utf8_lossy(name).into_owned());
+                }
+                TrackEventKind::Midi { channel, message } => {
+                    let channel = channel.as_int();
+                    match message {
+                        MidiMessage::NoteOn { key, vel } if vel.as_int() > 0 => {
+                            held.push((channel, key.as_int(), tick, vel.as_int()));
+                        }
+                        MidiMessage::NoteOn { key, .. } | MidiMessage::NoteOff { key, .. } => {
+                            let key = key.as_int();
+                            if let Some(ix) = held.iter().position(|h| (h.0, h.1) == (channel, key))
+                            {
+                                let (_, key, start, velocity) = held.remove(ix);
+                                let ticks = start as f32..tick as f32;
+                                track.notes.push(Note {
+                                    ticks,
+                                    key,
+                                    velocity,
+                                });
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                _ => (),
+            }
+        }
+        for (_, key, start, velocity) in held {
+            let ticks = start as f32..tick as f32;
+            track.notes.push(Note {
+                ticks,
+                key,
+                velocity,
+            });
+        }
+        track
+            .notes
+            .sort_by(|a, b| a.ticks.start.total_cmp(&b.ticks.start));
+        if track.name.is_some() || !track.notes.is_empty() {
+            tracks.push(track);
+        }
+    }
+
+    // Ensure there is always a tempo and time signature from the start.
+    tempos.sort_by(|a, b| a.tick.total_cmp(&b.tick));
+    time_sigs.sort_by(|a, b| a.tick.total_cmp(&b.tick));
+    if tempos.first().map_or(true, |t| t.tick > 0.0) {
+        let bpm = DEFAULT_TEMPO;
+        tempos.insert(0, TempoChange { tick: 0.0, bpm });
+    }
+    if time_sigs.first().map_or(true, |t| t.tick > 0.0) {
+        let time_sig = TimeSig { top: 4, bottom: 4 };
+        time_sigs.insert(
+            0,
+            TimeSigChange {
+                tick: 0.0,
+                time_sig,
+            },
+        );
+    }
+
+    Ok(Song {
+        ticks_per_beat,
+        tempos,
+        time_sigs,
+        tracks,
+    })
+}
+
+impl Song {
+    /// The tempo in beats per minute at the given tick.
+    pub fn tempo_at(&self, tick: f32) -> f64 {
+        self.tempos
+            .iter()
+            .take_while(|t| t.tick <= tick)
+            .last()
+            .map_or(DEFAULT_TEMPO, |t| t.bpm)
+    }
+
+    /// The time in seconds at the given tick, accounting for all tempo changes before it.
+    pub fn seconds_at(&self, tick: f32) -> f64 {
+        let ticks_per_beat = self.ticks_per_beat.max(1) as f64;
+        let mut seconds = 0.0;
+        let mut prev = TempoChange {
+            tick: 0.0,
+            bpm: DEFAULT_TEMPO,
+        };
+        for change in self.tempos.iter().take_while(|t| t.tick < tick) {
+            let beats = (change.tick - prev.tick) as f64 / ticks_per_beat;
+            seconds += beats * 60.0 / prev.bpm;
+            prev = *change;
+        }
+        let beats = (tick - prev.tick) as f64 / ticks_per_beat;
+        seconds + beats * 60.0 / prev.bpm
+    }
+
+    /// The bar containing the given tick, with its range in absolute ticks.
+    ///
+    /// Bars are counted from each time signature change, with the last bar before a change cut
+    /// short if the change doesn't fall on a bar line.
+    pub fn bar_at(&self, tick: f32) -> Bar {
+        let ix = self
+            .time_sigs
+            .iter()
+            .rposition(|t| t.tick <= tick)
+            .unwrap_or(0);
+        let change = self.time_sigs[ix];
+        let next = self.time_sigs.get(ix + 1).map(|t| t.tick);
+        let bar_ticks = change.time_sig.beats_per_bar() * self.ticks_per_beat as f32;
+        let n = ((tick - change.tick) / bar_ticks).floor();
+        let start = change.tick + n * bar_ticks;
+        let end = (start + bar_ticks).min(next.unwrap_or(f32::INFINITY));
+        Bar {
+            tick_range: start..end,
+            time_sig: change.time_sig,
+        }
+    }
+
+    /// The range in ticks spanned by all notes.
+    pub fn tick_range(&self) -> Range<f32> {
+        self.tracks
+            .iter()
+            .filter(|t| !t.notes.is_empty())
+            .map(Track::tick_range)
+            .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+            .unwrap_or(0.0..0.0)
+    }
+
+    /// View the song from the given start tick, e.g. to lay out the timeline and ruler.
+    pub fn view(&self, start: f32, ticks_per_point: f32) -> SongView<'_> {
+        SongView {
+            song: self,
+            start,
+            ticks_per_point,
+        }
+    }
+}
+
+impl Track {
+    /// The range in ticks spanned by the track's notes, e.g. for displaying them as a clip.
+    pub fn tick_range(&self) -> Range<f32> {
+        let start = self
+            .notes
+            .iter()
+            .map(|n| n.ticks.start)
+            .fold(f32::MAX, f32::min);
+        let end = self
+            .notes
+            .iter()
+            .map(|n| n.ticks.end)
+            .fold(f32::MIN, f32::max);
+        match self.notes.is_empty() {
+            true => 0.0..0.0,
+            false => start..end,
+        }
+    }
+}
+
+impl<'a> MusicalInfo for SongView<'a> {
+    fn ticks_per_beat(&self) -> u32 {
+        self.song.ticks_per_beat
+    }
+    fn bar_at_ticks(&self, tick: f32) -> Bar {
+        let bar = self.song.bar_at(self.start + tick);
+        Bar {
+            tick_range: bar.tick_range.start - self.start..bar.tick_range.end - self.start,
+            time_sig: bar.time_sig,
+        }
+    }
+    fn ticks_per_point(&self) -> f32 {
+        self.ticks_per_point
+    }
+    fn first_bar_number(&self) -> i64 {
+        // Count the bars within each time signature up to the start of the view.
+        let mut number = 1;
+        for (ix, change) in self.song.time_sigs.iter().enumerate() {
+            if change.tick > self.start {
+                break;
+            }
+            let bar_ticks = change.time_sig.beats_per_bar() * self.song.ticks_per_beat as f32;
+            let next = self.song.time_sigs.get(ix + 1).map(|t| t.tick);
+            let bars = match next.filter(|&tick| tick <= self.start) {
+                // Include the last bar of the time signature, even if cut short.
+                Some(end) => ((end - change.tick) / bar_ticks).ceil(),
+                None => ((self.start - change.tick) / bar_ticks).floor(),
+            };
+            number += bars as i64;
+        }
+        number
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "failed to parse MIDI file: {}", err),
+            Error::Timecode => write!(f, "MIDI files with SMPTE timecode are not supported"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(err) => Some(err),
+            Error::Timecode => None,
+        }
+    }
+}
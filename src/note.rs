@@ -0,0 +1,12 @@
+use std::ops::Range;
+
+/// A single note, e.g. within a piano roll or drum lane.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Note {
+    /// The start and end of the note in ticks.
+    pub ticks: Range<f32>,
+    /// The MIDI key number, where `60` is middle C.
+    pub key: u8,
+    /// The MIDI velocity in the range `1..=127`.
+    pub velocity: u8,
+}
@@ -0,0 +1,296 @@
+use super::{
+    arbiter::{self, Layer},
+    cursor::{self, Target},
+    gesture::{self, Gesture},
+    note::Note,
+    touch, zoom, TimelineCtx,
+};
+use std::{
+    hash::Hash,
+    ops::{Range, RangeInclusive},
+};
+
+/// The width of the interactive area at the end of a note used for resizing.
+pub const EDGE_WIDTH: f32 = 4.0;
+/// The minimum length of a resized note in points.
+pub const MIN_NOTE_WIDTH: f32 = 4.0;
+/// The width of the mark drawn beside each chord tone row.
+pub const CHORD_MARK_WIDTH: f32 = 3.0;
+/// The names of the pitch classes starting from `C`.
+pub const PITCH_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// A set of pitch classes, where bit `n` represents the pitch class `n` semitones above `C`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PitchClasses(pub u16);
+
+/// The scale and chord in effect over a range of the piano roll.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Harmony {
+    /// The start and end of the region in ticks relative to the start of the timeline.
+    pub ticks: Range<f32>,
+    /// The pitch classes within the scale, whose rows are tinted.
+    pub scale: PitchClasses,
+    /// The pitch classes of the chord, whose rows are marked.
+    pub chord: PitchClasses,
+}
+
+/// Access to the notes displayed within a piano roll lane.
+pub trait PianoRoll {
+    /// The range of keys displayed from the bottom to the top of the lane. By default, all 128
+    /// MIDI keys.
+    fn key_range(&self) -> RangeInclusive<u8> {
+        0..=127
+    }
+    /// The total number of editable notes.
+    fn note_count(&self) -> usize;
+    /// The note at the given index, in ticks relative to the start of the timeline.
+    fn note(&self, index: usize) -> Note;
+    /// The note at the given index was moved or resized by dragging.
+    fn set_note(&mut self, index: usize, note: Note);
+    /// The note at the given index is being dragged, with the initial and current note.
+    fn note_gesture(&mut self, _index: usize, _gesture: Gesture<Note>) {}
+    /// Reference notes drawn dimmed behind the editable notes, e.g. another track's part while
+    /// writing harmonies. Ghost notes may not be interacted with.
+    ///
+    /// Returns no notes by default.
+    fn ghost_notes(&self) -> &[Note] {
+        &[]
+    }
+    /// The scale and chord regions highlighted behind the notes, e.g. from a chord track.
+    ///
+    /// Returns no regions by default.
+    fn harmony(&self) -> &[Harmony] {
+        &[]
+    }
+}
+
+impl PitchClasses {
+    /// The pitch classes of the given keys, e.g. `&[0, 4, 7]` for a C major triad.
+    pub fn new(keys: &[u8]) -> Self {
+        keys.iter().fold(Self::default(), |pcs, &key| pcs.with(key))
+    }
+
+    /// The same set including the pitch class of the given key.
+    pub fn with(self, key: u8) -> Self {
+        Self(self.0 | 1 << (key % 12))
+    }
+
+    /// Whether or not the pitch class of the given key is within the set.
+    pub fn contains(self, key: u8) -> bool {
+        self.0 & 1 << (key % 12) != 0
+    }
+}
+
+/// Whether or not the given key is a black key on a piano.
+pub fn is_black_key(key: u8) -> bool {
+    matches!(key % 12, 1 | 3 | 6 | 8 | 10)
+}
+
+/// The name of the given key, e.g. `C4` for middle C (`60`).
+pub fn key_name(key: u8) -> String {
+    let octave = key as i32 / 12 - 1;
+    format!("{}{}", PITCH_NAMES[key as usize % 12], octave)
+}
+
+/// Instantiate a piano roll lane of the given height.
+///
+/// Each key is drawn as a row, with black key rows shaded and each `C` labelled along the left
+/// edge of the visible area. Drag a note to move it in time and pitch, or drag its end to resize
+/// it. Ghost notes are drawn dimmed behind the editable notes.
+///
+/// Rows within the scale of each `PianoRoll::harmony` region are tinted and chord tones are
+/// marked at the left of the region, or of the visible area once the region's start scrolls out
+/// of view.
+///
+/// Scroll with `zoom::Modifiers::value` held to zoom the key axis, see `zoom::value_viewport`.
+pub fn lane(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    api: &mut dyn PianoRoll,
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let w = ui.available_width();
+    let desired_size = egui::Vec2::new(w, height);
+    let (rect, mut response) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+
+    // Transforms between keys and screen space, where each key spans one unit.
+    let keys = api.key_range();
+    let full_range = *keys.start() as f32..=*keys.end() as f32 + 1.0;
+    let key_range = zoom::value_viewport(ui, id, rect, full_range, timeline);
+    let prev_clip_rect = ui.clip_rect();
+    ui.set_clip_rect(prev_clip_rect.intersect(rect));
+    let y_range = rect.y_range().flip();
+    let to_y = |key: f32| egui::remap(key, key_range.clone(), y_range);
+    let key_h = rect.height() / (key_range.end() - key_range.start());
+    let note_rect = |note: &Note| {
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(note.ticks.start),
+            timeline.tick_to_x(note.ticks.end),
+        );
+        let y = egui::Rangef::new(to_y(note.key as f32 + 1.0), to_y(note.key as f32));
+        egui::Rect::from_x_y_ranges(x, y)
+    };
+
+    // Shade the black key rows and label each `C`.
+    let visuals = ui.style().noninteractive();
+    let black_fill = visuals.bg_stroke.color.linear_multiply(0.15);
+    let octave_stroke = egui::Stroke {
+        color: visuals.bg_stroke.color.linear_multiply(0.5),
+        ..visuals.bg_stroke
+    };
+    let label_x = ui.clip_rect().left() + 2.0;
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let text_color = visuals.text_color();
+    let first = key_range.start().floor().max(0.0) as u8;
+    let last = key_range.end().ceil().min(128.0) as u8;
+    for key in first..last {
+        let y = egui::Rangef::new(to_y(key as f32 + 1.0), to_y(key as f32));
+        if is_black_key(key) {
+            let row = egui::Rect::from_x_y_ranges(rect.x_range(), y);
+            ui.painter().rect_filled(row, 0.0, black_fill);
+        }
+        if key % 12 == 0 {
+            ui.painter().hline(rect.x_range(), y.max, octave_stroke);
+            if key_h >= font_id.size * 0.75 {
+                let pos = egui::Pos2::new(label_x, y.center());
+                let anchor = egui::Align2::LEFT_CENTER;
+                let name = key_name(key);
+                ui.painter()
+                    .text(pos, anchor, name, font_id.clone(), text_color);
+            }
+        }
+    }
+
+    // Tint the in-scale rows and mark the chord tones of each harmony region.
+    let visible = ui.clip_rect();
+    let accent = ui.visuals().selection.bg_fill;
+    let scale_fill = accent.linear_multiply(0.08);
+    let chord_fill = accent.linear_multiply(0.2);
+    for harmony in api.harmony() {
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(harmony.ticks.start),
+            timeline.tick_to_x(harmony.ticks.end),
+        );
+        let x = x.intersection(visible.x_range());
+        if x.span() <= 0.0 {
+            continue;
+        }
+        for key in first..last {
+            let y = egui::Rangef::new(to_y(key as f32 + 1.0), to_y(key as f32));
+            let row = egui::Rect::from_x_y_ranges(x, y);
+            if harmony.scale.contains(key) {
+                ui.painter().rect_filled(row, 0.0, scale_fill);
+            }
+            if harmony.chord.contains(key) {
+                ui.painter().rect_filled(row, 0.0, chord_fill);
+                let mark_x = egui::Rangef::new(x.min, (x.min + CHORD_MARK_WIDTH).min(x.max));
+                let mark = egui::Rect::from_x_y_ranges(mark_x, y);
+                ui.painter().rect_filled(mark, 0.0, accent);
+            }
+        }
+    }
+
+    // Draw the ghost notes behind the editable notes.
+    let ghost_fill = visuals.text_color().linear_multiply(0.15);
+    let ghost_stroke = egui::Stroke::new(1.0, visuals.text_color().linear_multiply(0.3));
+    for note in api.ghost_notes() {
+        let ghost_rect = note_rect(note);
+        if visible.intersects(ghost_rect) {
+            ui.painter().rect(ghost_rect, 1.0, ghost_fill, ghost_stroke);
+        }
+    }
+
+    // Interact with and draw the editable notes.
+    let color = timeline
+        .track_color
+        .unwrap_or(ui.visuals().selection.bg_fill);
+    let min_len = MIN_NOTE_WIDTH * timeline.ticks_per_point;
+    for ix in 0..api.note_count() {
+        let note = api.note(ix);
+        // Always interact with a dragged note so that its gesture ends, even out of view.
+        let n_rect = note_rect(&note);
+        let note_id = id.with(ix);
+        let ctx = ui.ctx();
+        let dragged = ctx.is_being_dragged(note_id) || ctx.is_being_dragged(note_id.with("end"));
+        if !visible.intersects(n_rect) && !dragged {
+            continue;
+        }
+        let body_sense = arbiter::sense(
+            ui,
+            Layer::Clip,
+            n_rect,
+            note_id,
+            egui::Sense::click_and_drag(),
+        );
+        let body = ui.interact(n_rect, note_id, body_sense);
+        let mut end_rect = n_rect;
+        end_rect.min.x = (n_rect.right() - EDGE_WIDTH).max(n_rect.center().x);
+        let end_rect = touch::inflate_x(ui, end_rect);
+        let end_id = note_id.with("end");
+        let end_sense = arbiter::sense(ui, Layer::Handle, end_rect, end_id, egui::Sense::drag());
+        let end = ui.interact(end_rect, end_id, end_sense);
+        cursor::set(&body, Target::Move);
+        cursor::set(&end, Target::Edge);
+
+        // Move or resize relative to the note at the start of the drag, unless locked.
+        let initial_id = note_id.with("initial");
+        if body.drag_started() || end.drag_started() {
+            ui.data_mut(|d| d.insert_temp(initial_id, note.clone()));
+        }
+        let initial: Option<Note> = ui.data(|d| d.get_temp(initial_id));
+        let origin = ui.input(|i| i.pointer.press_origin());
+        let pointer = body.interact_pointer_pos().or(end.interact_pointer_pos());
+        let mut new_note = note.clone();
+        if let (Some(initial), Some(origin), Some(pos)) = (initial, origin, pointer) {
+            let delta_ticks = (pos.x - origin.x) * timeline.ticks_per_point;
+            if body.dragged() {
+                let delta_keys = ((origin.y - pos.y) / key_h).round() as i32;
+                let start = (initial.ticks.start + delta_ticks).max(0.0);
+                let len = initial.ticks.end - initial.ticks.start;
+                new_note.ticks = start..start + len;
+                new_note.key = (initial.key as i32 + delta_keys)
+                    .clamp(*keys.start() as i32, *keys.end() as i32)
+                    as u8;
+            } else if end.dragged() {
+                let end = (initial.ticks.end + delta_ticks).max(initial.ticks.start + min_len);
+                new_note.ticks.end = end;
+            }
+        }
+        let locked = |n: &Note| timeline.is_locked(n.ticks.clone());
+        if new_note != note && !locked(&note) && !locked(&new_note) {
+            api.set_note(ix, new_note.clone());
+            response.mark_changed();
+        }
+        if body.drag_stopped() || end.drag_stopped() {
+            ui.data_mut(|d| d.remove::<Note>(initial_id));
+        }
+        let after = api.note(ix);
+        for handle in [&body, &end] {
+            if let Some(gesture) = gesture::track(ui, handle, note.clone(), after.clone()) {
+                if let Gesture::Cancelled { ref initial } = gesture {
+                    api.set_note(ix, initial.clone());
+                    ui.data_mut(|d| d.remove::<Note>(initial_id));
+                }
+                api.note_gesture(ix, gesture);
+            }
+        }
+
+        // Draw the note, with its opacity reflecting its velocity.
+        let n_rect = note_rect(&api.note(ix));
+        let interacting = body.hovered() || body.dragged() || end.hovered() || end.dragged();
+        let strength = 0.4 + 0.6 * note.velocity.min(127) as f32 / 127.0;
+        let fill = color.linear_multiply(strength);
+        let stroke = match interacting {
+            true => ui.visuals().widgets.hovered.fg_stroke,
+            false => egui::Stroke::new(1.0, color),
+        };
+        ui.painter().rect(n_rect, 1.0, fill, stroke);
+    }
+
+    ui.set_clip_rect(prev_clip_rect);
+    response
+}
@@ -1,15 +1,137 @@
-use super::ruler::MusicalInfo;
+use super::{
+    arbiter::{self, Layer},
+    cursor::{self, Target},
+    format::TimeFormatter,
+    gesture::{self, Gesture},
+    ruler::MusicalInfo,
+    scrub::{self, Scrub},
+    style,
+    time::TimeDomain,
+    touch,
+};
+use std::{ops::Range, time::Duration};
+
+/// The minimum width of the text field used to edit the playhead position.
+pub const LABEL_EDIT_MIN_WIDTH: f32 = 80.0;
+
+/// The size of the cap drawn at the top of the playhead.
+pub const CAP_SIZE: f32 = 8.0;
+/// The number of translucent layers drawn to produce the glow while playing.
+pub const GLOW_LAYERS: usize = 3;
+
+/// Visual configuration for the playhead.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The style of the playhead line.
+    pub line: Line,
+    /// Overrides the color of the line and cap, otherwise derived from the interaction visuals.
+    pub color: Option<egui::Color32>,
+    /// The shape drawn at the top of the playhead.
+    pub cap: Cap,
+    /// Whether or not to draw a soft glow around the line while `Info::is_playing`.
+    pub glow_while_playing: bool,
+    /// Overrides the color of the line and cap while the playhead is being dragged.
+    pub drag_color: Option<egui::Color32>,
+    /// Whether or not to clamp the playhead to `Info::bounds` when it is displayed or set.
+    pub clamp_to_bounds: bool,
+    /// Whether or not to disable interaction with the playhead while it is outside the visible
+    /// timeline area, so that it can't swallow clicks intended for widgets at the edges.
+    pub hide_offscreen: bool,
+    /// Whether or not the line extends to the bottom of the timeline, or stops at the bottom of
+    /// the last track.
+    pub extend_beyond_last_track: bool,
+    /// Whether or not the playhead may be dragged, clicked or edited. When `false`, the playhead
+    /// is still drawn but never calls `Interaction::set_playhead_ticks`.
+    pub interactive: bool,
+    /// If set, requests repaints at this rate in Hz while `Info::is_playing`, e.g. `30.0`.
+    ///
+    /// Lets the playhead move smoothly during playback at a capped rate while the rest of the UI
+    /// repaints reactively, reducing GPU load on battery-powered devices. Rates above the
+    /// monitor's refresh rate have no further effect. By default, the host drives repaints.
+    pub playing_repaint_rate: Option<f32>,
+    /// Overrides the color of the return marker drawn at `Info::return_ticks`, otherwise the weak
+    /// text color.
+    pub return_color: Option<egui::Color32>,
+}
+
+/// The style of the playhead line.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Line {
+    Solid,
+    /// A dashed line with the given dash and gap lengths in points.
+    Dashed {
+        dash: f32,
+        gap: f32,
+    },
+}
+
+/// The shape drawn at the top of the playhead.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Cap {
+    None,
+    /// A downward pointing triangle centered on the line.
+    Triangle,
+    /// A small rectangular flag to the right of the line.
+    Flag,
+}
+
+/// A beat crossed by the playhead during playback, reported via `Interaction::beat_crossed`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Beat {
+    /// The position of the beat in ticks relative to the start of the timeline.
+    pub tick: f32,
+    /// The index of the beat within its bar, where `0` is the downbeat.
+    pub index_in_bar: usize,
+    /// The metric strength of the beat. See `TimeSig::metric_level`.
+    pub level: u8,
+}
 
 /// For retrieving information about the playhead.
-pub trait Info: MusicalInfo {
+pub trait Info: TimeDomain {
     /// The location of the playhead in ticks relative to the start of the timeline.
     fn playhead_ticks(&self) -> f32;
+    /// An optional formatter used to display the playhead position in a label at its top.
+    ///
+    /// Double-clicking the label allows for typing an exact position, parsed by the formatter.
+    fn formatter(&self) -> Option<&dyn TimeFormatter> {
+        None
+    }
+    /// The bounds of the project in ticks relative to the start of the timeline, used when
+    /// clamping the playhead. By default, returns `None` meaning the project is unbounded.
+    fn bounds(&self) -> Option<Range<f32>> {
+        None
+    }
+    /// Whether or not playback is in progress, used to enable the playhead glow.
+    fn is_playing(&self) -> bool {
+        false
+    }
+    /// The position in ticks from which playback will resume, or to which the playhead returns
+    /// when stopped, drawn as a secondary dashed marker distinct from the playhead.
+    ///
+    /// Returns `None` by default, in which case no marker is drawn.
+    fn return_ticks(&self) -> Option<f32> {
+        None
+    }
 }
 
 /// For handling interaction with the playhead.
 pub trait Interaction {
     /// Set the location of the playhead in ticks.
     fn set_playhead_ticks(&mut self, ticks: f32);
+    /// The playhead is being scrubbed, with the initial and current location in ticks.
+    fn playhead_gesture(&mut self, _gesture: Gesture<f32>) {}
+    /// The playhead is being dragged, with the position under the pointer and the velocity of the
+    /// drag, or the drag ended. Useful for varispeed scrub audio.
+    fn scrub(&mut self, _scrub: Scrub) {}
+    /// The playhead crossed a beat during playback since the previous frame, e.g. for flashing a
+    /// beat indicator or driving metronome visuals.
+    ///
+    /// Only reported while `Info::is_playing` within a musical domain, i.e. where
+    /// `TimeDomain::musical_info` returns `Some`. If several beats were crossed within a single
+    /// frame, only the last is reported. After a jump, i.e. moving backwards (e.g. looping back to
+    /// the start of a loop region) or being clicked, the beat is only reported if the playhead
+    /// landed within its previous per-frame advance of it.
+    fn beat_crossed(&mut self, _beat: Beat) {}
 }
 
 /// For both providing info and handling interaction.
@@ -17,41 +139,347 @@ pub trait Playhead: Info + Interaction {}
 
 impl<T> Playhead for T where T: Info + Interaction {}
 
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            line: Line::Solid,
+            color: None,
+            cap: Cap::None,
+            glow_while_playing: false,
+            drag_color: None,
+            clamp_to_bounds: false,
+            hide_offscreen: false,
+            extend_beyond_last_track: true,
+            interactive: true,
+            playing_repaint_rate: None,
+            return_color: None,
+        }
+    }
+}
+
 /// Set the playhead widget - a thin line for indicating progress through the timeline.
-pub fn set(ui: &mut egui::Ui, timeline_rect: egui::Rect, api: &mut dyn Playhead) -> egui::Response {
+///
+/// The `tracks_bottom` is the y position of the bottom of the last track, at which the line stops
+/// unless `Config::extend_beyond_last_track` is set. The `timeline_start` is the absolute tick at
+/// the start of the view as per `TimelineApi::timeline_start`, used to follow the playhead in
+/// absolute ticks when reporting beats so that scrolling the view isn't mistaken for a jump. The
+/// playhead's ID is derived from the `ui`, so at most one playhead should be set per `ui`.
+///
+/// The width of the line is the `TimelineStyle::playhead_width` of the timeline containing the
+/// `ui`, as set up by `TracksLayout::playhead`.
+pub fn set(
+    ui: &mut egui::Ui,
+    timeline_rect: egui::Rect,
+    tracks_bottom: f32,
+    timeline_start: Option<f32>,
+    config: &Config,
+    api: &mut dyn Playhead,
+) -> egui::Response {
+    let mut timeline_rect = timeline_rect;
+    if !config.extend_beyond_last_track {
+        timeline_rect.max.y = tracks_bottom.clamp(timeline_rect.top(), timeline_rect.bottom());
+    }
+
+    // Clamp the playhead to the project bounds if necessary.
+    let bounds = api.bounds().filter(|_| config.clamp_to_bounds);
+    let clamp = |ticks: f32| match bounds {
+        Some(ref b) => ticks.max(b.start).min(b.end),
+        None => ticks,
+    };
+
     // Allocate a thin `Rect` over the timeline at the playhead.
-    let playhead_ticks = api.playhead_ticks();
-    let playhead_x = timeline_rect.left() + playhead_ticks / api.ticks_per_point();
-    let playhead_w = 1.0;
+    let playhead_ticks = clamp(api.playhead_ticks());
+    let playhead_x = timeline_rect.left() + playhead_ticks / api.units_per_point();
+    let width = style::current(ui).playhead_width;
+    let playhead_w = width.max(1.0);
     let half_w = playhead_w * 0.5;
     let min = egui::Pos2::new(playhead_x - half_w, timeline_rect.top());
     let max = egui::Pos2::new(playhead_x + half_w, timeline_rect.bottom());
-    let rect = egui::Rect::from_min_max(min, max);
-    let mut response = ui.allocate_rect(rect, egui::Sense::click_and_drag());
+    let rect = touch::inflate_x(ui, egui::Rect::from_min_max(min, max));
+    let visible = timeline_rect.x_range().contains(playhead_x);
+    let id = ui.id().with("playhead");
+    let dragging = ui.ctx().dragged_id() == Some(id);
+    let sense = if !config.interactive {
+        egui::Sense::hover()
+    } else if visible || dragging || !config.hide_offscreen {
+        let sense = egui::Sense::click_and_drag();
+        arbiter::sense(ui, Layer::Playhead, rect, id, sense)
+    } else {
+        egui::Sense::hover()
+    };
+    ui.advance_cursor_after_rect(rect);
+    let mut response = ui.interact(rect, id, sense);
+    if sense.drag {
+        cursor::set(&response, Target::Edge);
+    }
 
     let timeline_w = timeline_rect.width();
-    let ticks_per_point = api.ticks_per_point();
+    let ticks_per_point = api.units_per_point();
     let visible_ticks = ticks_per_point * timeline_w;
 
     // Handle interactions.
+    let before = api.playhead_ticks();
     if response.clicked() || response.dragged() {
         if let Some(pt) = response.interact_pointer_pos() {
             let tick = (((pt.x - timeline_rect.min.x) / timeline_w) * visible_ticks).max(0.0);
-            api.set_playhead_ticks(tick);
+            api.set_playhead_ticks(clamp(api.snap(tick)));
             response.mark_changed();
         }
     }
+    if let Some(gesture) = gesture::track(ui, &response, before, api.playhead_ticks()) {
+        if let Gesture::Cancelled { initial } = gesture {
+            api.set_playhead_ticks(initial);
+        }
+        api.playhead_gesture(gesture);
+    }
+    let pointer = ui.input(|i| i.pointer.latest_pos());
+    if let Some(pt) = response.interact_pointer_pos().or(pointer) {
+        let tick = clamp(((pt.x - timeline_rect.min.x) * ticks_per_point).max(0.0));
+        if let Some(scrub) = scrub::track(ui, &response, tick) {
+            api.scrub(scrub);
+        }
+    }
+
+    // Report the last beat crossed since the previous frame during playback, following the
+    // playhead in absolute ticks so that scrolling the view doesn't move it.
+    let beat_id = response.id.with("beat");
+    let prev: Option<(f32, f32)> = ui.data(|d| d.get_temp(beat_id));
+    let playing = api.is_playing() && !dragging;
+    let tick = api.playhead_ticks();
+    let abs_tick = timeline_start.unwrap_or(0.0) + tick;
+    let (beat, advance) = match api.musical_info().filter(|_| playing) {
+        None => (None, None),
+        Some(info) => match prev {
+            None => (crossed_beat(info, tick - 0.5, tick), Some(0.0)),
+            Some((prev, advance)) => {
+                let delta = abs_tick - prev;
+                match delta >= 0.0 && !response.changed() {
+                    true => (crossed_beat(info, tick - delta, tick), Some(delta)),
+                    false => (crossed_beat(info, tick - advance, tick), Some(advance)),
+                }
+            }
+        },
+    };
+    ui.data_mut(|d| match advance {
+        Some(advance) => d.insert_temp(beat_id, (abs_tick, advance)),
+        None => d.remove::<(f32, f32)>(beat_id),
+    });
+    if let Some(beat) = beat {
+        api.beat_crossed(beat);
+    }
+
+    // Draw the return marker beneath the playhead, recording the painted area for repaint hints.
+    let mut painted = None;
+    if let Some(ticks) = api.return_ticks().map(clamp) {
+        let x = timeline_rect.left() + ticks / ticks_per_point;
+        if timeline_rect.x_range().contains(x) {
+            let color = config
+                .return_color
+                .unwrap_or_else(|| ui.visuals().weak_text_color());
+            painted = Some(paint_return_marker(ui, x, timeline_rect.y_range(), color));
+        }
+    }
 
-    // Draw a thin rect.
-    if timeline_rect.x_range().contains(playhead_x) {
+    // Draw the playhead line.
+    if visible {
         let visuals = ui.style().interact(&response);
         let radius = 0.0;
         let stroke = egui::Stroke {
-            width: 0.5,
+            width: style::snap_stroke_width(ui.ctx(), 0.5),
             ..visuals.fg_stroke
         };
-        ui.painter().rect(rect, radius, visuals.bg_fill, stroke);
+        let color = match (response.dragged(), config.drag_color, config.color) {
+            (true, Some(color), _) | (_, _, Some(color)) => color,
+            _ => visuals.fg_stroke.color,
+        };
+        let line_width = style::snap_stroke_width(ui.ctx(), width);
+        let line_stroke = egui::Stroke::new(line_width, color);
+        let y_range = timeline_rect.y_range();
+        if config.glow_while_playing && api.is_playing() {
+            let glow_color = color.linear_multiply(0.15);
+            for i in 1..=GLOW_LAYERS {
+                let glow_stroke = egui::Stroke::new(width + i as f32 * 2.0, glow_color);
+                ui.painter().vline(playhead_x, y_range, glow_stroke);
+            }
+        }
+        match config.line {
+            Line::Solid => {
+                ui.painter().vline(playhead_x, y_range, line_stroke);
+            }
+            Line::Dashed { dash, gap } => {
+                let top = egui::Pos2::new(playhead_x, y_range.min);
+                let bottom = egui::Pos2::new(playhead_x, y_range.max);
+                let dashes = egui::Shape::dashed_line(&[top, bottom], line_stroke, dash, gap);
+                ui.painter().extend(dashes);
+            }
+        }
+        let cap_h = paint_cap(ui.painter(), config.cap, playhead_x, y_range.min, color);
+        let half_w = (line_width * 0.5 + GLOW_LAYERS as f32).max(CAP_SIZE * 0.5);
+        let x = egui::Rangef::new(playhead_x - half_w, playhead_x + half_w);
+        let mut painted_rect = egui::Rect::from_x_y_ranges(x, y_range);
+
+        // Draw the position label, which may be double-clicked to type an exact position.
+        if let Some(text) = api.formatter().map(|f| f.format(playhead_ticks)) {
+            let label_rect = label_rect(ui, timeline_rect, playhead_x, cap_h, &text);
+            painted_rect = painted_rect.union(label_rect);
+            let label_id = response.id.with("label");
+            let label_sense = match config.interactive {
+                true => arbiter::sense(
+                    ui,
+                    Layer::Playhead,
+                    label_rect,
+                    label_id,
+                    egui::Sense::click(),
+                ),
+                false => egui::Sense::hover(),
+            };
+            let label = ui.interact(label_rect, label_id, label_sense);
+            if label_sense.click {
+                cursor::set(&label, Target::Text);
+            }
+            let mut editing: Option<String> = ui.data(|d| d.get_temp(label_id));
+            if label.double_clicked() {
+                editing = Some(text.clone());
+            }
+            match editing.take() {
+                Some(mut edit_text) => {
+                    let mut edit_rect = label_rect;
+                    edit_rect.set_width(label_rect.width().max(LABEL_EDIT_MIN_WIDTH));
+                    let edit = egui::TextEdit::singleline(&mut edit_text)
+                        .font(egui::TextStyle::Small)
+                        .margin(egui::Vec2::ZERO);
+                    let edit = ui.put(edit_rect, edit);
+                    if label.double_clicked() {
+                        edit.request_focus();
+                    }
+                    if !edit.lost_focus() {
+                        editing = Some(edit_text);
+                    } else if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        let parsed = api.formatter().and_then(|f| f.parse(&edit_text));
+                        if let Some(tick) = parsed {
+                            api.set_playhead_ticks(clamp(api.snap(tick.max(0.0))));
+                            response.mark_changed();
+                        }
+                    }
+                }
+                None => {
+                    let fill = ui.visuals().extreme_bg_color;
+                    ui.painter().rect(label_rect, radius, fill, stroke);
+                    let font_id = egui::TextStyle::Small.resolve(ui.style());
+                    let anchor = egui::Align2::CENTER_CENTER;
+                    let color = visuals.text_color();
+                    ui.painter()
+                        .text(label_rect.center(), anchor, text, font_id, color);
+                }
+            }
+            ui.data_mut(|d| match editing {
+                Some(text) => d.insert_temp(label_id, text),
+                None => d.remove::<String>(label_id),
+            });
+        }
+        painted = Some(painted.map_or(painted_rect, |r| r.union(painted_rect)));
+    }
+    ui.data_mut(|d| d.insert_temp(response.id.with("painted"), painted));
+
+    // Schedule the next frame of playback at the capped rate.
+    if let Some(hz) = config.playing_repaint_rate.filter(|&hz| hz > 0.0) {
+        if api.is_playing() {
+            ui.ctx()
+                .request_repaint_after(Duration::from_secs_f32(1.0 / hz));
+        }
     }
 
     response
 }
+
+/// The area painted by the playhead with the given response during this frame, if it was visible.
+pub fn painted_rect(ui: &egui::Ui, response: &egui::Response) -> Option<egui::Rect> {
+    ui.data(|d| d.get_temp(response.id.with("painted")))
+        .flatten()
+}
+
+/// Paint the cap at the top of the playhead, returning its height.
+fn paint_cap(painter: &egui::Painter, cap: Cap, x: f32, top: f32, color: egui::Color32) -> f32 {
+    let s = CAP_SIZE;
+    let h = s * 0.75;
+    match cap {
+        Cap::None => return 0.0,
+        Cap::Triangle => {
+            let points = vec![
+                egui::Pos2::new(x - s * 0.5, top),
+                egui::Pos2::new(x + s * 0.5, top),
+                egui::Pos2::new(x, top + h),
+            ];
+            let shape = egui::Shape::convex_polygon(points, color, egui::Stroke::NONE);
+            painter.add(shape);
+        }
+        Cap::Flag => {
+            let rect = egui::Rect::from_min_size(egui::Pos2::new(x, top), egui::vec2(s, h));
+            painter.rect_filled(rect, 0.0, color);
+        }
+    }
+    h
+}
+
+/// The last beat within `from..=to` excluding `from`, both in ticks relative to the start of the
+/// timeline.
+fn crossed_beat(info: &dyn MusicalInfo, from: f32, to: f32) -> Option<Beat> {
+    let bar = info.bar_at_ticks(to);
+    let ticks_per_beat = info.ticks_per_beat() as f32;
+    let time_sig = bar.time_sig;
+    let unit_ticks = ticks_per_beat * 4.0 / time_sig.bottom as f32;
+    let beat_ticks = match time_sig.is_compound() {
+        true => unit_ticks * 3.0,
+        false => unit_ticks,
+    };
+    if beat_ticks <= 0.0 {
+        return None;
+    }
+    let index_in_bar = ((to - bar.tick_range.start) / beat_ticks).floor().max(0.0);
+    let ticks_in_bar = index_in_bar * beat_ticks;
+    let tick = bar.tick_range.start + ticks_in_bar;
+    (tick > from).then(|| Beat {
+        tick,
+        index_in_bar: index_in_bar as usize,
+        level: time_sig.metric_level(ticks_per_beat, ticks_in_bar),
+    })
+}
+
+/// Paint the return marker as a dashed line topped by a triangle, returning the painted area.
+fn paint_return_marker(
+    ui: &egui::Ui,
+    x: f32,
+    y_range: egui::Rangef,
+    color: egui::Color32,
+) -> egui::Rect {
+    let width = style::snap_stroke_width(ui.ctx(), 1.0);
+    let stroke = egui::Stroke::new(width, color);
+    let top = egui::Pos2::new(x, y_range.min);
+    let bottom = egui::Pos2::new(x, y_range.max);
+    let dashes = egui::Shape::dashed_line(&[top, bottom], stroke, 4.0, 4.0);
+    ui.painter().extend(dashes);
+    paint_cap(ui.painter(), Cap::Triangle, x, y_range.min, color);
+    let half_w = CAP_SIZE * 0.5;
+    egui::Rect::from_x_y_ranges(egui::Rangef::new(x - half_w, x + half_w), y_range)
+}
+
+/// The rect occupied by the playhead's position label, kept within the timeline.
+fn label_rect(
+    ui: &egui::Ui,
+    timeline_rect: egui::Rect,
+    playhead_x: f32,
+    cap_h: f32,
+    text: &str,
+) -> egui::Rect {
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let color = egui::Color32::PLACEHOLDER;
+    let galley = ui
+        .painter()
+        .layout_no_wrap(text.to_string(), font_id, color);
+    let size = galley.size() + ui.spacing().button_padding;
+    let mut min = egui::Pos2::new(playhead_x + 1.0, timeline_rect.top() + cap_h);
+    if min.x + size.x > timeline_rect.right() {
+        min.x = playhead_x - 1.0 - size.x;
+    }
+    egui::Rect::from_min_size(min, size)
+}
@@ -0,0 +1,101 @@
+use super::TracksLayout;
+
+/// The width of each stripe of the barber-pole animation.
+pub const STRIPE_WIDTH: f32 = 8.0;
+/// The speed at which the barber-pole stripes move in points per second.
+pub const STRIPE_SPEED: f32 = 24.0;
+
+/// Access to the progress of long-running operations on tracks, e.g. freezing or rendering.
+pub trait Progress {
+    /// The progress of the operation on the track with the given ID, as set via `TrackCtx::id`,
+    /// in the range `0.0..=1.0`, or `None` if there is no operation in progress.
+    fn track_progress(&self, track: egui::Id) -> Option<f32>;
+}
+
+/// Draw a progress overlay across `rect`, e.g. a track or a clip via `clip::Info::progress`.
+///
+/// The completed portion is tinted, the remainder covered by moving barber-pole stripes, and the
+/// percentage is labelled at the center of the visible portion of `rect`. A repaint is requested
+/// while the overlay is visible so that the stripes keep moving.
+pub fn paint(ui: &egui::Ui, painter: &egui::Painter, rect: egui::Rect, progress: f32) {
+    let visible = painter.clip_rect().intersect(rect);
+    if !visible.is_positive() {
+        return;
+    }
+    ui.ctx().request_repaint();
+    let time = ui.input(|i| i.time);
+    let painter = painter.with_clip_rect(visible);
+    let visuals = ui.visuals();
+    let progress = progress.clamp(0.0, 1.0);
+    painter.rect_filled(rect, 0.0, visuals.extreme_bg_color.linear_multiply(0.6));
+
+    // Tint the completed portion.
+    let done_x = egui::lerp(rect.x_range(), progress);
+    let mut done = rect;
+    done.max.x = done_x;
+    let accent = visuals.selection.bg_fill;
+    painter.rect_filled(done, 0.0, accent.linear_multiply(0.5));
+
+    // Animate stripes across the visible remainder.
+    let mut remaining = visible;
+    remaining.min.x = remaining.min.x.max(done_x);
+    if remaining.width() > 0.0 {
+        let stripes = painter.with_clip_rect(remaining);
+        let h = rect.height();
+        let period = STRIPE_WIDTH * 2.0;
+        let offset = (time * STRIPE_SPEED as f64 % period as f64) as f32;
+        let first = ((remaining.left() - h - rect.left()) / period).floor() * period;
+        let mut x = rect.left() + first + offset - period;
+        let color = accent.linear_multiply(0.25);
+        while x < remaining.right() {
+            let points = vec![
+                egui::Pos2::new(x, rect.bottom()),
+                egui::Pos2::new(x + STRIPE_WIDTH, rect.bottom()),
+                egui::Pos2::new(x + STRIPE_WIDTH + h, rect.top()),
+                egui::Pos2::new(x + h, rect.top()),
+            ];
+            stripes.add(egui::Shape::convex_polygon(
+                points,
+                color,
+                egui::Stroke::NONE,
+            ));
+            x += period;
+        }
+    }
+
+    // Label the percentage, keeping it within view.
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    if visible.height() >= font_id.size {
+        let text = format!("{:.0}%", progress * 100.0);
+        let color = visuals.strong_text_color();
+        let anchor = egui::Align2::CENTER_CENTER;
+        painter.text(visible.center(), anchor, text, font_id, color);
+    }
+}
+
+/// Draw a progress overlay over each track with an operation in progress.
+///
+/// Tracks that were not set with an ID are skipped. Returns the screen space rects that were
+/// drawn.
+pub fn paint_tracks(ui: &egui::Ui, layout: &TracksLayout, api: &dyn Progress) -> Vec<egui::Rect> {
+    let mut rects = vec![];
+    for (ix, &(id, track_rect)) in layout.track_rects.iter().enumerate() {
+        let Some(progress) = api.track_progress(id) else {
+            continue;
+        };
+        // Scrolled tracks may only be drawn within the viewport.
+        let clip = match layout.scrolled_tracks.contains(&ix) {
+            true => layout.viewport.intersect(layout.timeline_rect),
+            false => layout.timeline_rect,
+        };
+        let rect =
+            egui::Rect::from_x_y_ranges(layout.timeline_rect.x_range(), track_rect.y_range());
+        if !clip.intersects(rect) {
+            continue;
+        }
+        let painter = ui.painter().with_clip_rect(clip);
+        paint(ui, &painter, rect, progress);
+        rects.push(rect.intersect(clip));
+    }
+    rects
+}
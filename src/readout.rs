@@ -0,0 +1,109 @@
+use super::format::{self, TimeFormatter};
+use std::{hash::Hash, ops::Range};
+
+/// The format in which the readout displays positions, toggled by clicking it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Positions as `bar.beat.tick` and durations as `bars.beats.ticks`, via
+    /// `Readout::formatter`.
+    #[default]
+    Musical,
+    /// Positions and durations as `m:ss.fff`, via `Readout::seconds`.
+    Clock,
+}
+
+/// Access to the positions displayed by the readout.
+pub trait Readout {
+    /// The current position, e.g. the playhead, in ticks relative to the start of the timeline.
+    fn position(&self) -> f32;
+    /// The start and end of the project in ticks relative to the start of the timeline.
+    fn range(&self) -> Range<f32>;
+    /// The formatter used to display positions and durations in the `Format::Musical` format.
+    fn formatter(&self) -> &dyn TimeFormatter;
+    /// The time in seconds at the given tick relative to the start of the timeline, e.g. via a
+    /// tempo map like `midi::Song::seconds_at`.
+    fn seconds(&self, tick: f32) -> f64;
+}
+
+/// The text displayed by the readout in a single format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Text {
+    /// The current position from the start of the project.
+    pub position: String,
+    /// The total length of the project.
+    pub length: String,
+    /// The time remaining from the current position until the end of the project, as a negative
+    /// duration.
+    pub remaining: String,
+}
+
+/// Show the current position, total length and remaining time of the project.
+///
+/// Clicking the readout toggles between the musical and clock formats. The format is persisted
+/// under the given `id_source` and may be read via `format`.
+pub fn show(ui: &mut egui::Ui, id_source: impl Hash, api: &dyn Readout) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let format = format(ui.ctx(), id);
+    let text = text(api, format);
+    let inner = egui::Frame::none()
+        .inner_margin(ui.spacing().button_padding)
+        .rounding(ui.visuals().widgets.inactive.rounding)
+        .fill(ui.visuals().extreme_bg_color)
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.strong(egui::RichText::new(text.position).monospace());
+                ui.weak("/");
+                ui.monospace(text.length);
+                ui.separator();
+                ui.monospace(text.remaining);
+            });
+        });
+    let mut response = ui.interact(inner.response.rect, id, egui::Sense::click());
+    if response.clicked() {
+        let next = match format {
+            Format::Musical => Format::Clock,
+            Format::Clock => Format::Musical,
+        };
+        ui.data_mut(|d| d.insert_temp(id, next));
+        response.mark_changed();
+    }
+    let hint = match format {
+        Format::Musical => "Click to show clock time",
+        Format::Clock => "Click to show bars and beats",
+    };
+    response.on_hover_text(hint)
+}
+
+/// The format currently displayed by the readout with the given ID, i.e. the response's ID.
+pub fn format(ctx: &egui::Context, id: egui::Id) -> Format {
+    ctx.data(|d| d.get_temp(id)).unwrap_or_default()
+}
+
+/// The text displayed by the readout in the given format.
+pub fn text(api: &dyn Readout, format: Format) -> Text {
+    let range = api.range();
+    let position = api
+        .position()
+        .clamp(range.start, range.end.max(range.start));
+    match format {
+        Format::Musical => {
+            let formatter = api.formatter();
+            let length = formatter.format_delta(range.end - range.start);
+            Text {
+                position: formatter.format(position),
+                length: length.trim_start_matches('+').to_string(),
+                remaining: formatter.format_delta(-(range.end - position)),
+            }
+        }
+        Format::Clock => {
+            let start = api.seconds(range.start);
+            let end = api.seconds(range.end);
+            let now = api.seconds(position);
+            Text {
+                position: format::format_seconds(now - start),
+                length: format::format_seconds(end - start),
+                remaining: format!("-{}", format::format_seconds(end - now)),
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use super::TracksLayout;
+use std::ops::Range;
+
+/// The width of the line drawn at the start of the recorded region.
+pub const START_LINE_WIDTH: f32 = 2.0;
+
+/// Access to the state of an in-progress recording.
+pub trait Recording {
+    /// The range captured so far in ticks relative to the start of the timeline, from the
+    /// punch-in or record start tick to the current playhead, or `None` while not recording.
+    fn recording_range(&self) -> Option<Range<f32>>;
+    /// Whether or not the track with the given ID, as set via `TrackCtx::id`, is armed.
+    fn is_armed(&self, track: egui::Id) -> bool;
+}
+
+/// Draw the region captured so far over each armed track while recording.
+///
+/// The region grows from the record start to the playhead as the host advances it. Tracks that
+/// were not set with an ID are skipped. Returns the screen space rects that were drawn.
+pub fn paint(ui: &egui::Ui, layout: &TracksLayout, api: &dyn Recording) -> Vec<egui::Rect> {
+    let range = match api.recording_range() {
+        Some(range) if range.end > range.start => range,
+        _ => return vec![],
+    };
+    let left = layout.timeline_rect.left();
+    let tpp = layout.ticks_per_point;
+    let x = egui::Rangef::new(left + range.start / tpp, left + range.end / tpp);
+    let color = ui.visuals().error_fg_color;
+    let fill = color.linear_multiply(0.2);
+    let stroke = egui::Stroke::new(START_LINE_WIDTH, color);
+    let mut rects = vec![];
+    for (ix, &(id, track_rect)) in layout.track_rects.iter().enumerate() {
+        if !api.is_armed(id) {
+            continue;
+        }
+        // Scrolled tracks may only be drawn within the viewport.
+        let clip = match layout.scrolled_tracks.contains(&ix) {
+            true => layout.viewport.intersect(layout.timeline_rect),
+            false => layout.timeline_rect,
+        };
+        let rect = egui::Rect::from_x_y_ranges(x, track_rect.y_range());
+        let painter = ui.painter().with_clip_rect(clip);
+        if !clip.intersects(rect) {
+            continue;
+        }
+        painter.rect_filled(rect, 0.0, fill);
+        painter.vline(x.min, rect.y_range(), stroke);
+        rects.push(rect.intersect(clip));
+    }
+    rects
+}
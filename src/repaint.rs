@@ -0,0 +1,54 @@
+/// A hint as to which parts of the timeline changed during the last frame.
+///
+/// The timeline never requests continuous repaints of its own accord. Hosts may use this hint to
+/// avoid repainting while idle, or to repaint only the dirty rect when using a partial-repaint
+/// backend.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Repaint {
+    /// Nothing changed.
+    #[default]
+    Idle,
+    /// Only the playhead, the growing recording region or a progress overlay moved, dirtying the
+    /// given rect.
+    Playhead(egui::Rect),
+    /// The view changed or is being interacted with.
+    Full,
+}
+
+impl Repaint {
+    /// Whether or not the timeline needs to be repainted.
+    pub fn needs_repaint(&self) -> bool {
+        *self != Repaint::Idle
+    }
+
+    /// The rect that must be repainted, if any, given the timeline's full rect.
+    pub fn dirty_rect(&self, full_rect: egui::Rect) -> Option<egui::Rect> {
+        match *self {
+            Repaint::Idle => None,
+            Repaint::Playhead(rect) => Some(rect.intersect(full_rect)),
+            Repaint::Full => Some(full_rect),
+        }
+    }
+
+    /// Include the given dirty playhead, recording region or progress overlay rect.
+    pub(crate) fn with_playhead(self, dirty: egui::Rect) -> Self {
+        match self {
+            Repaint::Idle => Repaint::Playhead(dirty),
+            Repaint::Playhead(rect) => Repaint::Playhead(rect.union(dirty)),
+            Repaint::Full => Repaint::Full,
+        }
+    }
+}
+
+/// Store the given state under `id`, returning whether or not it differs from the last stored.
+pub(crate) fn changed<T>(ui: &egui::Ui, id: egui::Id, state: T) -> bool
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    ui.data_mut(|d| {
+        let prev = d.get_temp::<T>(id);
+        let changed = prev.as_ref() != Some(&state);
+        d.insert_temp(id, state);
+        changed
+    })
+}
@@ -0,0 +1,86 @@
+use super::arbiter;
+use std::ops::Range;
+
+/// Which clips are shifted by a ripple edit, set via `Timeline::ripple`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// Only the clips following the edit on the same track. Requires both the edited and the
+    /// following clips to be on tracks with an ID set via `TrackCtx::id`.
+    Track,
+    /// The clips following the edit on all tracks.
+    AllTracks,
+}
+
+/// A batched ripple edit, emitted via `clip::Interaction::ripple` when a clip is deleted or its
+/// end is moved while ripple editing is enabled.
+///
+/// The host is expected to shift each clip for which `Ripple::applies_to` returns `true` by
+/// `delta` as a single edit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ripple {
+    /// The clips shifted by the edit.
+    pub scope: Scope,
+    /// The track of the edited clip as set via `TrackCtx::id`, if any.
+    pub track: Option<egui::Id>,
+    /// The edited clip.
+    pub source: egui::Id,
+    /// Clips starting at or after this tick relative to the start of the timeline are shifted.
+    pub from: f32,
+    /// The distance in ticks by which the following clips are shifted, negative when shifted
+    /// earlier.
+    pub delta: f32,
+}
+
+/// A ripple being previewed, along with the pass during which it was last updated.
+#[derive(Clone, Debug)]
+struct Preview {
+    ripple: Ripple,
+    pass: u64,
+}
+
+impl Ripple {
+    /// Whether or not the clip with the given ID, track and range is shifted by the edit.
+    ///
+    /// Clips that straddle `from` are left in place, as are all clips within `Scope::Track` when
+    /// either track is unknown.
+    pub fn applies_to(&self, clip: egui::Id, track: Option<egui::Id>, range: Range<f32>) -> bool {
+        let in_scope = match self.scope {
+            Scope::Track => self.track.is_some() && track == self.track,
+            Scope::AllTracks => true,
+        };
+        clip != self.source && in_scope && range.start >= self.from
+    }
+
+    /// The given range shifted by the edit.
+    ///
+    /// Every shifted clip moves by the same distance, limited once for the whole batch so that a
+    /// clip starting at `from` is never shifted before the start of the timeline.
+    pub fn shift(&self, range: Range<f32>) -> Range<f32> {
+        let delta = self.delta.max(-self.from.max(0.0));
+        range.start + delta..range.end + delta
+    }
+}
+
+/// The ID under which the ripple previewed within the timeline containing the `ui` is stored.
+fn preview_id(ui: &egui::Ui) -> egui::Id {
+    arbiter::scoped(ui, egui::Id::new("egui_timeline::ripple"))
+}
+
+/// Preview the given ripple on the following clips during this and the next pass.
+pub(crate) fn preview(ui: &egui::Ui, ripple: Ripple) {
+    let pass = ui.ctx().cumulative_pass_nr();
+    ui.data_mut(|d| d.insert_temp(preview_id(ui), Preview { ripple, pass }));
+}
+
+/// The ripple previewed during this or the previous pass, if any.
+pub(crate) fn previewed(ui: &egui::Ui) -> Option<Ripple> {
+    let pass = ui.ctx().cumulative_pass_nr();
+    ui.data(|d| d.get_temp::<Preview>(preview_id(ui)))
+        .filter(|preview| preview.pass + 1 >= pass)
+        .map(|preview| preview.ripple)
+}
+
+/// Stop previewing the ripple, e.g. once it has been applied.
+pub(crate) fn clear(ui: &egui::Ui) {
+    ui.data_mut(|d| d.remove::<Preview>(preview_id(ui)));
+}
@@ -1,4 +1,14 @@
-use super::Bar;
+use super::{
+    arbiter::{self, Layer},
+    cache,
+    format::TimeFormatter,
+    scrub::{self, Scrub},
+    selection::{self, TimeSelectionApi},
+    style,
+    time::{self, TimeDomain},
+    Bar,
+};
+use std::ops::{Range, RangeInclusive};
 
 /// Access to musical information required by the timeline.
 pub trait MusicalInfo {
@@ -10,69 +20,373 @@ pub trait MusicalInfo {
     fn ticks_per_point(&self) -> f32 {
         self.ticks_per_beat() as f32 / 16.0
     }
+    /// The number of the bar containing the start of the timeline view, used when formatting
+    /// positions. By default, returns `1`.
+    fn first_bar_number(&self) -> i64 {
+        1
+    }
 }
 
 /// Respond to when the user clicks on the ruler.
 pub trait MusicalInteract {
     /// The given tick location was clicked
     fn click_at_tick(&mut self, tick: f32);
+    /// The given tick location was double-clicked, e.g. to zoom to the next `zoom::Ladder` level
+    /// about the tick via `zoom::set`.
+    fn double_click_at_tick(&mut self, _tick: f32) {}
+    /// The ruler is being dragged to scrub, with the tick under the pointer and the velocity of
+    /// the drag, or the drag ended. Not reported while dragging a time selection.
+    fn scrub(&mut self, _scrub: Scrub) {}
 }
 
 /// The required API for the musical ruler widget.
 pub trait MusicalRuler {
     fn info(&self) -> &dyn MusicalInfo;
     fn interact(&mut self) -> &mut dyn MusicalInteract;
+    /// An optional formatter used to label the start of each bar.
+    fn formatter(&self) -> Option<&dyn TimeFormatter> {
+        None
+    }
+    /// Whether or not the ruler responds to clicks. Hosts may forward `TimelineCtx::lock` here.
+    fn interactive(&self) -> bool {
+        true
+    }
+    /// The layout of the ruler lane.
+    fn layout(&self) -> Layout {
+        Layout::default()
+    }
+    /// Access to the time selection. If `Some`, dragging on the ruler selects a range of time
+    /// while clicking clears the selection.
+    fn time_selection(&mut self) -> Option<&mut dyn TimeSelectionApi> {
+        None
+    }
 }
 
+/// Respond to when the user clicks on a ruler over any time domain.
+pub trait TimeInteract {
+    /// The given location in units from the start of the timeline was clicked.
+    fn click_at(&mut self, units: f32);
+    /// The given location in units from the start of the timeline was double-clicked, e.g. to
+    /// zoom in about the location.
+    fn double_click_at(&mut self, _units: f32) {}
+    /// The ruler is being dragged to scrub, with the location under the pointer and the velocity
+    /// of the drag, or the drag ended. Not reported while dragging a time selection.
+    fn scrub(&mut self, _scrub: Scrub) {}
+}
+
+/// The required API for a ruler over any time domain.
+pub trait TimeRuler {
+    fn domain(&self) -> &dyn TimeDomain;
+    fn interact(&mut self) -> &mut dyn TimeInteract;
+    /// An optional formatter used to label the most prominent steps, overriding the domain's own
+    /// labels.
+    fn formatter(&self) -> Option<&dyn TimeFormatter> {
+        None
+    }
+    /// Whether or not the ruler responds to clicks. Hosts may forward `TimelineCtx::lock` here.
+    fn interactive(&self) -> bool {
+        true
+    }
+    /// The layout of the ruler lane.
+    fn layout(&self) -> Layout {
+        Layout::default()
+    }
+    /// Access to the time selection. If `Some`, dragging on the ruler selects a range of time
+    /// while clicking clears the selection.
+    fn time_selection(&mut self) -> Option<&mut dyn TimeSelectionApi> {
+        None
+    }
+}
+
+/// The layout of a ruler lane.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Layout {
+    /// The height of the row containing the step lines. By default, `interact_size.y` is used.
+    pub height: Option<f32>,
+    /// The height of an optional separate row above the step lines in which labels are drawn, so
+    /// that dense labels don't overlap the step lines.
+    pub label_row: Option<f32>,
+    /// Mirror the ruler vertically so that step lines grow up from the bottom edge with labels
+    /// beneath them. Useful for rulers pinned to the bottom of the timeline.
+    pub flip: bool,
+    /// The maximum number of steps drawn per frame, beyond which the finest subdivisions are
+    /// dropped. By default, `MAX_STEPS` is used.
+    pub max_steps: Option<usize>,
+}
+
+/// The default width of the vertical value ruler.
+pub const VALUE_RULER_WIDTH: f32 = 36.0;
+
+/// The relative height of the step lines for each step level, starting with the most prominent.
+const STEP_HEIGHTS: [f32; 4] = [0.5, 0.3, 0.2, 0.125];
+
 /// Instantiate a musical ruler widget, showing bars and meters.
 pub fn musical(ui: &mut egui::Ui, api: &mut dyn MusicalRuler) -> egui::Response {
-    // Allocate space for the ruler.
-    let h = ui.spacing().interact_size.y;
+    let layout = api.layout();
+    let (rect, mut response) = allocate(ui, &layout, api.interactive());
+    let ticks_per_point = api.info().ticks_per_point();
+    let dragged = selection::dragged_range(ui, &response, rect, ticks_per_point);
+    let selecting = select(&mut response, dragged, api.time_selection());
+    if let Some(tick) = clicked_units(&response, rect, ticks_per_point).filter(|_| !selecting) {
+        api.interact().click_at_tick(tick);
+        response.mark_changed();
+    }
+    if let Some(scrub) = scrub_units(ui, &response, rect, ticks_per_point).filter(|_| !selecting) {
+        api.interact().scrub(scrub);
+    }
+    if let Some(tick) = double_clicked_units(&response, rect, ticks_per_point) {
+        api.interact().double_click_at_tick(tick);
+    }
+    if let Some(range) = api.time_selection().and_then(|s| s.time_selection()) {
+        selection::paint_time_selection(ui, rect, ticks_per_point, range);
+    }
+    paint_steps(ui, rect, &layout, api.info(), api.formatter());
+    response
+}
+
+/// Instantiate a ruler widget over any time domain, showing its steps and labels.
+pub fn time(ui: &mut egui::Ui, api: &mut dyn TimeRuler) -> egui::Response {
+    let layout = api.layout();
+    let (rect, mut response) = allocate(ui, &layout, api.interactive());
+    let units_per_point = api.domain().units_per_point();
+    let dragged = selection::dragged_range(ui, &response, rect, units_per_point)
+        .map(|r| api.domain().snap(r.start)..api.domain().snap(r.end));
+    let selecting = select(&mut response, dragged, api.time_selection());
+    if let Some(units) = clicked_units(&response, rect, units_per_point).filter(|_| !selecting) {
+        let units = api.domain().snap(units);
+        api.interact().click_at(units);
+        response.mark_changed();
+    }
+    if let Some(scrub) = scrub_units(ui, &response, rect, units_per_point).filter(|_| !selecting) {
+        api.interact().scrub(scrub);
+    }
+    if let Some(units) = double_clicked_units(&response, rect, units_per_point) {
+        api.interact().double_click_at(units);
+    }
+    if let Some(range) = api.time_selection().and_then(|s| s.time_selection()) {
+        selection::paint_time_selection(ui, rect, units_per_point, range);
+    }
+    paint_steps(ui, rect, &layout, api.domain(), api.formatter());
+    response
+}
+
+/// Update the time selection if there is one, returning whether or not a selection is being
+/// dragged.
+fn select(
+    response: &mut egui::Response,
+    dragged: Option<Range<f32>>,
+    api: Option<&mut dyn TimeSelectionApi>,
+) -> bool {
+    let api = match api {
+        Some(api) => api,
+        None => return false,
+    };
+    let selecting = dragged.is_some();
+    if selection::update(response, dragged, api) {
+        response.mark_changed();
+    }
+    selecting
+}
+
+/// Paint a slim vertical value ruler along the left edge of the visible part of the given track
+/// `rect`, e.g. for piano roll pitches, a dB scale or automation values.
+///
+/// The `value_range` is mapped from the bottom to the top of the `rect`, and is divided into
+/// `divisions` equal steps, each labelled using `label`. As the ruler is clipped to the `ui`'s
+/// clip rect, it scrolls vertically with the track while remaining pinned to the left of the
+/// visible area. Returns the rect occupied by the ruler.
+pub fn value(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    value_range: RangeInclusive<f32>,
+    divisions: usize,
+    label: impl Fn(f32) -> String,
+) -> egui::Rect {
+    let clip_rect = ui.clip_rect().intersect(rect);
+    let left = clip_rect.left();
+    let ruler_rect = egui::Rect::from_x_y_ranges(left..=left + VALUE_RULER_WIDTH, rect.y_range());
+    let painter = ui.painter().with_clip_rect(clip_rect);
+    let vis = ui.style().noninteractive();
+    painter.rect(ruler_rect, 0.0, vis.bg_fill, vis.bg_stroke);
+
+    // Draw a tick and label for each division.
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let text_color = vis.text_color();
+    let tick_w = VALUE_RULER_WIDTH * STEP_HEIGHTS[2];
+    let divisions = divisions.max(1);
+    for i in 0..=divisions {
+        let value = egui::lerp(value_range.clone(), i as f32 / divisions as f32);
+        let y = egui::remap(value, value_range.clone(), rect.y_range().flip());
+        if !clip_rect.y_range().contains(y) {
+            continue;
+        }
+        let right = ruler_rect.right();
+        painter.hline(right - tick_w..=right, y, vis.fg_stroke);
+        let anchor = match i {
+            0 => egui::Align2::RIGHT_BOTTOM,
+            n if n == divisions => egui::Align2::RIGHT_TOP,
+            _ => egui::Align2::RIGHT_CENTER,
+        };
+        let pos = egui::Pos2::new(right - tick_w - 2.0, y);
+        painter.text(pos, anchor, label(value), font_id.clone(), text_color);
+    }
+    ruler_rect
+}
+
+/// Allocate space for the ruler.
+fn allocate(ui: &mut egui::Ui, layout: &Layout, interactive: bool) -> (egui::Rect, egui::Response) {
+    let step_h = layout.height.unwrap_or(ui.spacing().interact_size.y);
+    let h = step_h + layout.label_row.unwrap_or(0.0);
     let w = ui.available_width();
     let desired_size = egui::Vec2::new(w, h);
-    let (rect, mut response) = ui.allocate_exact_size(desired_size, egui::Sense::click_and_drag());
+    let rect = egui::Rect::from_min_size(ui.available_rect_before_wrap().min, desired_size);
+    let id = ui.id().with("ruler");
+    let sense = match interactive {
+        true => arbiter::sense(ui, Layer::Ruler, rect, id, egui::Sense::click_and_drag()),
+        false => egui::Sense::hover(),
+    };
+    ui.advance_cursor_after_rect(rect);
+    let response = ui.interact(rect, id, sense);
+    (rect, response)
+}
 
-    // Check for clicks.
-    let w = rect.width();
-    let ticks_per_point = api.info().ticks_per_point();
-    let visible_ticks = w * ticks_per_point;
+/// Check for clicks, returning the clicked location in units.
+fn clicked_units(response: &egui::Response, rect: egui::Rect, units_per_point: f32) -> Option<f32> {
     if response.clicked() || response.dragged() {
         if let Some(pt) = response.interact_pointer_pos() {
-            let tick = (((pt.x - rect.min.x) / w) * visible_ticks).max(0.0);
-            api.interact().click_at_tick(tick);
-            response.mark_changed();
+            return Some(((pt.x - rect.min.x) * units_per_point).max(0.0));
         }
     }
+    None
+}
 
-    // Time to draw things.
-    let vis = ui.style().noninteractive();
+/// Track scrubbing by dragging along the ruler, in units from the start of the timeline.
+fn scrub_units(
+    ui: &egui::Ui,
+    response: &egui::Response,
+    rect: egui::Rect,
+    units_per_point: f32,
+) -> Option<Scrub> {
+    let pointer = ui.input(|i| i.pointer.latest_pos());
+    let pt = response.interact_pointer_pos().or(pointer)?;
+    let units = ((pt.x - rect.min.x) * units_per_point).max(0.0);
+    scrub::track(ui, response, units)
+}
 
-    // Draw each of the step lines.
+/// Check for double clicks, returning the double-clicked location in units.
+fn double_clicked_units(
+    response: &egui::Response,
+    rect: egui::Rect,
+    units_per_point: f32,
+) -> Option<f32> {
+    let pt = response
+        .interact_pointer_pos()
+        .filter(|_| response.double_clicked())?;
+    Some(((pt.x - rect.min.x) * units_per_point).max(0.0))
+}
+
+/// Draw each of the step lines along with any labels.
+fn paint_steps<D: TimeDomain + ?Sized>(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    layout: &Layout,
+    domain: &D,
+    formatter: Option<&dyn TimeFormatter>,
+) {
+    profile_scope!("ruler_steps");
+    // Split the rect into the label and step rows.
+    let (label_rect, step_rect) = match layout.label_row {
+        Some(label_h) if layout.flip => {
+            let (step_rect, label_rect) = rect.split_top_bottom_at_y(rect.bottom() - label_h);
+            (label_rect, step_rect)
+        }
+        Some(label_h) => rect.split_top_bottom_at_y(rect.top() + label_h),
+        None => (rect, rect),
+    };
+
+    let vis = ui.style().noninteractive();
     let mut stroke = vis.fg_stroke;
+    let stroke_width = style::current(ui)
+        .ruler_stroke_width
+        .unwrap_or(stroke.width);
+    stroke.width = style::snap_stroke_width(ui.ctx(), stroke_width);
     let bar_color = stroke.color.linear_multiply(0.5);
     let step_color = stroke.color.linear_multiply(0.125);
-    let bar_y = rect.center().y;
-    let step_even_y = rect.top() + rect.height() * 0.25;
-    let step_odd_y = rect.top() + rect.height() * 0.125;
-
-    // Iterate over the steps of the ruler to draw them.
-    let visible_len = w;
-    let info = api.info();
-    let mut steps = Steps::new(info, visible_len, super::MIN_STEP_GAP);
-    while let Some(step) = steps.next(info) {
-        let (y, color) = match step.index_in_bar {
-            0 => (bar_y, bar_color),
-            n if n % 2 == 0 => (step_even_y, step_color),
-            _ => (step_odd_y, step_color),
-        };
-        stroke.color = color;
-        let x = rect.left() + step.x;
-        let a = egui::Pos2::new(x, rect.top());
-        let b = egui::Pos2::new(x, y);
-        ui.painter().line_segment([a, b], stroke);
-    }
+    let font_id = egui::TextStyle::Small.resolve(ui.style());
+    let text_color = vis.text_color();
 
-    response
+    // Collect the steps and their labels, reusing the previous frame's mesh while unchanged.
+    let visible_len = rect.width();
+    let max_steps = layout.max_steps.unwrap_or(super::MAX_STEPS);
+    let all_steps = domain.steps(visible_len, super::MIN_STEP_GAP);
+    let steps: Vec<(u32, u8, Option<String>)> = time::limit_steps(all_steps, max_steps)
+        .into_iter()
+        .map(|step| {
+            let label = match formatter {
+                Some(f) if step.level == 0 => Some(f.format(step.units)),
+                Some(_) => None,
+                None => domain.label(&step),
+            };
+            (step.x.to_bits(), step.level, label)
+        })
+        .collect();
+    let key = (
+        cache::rect_bits(rect),
+        cache::rect_bits(step_rect),
+        layout.flip,
+        stroke.width.to_bits(),
+        [bar_color, step_color, text_color],
+        &font_id,
+        &steps,
+    );
+    let id = ui.id().with("ruler_steps");
+    cache::paint(ui.painter(), id, key, || {
+        let mut shapes = vec![];
+        for (x, level, label) in &steps {
+            let level = (*level as usize).min(STEP_HEIGHTS.len() - 1);
+            stroke.color = match level {
+                0 => bar_color,
+                _ => step_color,
+            };
+            let x = rect.left() + f32::from_bits(*x);
+            let step_h = step_rect.height() * STEP_HEIGHTS[level];
+            let (a, b) = match layout.flip {
+                true => (step_rect.bottom(), step_rect.bottom() - step_h),
+                false => (step_rect.top(), step_rect.top() + step_h),
+            };
+            let (a, b) = (egui::Pos2::new(x, a), egui::Pos2::new(x, b));
+            shapes.push(egui::Shape::line_segment([a, b], stroke));
+            if let Some(label) = label {
+                let (pos, anchor) = match layout.flip {
+                    true => (label_rect.top(), egui::Align2::LEFT_TOP),
+                    false => (label_rect.bottom(), egui::Align2::LEFT_BOTTOM),
+                };
+                let pos = egui::Pos2::new(x + 2.0, pos);
+                let galley =
+                    ui.painter()
+                        .layout_no_wrap(label.clone(), font_id.clone(), text_color);
+                let label_rect = anchor.anchor_size(pos, galley.size());
+                shapes.push(egui::Shape::galley(label_rect.min, galley, text_color));
+            }
+        }
+        shapes
+    });
+}
+
+/// The bars overlapping the first `visible_ticks` of the timeline view, paired with their bar
+/// numbers starting from `MusicalInfo::first_bar_number`.
+pub fn bars(info: &dyn MusicalInfo, visible_ticks: f32) -> impl Iterator<Item = (i64, Bar)> + '_ {
+    let mut next = Some((info.first_bar_number(), info.bar_at_ticks(0.0)));
+    std::iter::from_fn(move || {
+        let (number, bar) = next.take()?;
+        if bar.tick_range.start <= visible_ticks && bar.tick_range.end > bar.tick_range.start {
+            let following = info.bar_at_ticks(bar.tick_range.end + 0.5);
+            next = Some((number + 1, following));
+            Some((number, bar))
+        } else {
+            None
+        }
+    })
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -85,86 +399,130 @@ pub struct Step {
     pub ticks: f32,
     /// The location of the step along the x axis from the start of the ruler.
     pub x: f32,
+    /// The metric strength of the step within its bar. See `TimeSig::metric_level`.
+    pub level: u8,
 }
 
-#[derive(Clone, Debug)]
-pub struct Steps {
+/// An iterator over the steps of a musical ruler.
+///
+/// `MusicalInfo` is consulted only once per bar, while the steps within each bar are produced
+/// with plain arithmetic.
+#[derive(Clone)]
+pub struct Steps<'a> {
+    info: &'a dyn MusicalInfo,
     ticks_per_beat: f32,
     ticks_per_point: f32,
     visible_ticks: f32,
     min_step_ticks: f32,
-    index_in_bar: usize,
-    step_ticks: f32,
     bar: Bar,
-    ticks: f32,
+    step_ticks: f32,
+    index_in_bar: usize,
+    steps_in_bar: usize,
+}
+
+impl<'a> std::fmt::Debug for Steps<'a> {
+    // `MusicalInfo` isn't required to implement `Debug`, so it is skipped.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Steps")
+            .field("ticks_per_beat", &self.ticks_per_beat)
+            .field("ticks_per_point", &self.ticks_per_point)
+            .field("visible_ticks", &self.visible_ticks)
+            .field("min_step_ticks", &self.min_step_ticks)
+            .field("bar", &self.bar)
+            .field("step_ticks", &self.step_ticks)
+            .field("index_in_bar", &self.index_in_bar)
+            .field("steps_in_bar", &self.steps_in_bar)
+            .finish_non_exhaustive()
+    }
 }
 
-impl Steps {
+impl<'a> Steps<'a> {
     /// Create a new `Steps`.
-    pub fn new(api: &dyn MusicalInfo, visible_len: f32, min_step_gap: f32) -> Self {
-        let ticks_per_beat = api.ticks_per_beat() as f32;
-        let ticks_per_point = api.ticks_per_point();
-        let visible_ticks = ticks_per_point * visible_len;
-        let min_step_ticks = ticks_per_point * min_step_gap;
-        Self {
-            ticks_per_beat,
+    pub fn new(info: &'a dyn MusicalInfo, visible_len: f32, min_step_gap: f32) -> Self {
+        let ticks_per_point = info.ticks_per_point();
+        let mut steps = Self {
+            info,
+            ticks_per_beat: info.ticks_per_beat() as f32,
             ticks_per_point,
-            visible_ticks,
-            min_step_ticks,
-            index_in_bar: 0,
+            visible_ticks: ticks_per_point * visible_len,
+            min_step_ticks: ticks_per_point * min_step_gap,
+            bar: info.bar_at_ticks(0.0),
             step_ticks: 0.0,
-            bar: api.bar_at_ticks(0.0),
-            ticks: 0.0,
-        }
+            index_in_bar: 0,
+            steps_in_bar: 0,
+        };
+        steps.enter_bar(steps.bar.clone());
+        steps
     }
 
-    /// Produce the next `Step`.
-    pub fn next(&mut self, api: &dyn MusicalInfo) -> Option<Step> {
-        'bars: loop {
-            // If this is the first step of the bar, update step interval.
-            if self.index_in_bar == 0 {
-                self.ticks = self.bar.tick_range.start;
-                let mut beat_subdivs = self.bar.time_sig.bottom / 4;
-                self.step_ticks = self.ticks_per_beat as f32 / beat_subdivs as f32;
-                if self.step_ticks >= self.min_step_ticks {
-                    loop {
-                        let new_beat_subdivs = beat_subdivs * 2;
-                        let new_step_ticks = self.ticks_per_beat as f32 / new_beat_subdivs as f32;
-                        if new_step_ticks <= self.min_step_ticks {
-                            break;
-                        }
-                        beat_subdivs = new_beat_subdivs;
-                        self.step_ticks = new_step_ticks;
-                    }
-                } else {
-                    self.step_ticks = self.bar.tick_range.end - self.bar.tick_range.start;
+    /// The bar containing the most recently produced `Step`.
+    pub fn bar(&self) -> &Bar {
+        &self.bar
+    }
+
+    /// Determine the step interval and the range of visible steps for the given bar.
+    fn enter_bar(&mut self, bar: Bar) {
+        let bar_ticks = bar.tick_range.end - bar.tick_range.start;
+        let mut beat_subdivs = (bar.time_sig.bottom / 4).max(1);
+        self.step_ticks = self.ticks_per_beat / beat_subdivs as f32;
+        if self.step_ticks >= self.min_step_ticks {
+            loop {
+                let new_beat_subdivs = beat_subdivs * 2;
+                let new_step_ticks = self.ticks_per_beat / new_beat_subdivs as f32;
+                if new_step_ticks <= self.min_step_ticks {
+                    break;
                 }
+                beat_subdivs = new_beat_subdivs;
+                self.step_ticks = new_step_ticks;
             }
+        } else {
+            self.step_ticks = bar_ticks;
+        }
+        // Skip steps that begin before the start of the visible area.
+        self.steps_in_bar = (bar_ticks / self.step_ticks - 1e-4).ceil().max(0.0) as usize;
+        self.index_in_bar = match bar.tick_range.start < 0.0 {
+            true => (-bar.tick_range.start / self.step_ticks).ceil() as usize,
+            false => 0,
+        };
+        self.bar = bar;
+    }
+}
 
-            'ticks: loop {
-                if self.ticks > self.visible_ticks {
+impl Iterator for Steps<'_> {
+    type Item = Step;
+    fn next(&mut self) -> Option<Step> {
+        loop {
+            let bar_start = self.bar.tick_range.start;
+            if self.index_in_bar >= self.steps_in_bar {
+                let bar_end = self.bar.tick_range.end;
+                if bar_end <= bar_start || bar_end > self.visible_ticks {
                     return None;
                 }
-                if self.ticks >= self.bar.tick_range.end {
-                    self.index_in_bar = 0;
-                    self.bar = api.bar_at_ticks(self.bar.tick_range.end + 0.5);
-                    continue 'bars;
-                }
-                let index_in_bar = self.index_in_bar;
-                let ticks = self.ticks;
-                self.index_in_bar += 1;
-                self.ticks += self.step_ticks;
-                if ticks < 0.0 {
-                    continue 'ticks;
-                }
-                let x = ticks / self.ticks_per_point;
-                let step = Step {
-                    index_in_bar,
-                    ticks,
-                    x,
-                };
-                return Some(step);
+                let next = self.info.bar_at_ticks(bar_end + 0.5);
+                self.enter_bar(next);
+                continue;
+            }
+            let index_in_bar = self.index_in_bar;
+            let ticks_in_bar = index_in_bar as f32 * self.step_ticks;
+            let ticks = bar_start + ticks_in_bar;
+            if ticks > self.visible_ticks {
+                return None;
             }
+            self.index_in_bar += 1;
+            let level = match index_in_bar {
+                0 => 0,
+                _ => self
+                    .bar
+                    .time_sig
+                    .metric_level(self.ticks_per_beat, ticks_in_bar),
+            };
+            let x = ticks / self.ticks_per_point;
+            return Some(Step {
+                index_in_bar,
+                ticks,
+                x,
+                level,
+            });
         }
     }
 }
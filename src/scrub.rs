@@ -0,0 +1,50 @@
+use super::gesture;
+
+/// Reported while the playhead or ruler is dragged, e.g. for driving varispeed scrub audio.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Scrub {
+    /// The drag moved to the given position relative to the start of the timeline, with the
+    /// instantaneous velocity of the drag in ticks (or units of the time domain) per second.
+    ///
+    /// The velocity is negative while dragging backwards, and `0.0` while the pointer is held
+    /// still.
+    Moved { position: f32, velocity: f32 },
+    /// The drag was released or cancelled at the given position, at which point scrub audio
+    /// should stop.
+    Stopped { position: f32 },
+}
+
+/// The position and time of the previous frame of a scrub.
+#[derive(Copy, Clone, Debug)]
+struct Last {
+    position: f32,
+    time: f64,
+}
+
+/// Track the scrub driven by the given response, with the position under the pointer.
+///
+/// While the pointer moves, a repaint is requested so that the velocity drops to `0.0` once it
+/// is held still. `Stopped` is only reported for drags that reported `Moved`.
+pub(crate) fn track(ui: &egui::Ui, response: &egui::Response, position: f32) -> Option<Scrub> {
+    let id = response.id.with("scrub");
+    let last: Option<Last> = ui.data(|d| d.get_temp(id));
+    if response.drag_stopped() || gesture::cancelled(ui, response) {
+        ui.data_mut(|d| d.remove::<Last>(id));
+        return last.map(|_| Scrub::Stopped { position });
+    }
+    if !response.dragged() {
+        return None;
+    }
+    let time = ui.input(|i| i.time);
+    ui.data_mut(|d| d.insert_temp(id, Last { position, time }));
+    let velocity = match last {
+        Some(last) if time > last.time => {
+            ((position - last.position) as f64 / (time - last.time)) as f32
+        }
+        _ => 0.0,
+    };
+    if velocity != 0.0 {
+        ui.ctx().request_repaint();
+    }
+    Some(Scrub::Moved { position, velocity })
+}
@@ -0,0 +1,270 @@
+use std::{collections::HashSet, hash::Hash, ops::Range};
+
+/// The fraction of the snap unit by which the selection is nudged while `Shift` is held.
+pub const FINE_NUDGE: f32 = 0.1;
+
+/// Access to the time selection, a range of time made by dragging on the ruler.
+///
+/// The time selection is distinct from the loop region, and is intended for range-based
+/// operations like cutting, copying or inserting time.
+pub trait TimeSelectionApi {
+    /// The selected range in ticks relative to the start of the timeline, if any.
+    fn time_selection(&self) -> Option<Range<f32>>;
+    /// Set or clear the selected range.
+    fn set_time_selection(&mut self, selection: Option<Range<f32>>);
+}
+
+/// The selection across the whole timeline, owned by the host so that it may be saved with the
+/// project or changed by host commands.
+///
+/// Set each frame via `Timeline::selection`. Clips and keyframe lanes read and update it while
+/// laid out, and the result is reported via `TracksLayout::selection_changed`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SelectionState {
+    /// The selected clips by `clip::Info::id`.
+    pub clips: HashSet<egui::Id>,
+    /// The selected keyframes by the ID of their `keyframe::lane` and
+    /// `keyframe::Keyframes::keyframe_id`.
+    pub keyframes: HashSet<(egui::Id, egui::Id)>,
+    /// The time selection in the host's own units, typically absolute ticks. The timeline only
+    /// carries it, leaving it to the host's `TimeSelectionApi`.
+    pub time: Option<Range<f32>>,
+    /// The selected tracks by `TrackCtx::id`, drawn with highlighted headers.
+    pub tracks: HashSet<egui::Id>,
+}
+
+impl SelectionState {
+    /// Whether or not nothing is selected.
+    pub fn is_empty(&self) -> bool {
+        self.clips.is_empty()
+            && self.keyframes.is_empty()
+            && self.time.is_none()
+            && self.tracks.is_empty()
+    }
+
+    /// Deselect everything.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Select the given clip, either adding it to or toggling it within the selection when
+    /// `toggle` is `true`, or replacing the selected clips and keyframes otherwise.
+    pub fn select_clip(&mut self, id: egui::Id, toggle: bool) {
+        if !toggle {
+            self.clips.clear();
+            self.keyframes.clear();
+        } else if self.clips.remove(&id) {
+            return;
+        }
+        self.clips.insert(id);
+    }
+
+    /// Select the candidates matching the given command, adding them to the current selection if
+    /// `add` is `true`, or replacing the selected items and tracks otherwise. The time selection is
+    /// left untouched.
+    ///
+    /// Returns the number of candidates that matched.
+    pub fn select(
+        &mut self,
+        command: &Command,
+        candidates: impl IntoIterator<Item = Candidate>,
+        add: bool,
+    ) -> usize {
+        if !add {
+            self.clips.clear();
+            self.keyframes.clear();
+            self.tracks.clear();
+        }
+        if let Command::Track(track) = *command {
+            self.tracks.insert(track);
+        }
+        let mut count = 0;
+        for candidate in candidates.into_iter().filter(|c| command.matches(c)) {
+            match candidate.item {
+                Item::Clip(id) => self.clips.insert(id),
+                Item::Keyframe(lane, keyframe) => self.keyframes.insert((lane, keyframe)),
+            };
+            count += 1;
+        }
+        count
+    }
+}
+
+/// An item that may be selected via a `Command`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Item {
+    /// A clip by `clip::Info::id`.
+    Clip(egui::Id),
+    /// A keyframe by the ID of its `keyframe::lane` and `keyframe::Keyframes::keyframe_id`.
+    Keyframe(egui::Id, egui::Id),
+}
+
+/// An item owned by the host, considered by `SelectionState::select`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    /// The item selected if it matches the command.
+    pub item: Item,
+    /// The track containing the item as set via `TrackCtx::id`, if any.
+    pub track: Option<egui::Id>,
+    /// The range occupied by the item in the same units as the command, e.g. absolute ticks. An
+    /// empty range for keyframes.
+    pub range: Range<f32>,
+}
+
+/// A host command selecting items by position or track, e.g. in response to a menu item.
+///
+/// Applied via `SelectionState::select`, with the result shown once it is next set via
+/// `Timeline::selection`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// The items starting at or after the given position, e.g. the playhead.
+    Following(f32),
+    /// The items lying entirely within the given range, e.g. the loop region.
+    Within(Range<f32>),
+    /// The items on the given track, which is selected along with them.
+    Track(egui::Id),
+}
+
+impl Command {
+    /// Whether or not the command selects the given candidate.
+    pub fn matches(&self, candidate: &Candidate) -> bool {
+        let range = &candidate.range;
+        match *self {
+            Command::Following(from) => range.start >= from,
+            Command::Within(ref within) => range.start >= within.start && range.end <= within.end,
+            Command::Track(track) => candidate.track == Some(track),
+        }
+    }
+}
+
+/// Access to the selected items (e.g. clips, keyframes or markers) for keyboard nudging.
+pub trait NudgeApi {
+    /// Whether or not any items are selected.
+    fn has_selection(&self) -> bool;
+    /// The current snap unit in ticks.
+    fn snap_ticks(&self) -> f32;
+    /// Move all selected items by the given number of ticks as a single batch.
+    fn nudge_selection(&mut self, delta: f32);
+}
+
+/// Make the given `rect`, typically the whole timeline, focusable and nudge the selection with
+/// the arrow keys while it has focus.
+///
+/// The timeline gains focus when an item within `rect` is clicked or dragged, leaving items
+/// selected, and holds on to the horizontal arrow keys while focused. `Left` and `Right` nudge the
+/// selection by the snap unit, or by `FINE_NUDGE` of the snap unit while `Shift` is held. Returns
+/// `true` if the selection was nudged.
+///
+/// Call this every frame after laying out the items, so that the timeline keeps its focus.
+pub fn nudge(
+    ui: &egui::Ui,
+    id_source: impl Hash,
+    rect: egui::Rect,
+    api: &mut dyn NudgeApi,
+) -> bool {
+    let id = ui.make_persistent_id(id_source);
+    // Egui drops focus from widgets that aren't interacted with during a pass.
+    ui.interact(rect, id, egui::Sense::focusable_noninteractive());
+    let item_pressed = ui
+        .ctx()
+        .interaction_snapshot(|s| s.clicked.or(s.drag_started).is_some());
+    let within = ui.input(|i| i.pointer.interact_pos().is_some_and(|p| rect.contains(p)));
+    if item_pressed && within && api.has_selection() {
+        ui.memory_mut(|m| m.request_focus(id));
+    }
+    if !ui.memory(|m| m.has_focus(id)) {
+        return false;
+    }
+    let filter = egui::EventFilter {
+        horizontal_arrows: true,
+        ..Default::default()
+    };
+    ui.memory_mut(|m| m.set_focus_lock_filter(id, filter));
+    if !api.has_selection() {
+        return false;
+    }
+    let (steps, fine) = ui.input(|i| {
+        let left = i.num_presses(egui::Key::ArrowLeft) as f32;
+        let right = i.num_presses(egui::Key::ArrowRight) as f32;
+        (right - left, i.modifiers.shift)
+    });
+    if steps == 0.0 {
+        return false;
+    }
+    let unit = match fine {
+        true => api.snap_ticks() * FINE_NUDGE,
+        false => api.snap_ticks(),
+    };
+    api.nudge_selection(steps * unit);
+    true
+}
+
+/// The selection stored under the given ID, if any.
+pub(crate) fn load(ctx: &egui::Context, id: egui::Id) -> Option<SelectionState> {
+    ctx.data(|d| d.get_temp(id))
+}
+
+/// Store the selection under the given ID, read back by the widgets that follow.
+pub(crate) fn store(ctx: &egui::Context, id: egui::Id, state: SelectionState) {
+    ctx.data_mut(|d| d.insert_temp(id, state));
+}
+
+/// The range of units dragged over within the given ruler `rect`, if any.
+pub(crate) fn dragged_range(
+    ui: &egui::Ui,
+    response: &egui::Response,
+    rect: egui::Rect,
+    units_per_point: f32,
+) -> Option<Range<f32>> {
+    if !response.dragged() {
+        return None;
+    }
+    let origin = ui.input(|i| i.pointer.press_origin())?;
+    let pos = response.interact_pointer_pos()?;
+    let to_units = |x: f32| ((x - rect.left()) * units_per_point).max(0.0);
+    let (a, b) = (to_units(origin.x), to_units(pos.x));
+    Some(a.min(b)..a.max(b))
+}
+
+/// Apply the `dragged` range to the time selection, or clear it if the response was clicked.
+///
+/// Returns `true` if the selection changed.
+pub(crate) fn update(
+    response: &egui::Response,
+    dragged: Option<Range<f32>>,
+    api: &mut dyn TimeSelectionApi,
+) -> bool {
+    let selection = match dragged {
+        Some(range) => Some(range),
+        None if response.clicked() => None,
+        None => return false,
+    };
+    if selection == api.time_selection() {
+        return false;
+    }
+    api.set_time_selection(selection);
+    true
+}
+
+/// Paint the time selection as a translucent column over the given rect.
+pub fn paint_time_selection(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    ticks_per_point: f32,
+    selection: Range<f32>,
+) {
+    let x = egui::Rangef::new(
+        rect.left() + selection.start / ticks_per_point,
+        rect.left() + selection.end / ticks_per_point,
+    );
+    let sel_rect = egui::Rect::from_x_y_ranges(x, rect.y_range()).intersect(rect);
+    if !sel_rect.is_positive() {
+        return;
+    }
+    let selection_visuals = ui.visuals().selection;
+    let fill = selection_visuals.bg_fill.linear_multiply(0.2);
+    let stroke = egui::Stroke::new(1.0, selection_visuals.bg_fill);
+    ui.painter().rect_filled(sel_rect, 0.0, fill);
+    ui.painter().vline(x.min, sel_rect.y_range(), stroke);
+    ui.painter().vline(x.max, sel_rect.y_range(), stroke);
+}
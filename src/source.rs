@@ -0,0 +1,123 @@
+use super::{paint_hatching, TimelineCtx};
+use std::{hash::Hash, ops::Range, time::Duration};
+
+/// The width of each chunk requested from a data source, in points.
+pub const CHUNK_WIDTH: f32 = 256.0;
+/// How often lanes with pending chunks are repainted while waiting on the source.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The result of polling a data source.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Poll<T> {
+    /// The data is not yet available.
+    Pending,
+    /// The data is ready to be drawn.
+    Ready(T),
+}
+
+/// A non-blocking source of data for a lane, e.g. waveform peaks, thumbnails or events loaded in
+/// the background.
+///
+/// Ranges are in absolute ticks, offset by `TimelineApi::timeline_start`, divided into chunks of
+/// `CHUNK_WIDTH` points. The `lod` is the base-2 logarithm of the ticks per point that the data
+/// is requested for, so that each level has half the detail of the previous. Chunks keep the same
+/// range as the view scrolls, so sources may cache data by range.
+pub trait LaneDataSource {
+    /// The data for a single chunk.
+    type Data;
+    /// Begin loading the data within the given range. Must not block.
+    fn request(&mut self, range: Range<f32>, lod: i32);
+    /// The data within the given range if it has finished loading.
+    fn poll(&mut self, range: Range<f32>, lod: i32) -> Poll<&Self::Data>;
+    /// A previously requested range is no longer visible and may be abandoned.
+    fn cancel(&mut self, _range: Range<f32>, _lod: i32) {}
+}
+
+/// The level of detail for the given number of ticks per point.
+pub fn lod(ticks_per_point: f32) -> i32 {
+    ticks_per_point.max(f32::MIN_POSITIVE).log2().floor() as i32
+}
+
+/// Instantiate a lane of the given height drawing data from the given source.
+///
+/// Each visible chunk is polled, then requested if still pending and not already in flight. Ready
+/// chunks are drawn with `paint`, while pending chunks are drawn as placeholders and the lane is
+/// repainted every `POLL_INTERVAL` until they fill in. Requests for chunks that scroll out of view
+/// before completing are cancelled.
+///
+/// Chunks are keyed by absolute ticks, so the host must provide `TimelineApi::timeline_start` for
+/// new chunks to be requested as the view scrolls.
+pub fn lane<T>(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    source: &mut dyn LaneDataSource<Data = T>,
+    mut paint: impl FnMut(&egui::Painter, egui::Rect, &T),
+) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let w = ui.available_width();
+    let (rect, response) = ui.allocate_exact_size(egui::Vec2::new(w, height), egui::Sense::hover());
+    let visible = ui.clip_rect().intersect(rect);
+    if !visible.is_positive() {
+        return response;
+    }
+    let mut in_flight: Vec<(Range<f32>, i32)> = ui.data(|d| d.get_temp(id)).unwrap_or_default();
+
+    // Visit each visible chunk, in absolute ticks.
+    let lod = lod(timeline.ticks_per_point);
+    let chunk_ticks = CHUNK_WIDTH * 2f32.powi(lod);
+    let offset = timeline.timeline_start.unwrap_or(0.0);
+    let start = (offset + timeline.x_to_tick(visible.left())).max(0.0);
+    let end = offset + timeline.x_to_tick(visible.right());
+    let mut visible_chunks = vec![];
+    let mut pending = false;
+    let mut ix = (start / chunk_ticks).floor() as i64;
+    while (ix as f32 * chunk_ticks) < end {
+        let range = ix as f32 * chunk_ticks..(ix + 1) as f32 * chunk_ticks;
+        ix += 1;
+        let x = egui::Rangef::new(
+            timeline.tick_to_x(range.start - offset),
+            timeline.tick_to_x(range.end - offset),
+        );
+        let chunk_rect = egui::Rect::from_x_y_ranges(x, rect.y_range());
+        let painter = ui.painter().with_clip_rect(visible.intersect(chunk_rect));
+        let key = (range.clone(), lod);
+        match source.poll(range.clone(), lod) {
+            Poll::Ready(data) => {
+                in_flight.retain(|k| *k != key);
+                paint(&painter, chunk_rect, data);
+            }
+            Poll::Pending => {
+                if !in_flight.contains(&key) {
+                    source.request(range, lod);
+                    in_flight.push(key.clone());
+                }
+                paint_placeholder(ui, &painter, chunk_rect);
+                pending = true;
+            }
+        }
+        visible_chunks.push(key);
+    }
+
+    // Cancel requests for chunks that are no longer visible.
+    in_flight.retain(|(range, lod)| {
+        let keep = visible_chunks.iter().any(|(r, l)| r == range && l == lod);
+        if !keep {
+            source.cancel(range.clone(), *lod);
+        }
+        keep
+    });
+    ui.data_mut(|d| d.insert_temp(id, in_flight));
+    if pending {
+        ui.ctx().request_repaint_after(POLL_INTERVAL);
+    }
+    response
+}
+
+/// Paint a placeholder for a chunk that is still loading.
+fn paint_placeholder(ui: &egui::Ui, painter: &egui::Painter, rect: egui::Rect) {
+    let color = ui.visuals().weak_text_color().linear_multiply(0.25);
+    painter.rect_filled(rect, 0.0, ui.visuals().faint_bg_color);
+    paint_hatching(painter, rect, egui::Stroke::new(1.0, color));
+}
@@ -0,0 +1,146 @@
+/// Visual configuration for the timeline.
+///
+/// Colors and fonts set to `None` are derived from the `egui::Style` of the timeline's `Ui`.
+#[derive(Clone, Debug)]
+pub struct TimelineStyle {
+    /// The fill used for alternating bar shading.
+    pub bar_shading: Option<egui::Color32>,
+    /// The font used for clip labels. By default, uses `egui::TextStyle::Small`.
+    pub clip_label_font: Option<egui::FontId>,
+    /// The padding between a clip's edges and its label.
+    pub clip_label_padding: egui::Vec2,
+    /// Clip labels are hidden when the clip is narrower than this width in points.
+    pub clip_label_min_width: f32,
+    /// The width of the grid lines in points, snapped to whole physical pixels.
+    pub grid_stroke_width: f32,
+    /// The width of the ruler step lines in points, snapped to whole physical pixels. By default,
+    /// the width of the non-interactive widget `fg_stroke` is used.
+    pub ruler_stroke_width: Option<f32>,
+    /// The width of the playhead line in points, snapped to whole physical pixels.
+    pub playhead_width: f32,
+    /// The maximum number of grid lines drawn per frame, beyond which the finest subdivisions
+    /// are dropped. See `time::limit_steps`.
+    pub max_steps: usize,
+    /// The line drawn where the header panels and pinned tracks meet the rest of the timeline.
+    /// By default, uses the non-interactive widget `bg_stroke`.
+    pub separator_stroke: Option<egui::Stroke>,
+    /// The color of the shadow cast by the header panels and pinned tracks over content scrolling
+    /// beneath them. By default, uses the window shadow color.
+    pub frozen_shadow_color: Option<egui::Color32>,
+    /// The width of the frozen shadow in points. `0.0` disables the shadow.
+    pub frozen_shadow_width: f32,
+    /// The overlay dimming the content of muted tracks, or of all but the soloed tracks while any
+    /// are soloed. By default, uses the panel fill at half opacity.
+    pub inaudible_overlay: Option<egui::Color32>,
+}
+
+impl TimelineStyle {
+    /// The fill used for alternating bar shading.
+    pub fn bar_shading(&self, visuals: &egui::Visuals) -> egui::Color32 {
+        self.bar_shading.unwrap_or(visuals.faint_bg_color)
+    }
+
+    /// The line drawn where frozen regions meet the rest of the timeline.
+    pub fn separator_stroke(&self, visuals: &egui::Visuals) -> egui::Stroke {
+        self.separator_stroke
+            .unwrap_or(visuals.widgets.noninteractive.bg_stroke)
+    }
+
+    /// The color of the shadow cast by frozen regions.
+    pub fn frozen_shadow_color(&self, visuals: &egui::Visuals) -> egui::Color32 {
+        self.frozen_shadow_color
+            .unwrap_or(visuals.window_shadow.color)
+    }
+
+    /// The overlay dimming the content of inaudible tracks.
+    pub fn inaudible_overlay(&self, visuals: &egui::Visuals) -> egui::Color32 {
+        self.inaudible_overlay
+            .unwrap_or_else(|| visuals.panel_fill.gamma_multiply(0.5))
+    }
+
+    /// The font used for clip labels.
+    pub fn clip_label_font(&self, style: &egui::Style) -> egui::FontId {
+        self.clip_label_font
+            .clone()
+            .unwrap_or_else(|| egui::TextStyle::Small.resolve(style))
+    }
+}
+
+/// The key of the `egui::UiStack` tag holding the style of the timeline that a `Ui` belongs to.
+const TAG: &str = "egui_timeline::style";
+
+/// Tag a `Ui` with the style of the timeline it belongs to, read by widgets within it via
+/// `current`.
+pub(crate) fn tag(info: egui::UiStackInfo, style: &TimelineStyle) -> egui::UiStackInfo {
+    info.with_tag_value(TAG, style.clone())
+}
+
+/// The style of the timeline containing the `ui`, or the default style outside of any timeline.
+pub(crate) fn current(ui: &egui::Ui) -> TimelineStyle {
+    ui.stack()
+        .iter()
+        .find_map(|frame| frame.tags().get_downcast::<TimelineStyle>(TAG).cloned())
+        .unwrap_or_default()
+}
+
+/// Paint a shadow within `rect` fading from `color` at the given edge to transparent at the
+/// opposite edge.
+pub fn paint_shadow(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    edge: egui::Direction,
+    color: egui::Color32,
+) {
+    if !rect.is_positive() || color == egui::Color32::TRANSPARENT {
+        return;
+    }
+    let clear = egui::Color32::TRANSPARENT;
+    let [lt, rt, rb, lb] = match edge {
+        egui::Direction::LeftToRight => [color, clear, clear, color],
+        egui::Direction::RightToLeft => [clear, color, color, clear],
+        egui::Direction::TopDown => [color, color, clear, clear],
+        egui::Direction::BottomUp => [clear, clear, color, color],
+    };
+    let vertex = |pos, color| egui::epaint::Vertex {
+        pos,
+        uv: egui::epaint::WHITE_UV,
+        color,
+    };
+    let mut mesh = egui::Mesh::default();
+    mesh.vertices.extend([
+        vertex(rect.left_top(), lt),
+        vertex(rect.right_top(), rt),
+        vertex(rect.right_bottom(), rb),
+        vertex(rect.left_bottom(), lb),
+    ]);
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(0, 2, 3);
+    painter.add(mesh);
+}
+
+/// Snap the given stroke width in points to a whole number of physical pixels, and at least one.
+///
+/// This avoids thin lines disappearing or doubling in width at fractional DPI scales.
+pub fn snap_stroke_width(ctx: &egui::Context, width: f32) -> f32 {
+    let pixels_per_point = ctx.pixels_per_point();
+    (width * pixels_per_point).round().max(1.0) / pixels_per_point
+}
+
+impl Default for TimelineStyle {
+    fn default() -> Self {
+        Self {
+            bar_shading: None,
+            clip_label_font: None,
+            clip_label_padding: egui::vec2(4.0, 2.0),
+            clip_label_min_width: 24.0,
+            grid_stroke_width: 1.0,
+            ruler_stroke_width: None,
+            playhead_width: 1.0,
+            max_steps: crate::MAX_STEPS,
+            separator_stroke: None,
+            frozen_shadow_color: None,
+            frozen_shadow_width: 6.0,
+            inaudible_overlay: None,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use super::{
+    cursor::{self, Target},
+    gesture::{self, Gesture},
+};
+use std::{hash::Hash, ops::RangeInclusive};
+
+/// The change in tempo in beats per minute for each point dragged vertically.
+pub const DRAG_BPM_PER_POINT: f32 = 0.5;
+/// The fraction of `DRAG_BPM_PER_POINT` used while `Shift` is held.
+pub const FINE_DRAG: f32 = 0.1;
+/// The minimum width of the text field used to type a tempo.
+pub const EDIT_MIN_WIDTH: f32 = 48.0;
+
+/// Access to the tempo displayed and changed by the tempo readout.
+pub trait Tempo {
+    /// The current tempo in beats per minute, e.g. at the playhead.
+    fn bpm(&self) -> f32;
+    /// The range within which the tempo may be set. By default, `20.0..=999.0`.
+    fn bpm_range(&self) -> RangeInclusive<f32> {
+        20.0..=999.0
+    }
+    /// A change to the given tempo was requested by dragging or typing.
+    fn set_bpm(&mut self, bpm: f32);
+    /// The tempo is being dragged, with the initial and current tempo.
+    fn bpm_gesture(&mut self, _gesture: Gesture<f32>) {}
+}
+
+/// Show the tempo as a compact readout, designed for the pinned header corner, e.g. the header of
+/// a pinned ruler track.
+///
+/// Drag vertically to change the tempo, holding `Shift` for finer steps, or double-click to type an
+/// exact tempo and confirm with `Enter`. Changes are requested via `Tempo::set_bpm`, rounded to
+/// hundredths of a beat per minute and clamped to `Tempo::bpm_range`.
+pub fn show(ui: &mut egui::Ui, id_source: impl Hash, api: &mut dyn Tempo) -> egui::Response {
+    let id = ui.make_persistent_id(id_source);
+    let edit_id = id.with("edit");
+    let focus_id = id.with("focus");
+    let range = api.bpm_range();
+    let clamp = |bpm: f32| ((bpm * 100.0).round() / 100.0).clamp(*range.start(), *range.end());
+    let bpm = api.bpm();
+    let text = format!("{:.2} BPM", bpm);
+
+    // Type an exact tempo after double-clicking.
+    let editing: Option<String> = ui.data(|d| d.get_temp(edit_id));
+    if let Some(mut edit_text) = editing {
+        let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+        let w = ui.fonts(|f| {
+            f.layout_no_wrap(text, font_id, egui::Color32::WHITE)
+                .rect
+                .width()
+        });
+        let edit = egui::TextEdit::singleline(&mut edit_text)
+            .font(egui::TextStyle::Monospace)
+            .desired_width(w.max(EDIT_MIN_WIDTH));
+        let mut response = ui.add(edit);
+        if ui.data_mut(|d| d.remove_temp::<bool>(focus_id)).is_some() {
+            response.request_focus();
+        }
+        if !response.lost_focus() {
+            ui.data_mut(|d| d.insert_temp(edit_id, edit_text));
+            return response;
+        }
+        ui.data_mut(|d| d.remove::<String>(edit_id));
+        let entered = ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if let Some(bpm) = parse(&edit_text).filter(|_| entered) {
+            api.set_bpm(clamp(bpm));
+            response.mark_changed();
+        }
+        return response;
+    }
+
+    let inner = egui::Frame::none()
+        .inner_margin(ui.spacing().button_padding)
+        .rounding(ui.visuals().widgets.inactive.rounding)
+        .fill(ui.visuals().extreme_bg_color)
+        .show(ui, |ui| ui.monospace(text));
+    let mut response = ui.interact(inner.response.rect, id, egui::Sense::click_and_drag());
+    cursor::set(&response, Target::VerticalHandle);
+
+    // Drag vertically to change the tempo, accumulating the unrounded tempo until released.
+    let drag_id = id.with("drag");
+    if response.dragged() {
+        let rate = match ui.input(|i| i.modifiers.shift) {
+            true => DRAG_BPM_PER_POINT * FINE_DRAG,
+            false => DRAG_BPM_PER_POINT,
+        };
+        let dragged: f32 = ui.data(|d| d.get_temp(drag_id)).unwrap_or(bpm);
+        let dragged =
+            (dragged - response.drag_delta().y * rate).clamp(*range.start(), *range.end());
+        ui.data_mut(|d| d.insert_temp(drag_id, dragged));
+        if clamp(dragged) != bpm {
+            api.set_bpm(clamp(dragged));
+            response.mark_changed();
+        }
+    }
+    if response.drag_stopped() {
+        ui.data_mut(|d| d.remove::<f32>(drag_id));
+    }
+    if let Some(gesture) = gesture::track(ui, &response, bpm, api.bpm()) {
+        if let Gesture::Cancelled { initial } = gesture {
+            ui.data_mut(|d| d.remove::<f32>(drag_id));
+            api.set_bpm(initial);
+        }
+        api.bpm_gesture(gesture);
+    }
+
+    if response.double_clicked() {
+        let text = format!("{:.2}", api.bpm());
+        ui.data_mut(|d| {
+            d.insert_temp(edit_id, text);
+            d.insert_temp(focus_id, true);
+        });
+        ui.ctx().request_repaint();
+    }
+    response.on_hover_text("Drag to change the tempo, or double-click to type it")
+}
+
+/// Parse a typed tempo in beats per minute, e.g. `128`, `92.5` or `140 bpm`.
+pub fn parse(text: &str) -> Option<f32> {
+    let text = text.trim();
+    let text = match text.len().checked_sub(3) {
+        Some(ix) if text.is_char_boundary(ix) && text[ix..].eq_ignore_ascii_case("bpm") => {
+            &text[..ix]
+        }
+        _ => text,
+    };
+    text.trim().parse().ok().filter(|bpm: &f32| bpm.is_finite())
+}
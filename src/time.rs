@@ -0,0 +1,433 @@
+use super::{
+    format::TimeFormatter,
+    ruler::{self, MusicalInfo},
+};
+
+/// A single step along a time domain, e.g. a bar, beat, second or frame.
+#[derive(Copy, Clone, Debug)]
+pub struct Step {
+    /// The prominence of the step, where `0` is the most prominent (e.g. the start of a bar or a
+    /// whole second) and higher levels are increasingly fine subdivisions.
+    pub level: u8,
+    /// The position of the step in units from the start of the visible area.
+    pub units: f32,
+    /// The location of the step along the x axis from the start of the visible area.
+    pub x: f32,
+}
+
+/// A domain of time over which the timeline is laid out, e.g. musical ticks, seconds, samples or
+/// video frames.
+///
+/// All types implementing `MusicalInfo` implement `TimeDomain` with ticks as the unit.
+pub trait TimeDomain {
+    /// The number of units per point along the x axis, affecting how "zoomed" the timeline is.
+    fn units_per_point(&self) -> f32;
+    /// The steps visible across `visible_len` points, spaced no less than `min_step_gap` points
+    /// apart.
+    fn steps<'a>(
+        &'a self,
+        visible_len: f32,
+        min_step_gap: f32,
+    ) -> Box<dyn Iterator<Item = Step> + 'a>;
+    /// An optional label for the given step, displayed by the ruler.
+    ///
+    /// Returns `None` by default, in which case no label is displayed.
+    fn label(&self, _step: &Step) -> Option<String> {
+        None
+    }
+    /// Snap the given position in units from the start of the visible area to the nearest
+    /// position that may be edited within this domain.
+    ///
+    /// Returns the position unchanged by default.
+    fn snap(&self, units: f32) -> f32 {
+        units
+    }
+    /// Access to the musical information if this is a musical domain, used for bar-aware
+    /// rendering such as alternating bar shading.
+    ///
+    /// Returns `None` by default.
+    fn musical_info(&self) -> Option<&dyn MusicalInfo> {
+        None
+    }
+}
+
+/// A clock time domain with seconds as the unit.
+#[derive(Copy, Clone, Debug)]
+pub struct Seconds {
+    /// The time in seconds at the start (left) of the visible area.
+    pub start: f64,
+    /// The number of seconds per point along the x axis.
+    pub seconds_per_point: f32,
+}
+
+/// An audio time domain with samples as the unit, for sample-accurate editing.
+#[derive(Copy, Clone, Debug)]
+pub struct Samples {
+    /// The sample position at the start (left) of the visible area.
+    pub start: f64,
+    /// The number of samples per point along the x axis.
+    pub samples_per_point: f32,
+    /// How the ruler and grid subdivide the samples.
+    pub subdivision: Subdivision,
+}
+
+/// The kind of intervals used to subdivide a domain.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Subdivision {
+    /// Intervals of 1, 2 or 5 × 10ⁿ units.
+    #[default]
+    Decimal,
+    /// Intervals of 2ⁿ units.
+    Binary,
+}
+
+/// A video time domain with frames as the unit.
+#[derive(Copy, Clone, Debug)]
+pub struct Frames {
+    /// The frame position at the start (left) of the visible area.
+    pub start: f64,
+    /// The number of frames per point along the x axis.
+    pub frames_per_point: f32,
+    /// The frame rate, determining how frames are grouped into seconds and labelled.
+    pub rate: FrameRate,
+    /// Whether or not positions should snap to whole frames.
+    pub snap_to_frames: bool,
+}
+
+/// Common video frame rates.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FrameRate {
+    /// 24000/1001 frames per second, labelled with 24 frame timecode.
+    Fps23_976,
+    Fps24,
+    Fps25,
+    /// 30000/1001 frames per second, labelled with non-drop-frame 30 frame timecode.
+    Fps29_97,
+    /// 30000/1001 frames per second, labelled with drop-frame timecode.
+    Fps29_97DropFrame,
+    Fps30,
+    Fps60,
+}
+
+impl<T: MusicalInfo> TimeDomain for T {
+    fn units_per_point(&self) -> f32 {
+        self.ticks_per_point()
+    }
+    fn steps<'a>(
+        &'a self,
+        visible_len: f32,
+        min_step_gap: f32,
+    ) -> Box<dyn Iterator<Item = Step> + 'a> {
+        musical_steps(self, visible_len, min_step_gap)
+    }
+    fn musical_info(&self) -> Option<&dyn MusicalInfo> {
+        Some(self)
+    }
+}
+
+impl<'b> TimeDomain for dyn MusicalInfo + 'b {
+    fn units_per_point(&self) -> f32 {
+        self.ticks_per_point()
+    }
+    fn steps<'a>(
+        &'a self,
+        visible_len: f32,
+        min_step_gap: f32,
+    ) -> Box<dyn Iterator<Item = Step> + 'a> {
+        musical_steps(self, visible_len, min_step_gap)
+    }
+    fn musical_info(&self) -> Option<&dyn MusicalInfo> {
+        Some(self)
+    }
+}
+
+impl TimeDomain for Seconds {
+    fn units_per_point(&self) -> f32 {
+        self.seconds_per_point
+    }
+    fn steps<'a>(
+        &'a self,
+        visible_len: f32,
+        min_step_gap: f32,
+    ) -> Box<dyn Iterator<Item = Step> + 'a> {
+        let steps = decimal_steps(
+            self.start,
+            self.seconds_per_point,
+            visible_len,
+            min_step_gap,
+        );
+        Box::new(steps)
+    }
+    fn label(&self, step: &Step) -> Option<String> {
+        match step.level {
+            0 => Some(self.format(step.units)),
+            _ => None,
+        }
+    }
+}
+
+impl TimeDomain for Samples {
+    fn units_per_point(&self) -> f32 {
+        self.samples_per_point
+    }
+    fn steps<'a>(
+        &'a self,
+        visible_len: f32,
+        min_step_gap: f32,
+    ) -> Box<dyn Iterator<Item = Step> + 'a> {
+        // Never subdivide beyond a single sample.
+        let spp = self.samples_per_point;
+        let min_step_gap = min_step_gap.max(1.0 / spp);
+        match self.subdivision {
+            Subdivision::Decimal => {
+                Box::new(decimal_steps(self.start, spp, visible_len, min_step_gap))
+            }
+            Subdivision::Binary => {
+                Box::new(binary_steps(self.start, spp, visible_len, min_step_gap))
+            }
+        }
+    }
+    fn label(&self, step: &Step) -> Option<String> {
+        match step.level {
+            0 => Some(self.format(step.units)),
+            _ => None,
+        }
+    }
+    fn snap(&self, units: f32) -> f32 {
+        ((self.start + units as f64).round() - self.start) as f32
+    }
+}
+
+impl TimeDomain for Frames {
+    fn units_per_point(&self) -> f32 {
+        self.frames_per_point
+    }
+    fn steps<'a>(
+        &'a self,
+        visible_len: f32,
+        min_step_gap: f32,
+    ) -> Box<dyn Iterator<Item = Step> + 'a> {
+        let fpp = self.frames_per_point;
+        let nominal = self.rate.nominal() as i64;
+        let min_step = (fpp * min_step_gap) as f64;
+        let step = if min_step <= nominal as f64 {
+            // Sub-second steps at the smallest whole divisor of a second.
+            (1..=nominal)
+                .find(|&d| nominal % d == 0 && d as f64 >= min_step)
+                .unwrap_or(nominal)
+        } else {
+            // Steps of whole seconds.
+            let secs = [1, 2, 5, 10, 15, 30, 60, 120, 300, 600, 1800, 3600];
+            let s = secs
+                .iter()
+                .copied()
+                .find(|&s| (s * nominal) as f64 >= min_step)
+                .unwrap_or(3600);
+            s * nominal
+        };
+        let level = move |n: i64| {
+            let frame = n * step;
+            if step < nominal {
+                match frame {
+                    f if f % nominal == 0 => 0,
+                    f if nominal % 2 == 0 && f % (nominal / 2) == 0 => 1,
+                    _ => 2,
+                }
+            } else {
+                match frame / nominal {
+                    s if s % 60 == 0 => 0,
+                    s if s % 10 == 0 => 1,
+                    _ => 2,
+                }
+            }
+        };
+        let steps = interval_steps(self.start, step as f64, fpp, visible_len, level);
+        Box::new(steps)
+    }
+    fn label(&self, step: &Step) -> Option<String> {
+        match step.level {
+            0 => Some(self.format(step.units)),
+            _ => None,
+        }
+    }
+    fn snap(&self, units: f32) -> f32 {
+        match self.snap_to_frames {
+            true => ((self.start + units as f64).round() - self.start) as f32,
+            false => units,
+        }
+    }
+}
+
+impl FrameRate {
+    /// The actual number of frames per second.
+    pub fn fps(&self) -> f64 {
+        match *self {
+            FrameRate::Fps23_976 => 24_000.0 / 1_001.0,
+            FrameRate::Fps24 => 24.0,
+            FrameRate::Fps25 => 25.0,
+            FrameRate::Fps29_97 | FrameRate::Fps29_97DropFrame => 30_000.0 / 1_001.0,
+            FrameRate::Fps30 => 30.0,
+            FrameRate::Fps60 => 60.0,
+        }
+    }
+
+    /// The whole number of frames per second used for timecode labels.
+    pub fn nominal(&self) -> u32 {
+        match *self {
+            FrameRate::Fps23_976 | FrameRate::Fps24 => 24,
+            FrameRate::Fps25 => 25,
+            FrameRate::Fps29_97 | FrameRate::Fps29_97DropFrame | FrameRate::Fps30 => 30,
+            FrameRate::Fps60 => 60,
+        }
+    }
+
+    /// Whether or not timecode labels use drop-frame numbering.
+    pub fn drop_frame(&self) -> bool {
+        matches!(*self, FrameRate::Fps29_97DropFrame)
+    }
+
+    /// Format the given frame index as `hh:mm:ss:ff` timecode.
+    ///
+    /// Drop-frame timecode skips frame numbers `00` and `01` at the start of every minute except
+    /// every tenth minute, and uses `;` to separate the frames.
+    pub fn timecode(&self, frame: i64) -> String {
+        let sign = if frame < 0 { "-" } else { "" };
+        let mut frame = frame.abs();
+        let fps = self.nominal() as i64;
+        if self.drop_frame() {
+            const FRAMES_PER_10_MINS: i64 = 17_982;
+            const FRAMES_PER_MIN: i64 = 1_798;
+            let tens = frame / FRAMES_PER_10_MINS;
+            let rem = frame % FRAMES_PER_10_MINS;
+            frame += 18 * tens;
+            if rem > 2 {
+                frame += 2 * ((rem - 2) / FRAMES_PER_MIN);
+            }
+        }
+        let ff = frame % fps;
+        let ss = (frame / fps) % 60;
+        let mm = (frame / (fps * 60)) % 60;
+        let hh = frame / (fps * 60 * 60);
+        let sep = if self.drop_frame() { ';' } else { ':' };
+        format!("{}{:02}:{:02}:{:02}{}{:02}", sign, hh, mm, ss, sep, ff)
+    }
+}
+
+/// Steps over a musical domain, emphasised by their metric strength within the bar.
+fn musical_steps<'a>(
+    info: &'a dyn MusicalInfo,
+    visible_len: f32,
+    min_step_gap: f32,
+) -> Box<dyn Iterator<Item = Step> + 'a> {
+    let steps = ruler::Steps::new(info, visible_len, min_step_gap).map(|step| Step {
+        level: step.level,
+        units: step.ticks,
+        x: step.x,
+    });
+    Box::new(steps)
+}
+
+/// Collect at most `max_steps` of the given steps, dropping the finest levels first.
+///
+/// Whenever the limit is exceeded, all steps of the finest level collected so far are discarded
+/// along with any later steps of that level. At most a few multiples of `max_steps` are ever
+/// consumed from `steps`, so that a pathological zoom can't stall the frame.
+pub fn limit_steps(steps: impl Iterator<Item = Step>, max_steps: usize) -> Vec<Step> {
+    const MAX_CONSUMED_PER_STEP: usize = 4;
+    let mut limited = Vec::new();
+    let mut max_level = u8::MAX;
+    for step in steps.take(max_steps.saturating_mul(MAX_CONSUMED_PER_STEP)) {
+        if step.level > max_level {
+            continue;
+        }
+        limited.push(step);
+        if limited.len() > max_steps {
+            match limited.iter().map(|s| s.level).max() {
+                Some(finest) if finest > 0 => {
+                    max_level = finest - 1;
+                    limited.retain(|s| s.level <= max_level);
+                }
+                _ => {
+                    limited.truncate(max_steps);
+                    break;
+                }
+            }
+        }
+    }
+    limited
+}
+
+/// Steps at "nice" decimal intervals of 1, 2 or 5 × 10ⁿ units.
+///
+/// Every tenth power of ten is emphasised with level `0`, with level `1` halfway between where
+/// possible. All other steps are level `2`. The given `start` is the position in units at the
+/// start (left) of the visible area.
+pub fn decimal_steps(
+    start: f64,
+    units_per_point: f32,
+    visible_len: f32,
+    min_step_gap: f32,
+) -> impl Iterator<Item = Step> {
+    let min_step = (units_per_point * min_step_gap) as f64;
+    let base = 10f64.powf(min_step.max(f64::MIN_POSITIVE).log10().floor());
+    let mantissa = [1u8, 2, 5, 10]
+        .iter()
+        .copied()
+        .find(|&m| base * m as f64 >= min_step)
+        .unwrap_or(10);
+    let (step, steps_per_major) = match mantissa {
+        1 => (base, 10),
+        2 => (base * 2.0, 5),
+        5 => (base * 5.0, 2),
+        _ => (base * 10.0, 10),
+    };
+    let level = move |n: i64| match n {
+        n if n % steps_per_major == 0 => 0,
+        n if steps_per_major == 10 && n % 5 == 0 => 1,
+        _ => 2,
+    };
+    interval_steps(start, step, units_per_point, visible_len, level)
+}
+
+/// Steps at intervals of 2ⁿ units.
+///
+/// Every sixteenth step is emphasised with level `0` and every fourth with level `1`. All other
+/// steps are level `2`. The given `start` is the position in units at the start (left) of the
+/// visible area.
+pub fn binary_steps(
+    start: f64,
+    units_per_point: f32,
+    visible_len: f32,
+    min_step_gap: f32,
+) -> impl Iterator<Item = Step> {
+    let min_step = (units_per_point * min_step_gap) as f64;
+    let step = 2f64.powf(min_step.max(f64::MIN_POSITIVE).log2().ceil());
+    let level = |n: i64| match n {
+        n if n % 16 == 0 => 0,
+        n if n % 4 == 0 => 1,
+        _ => 2,
+    };
+    interval_steps(start, step, units_per_point, visible_len, level)
+}
+
+/// Steps at a regular interval, with the level of each step determined by its index.
+fn interval_steps(
+    start: f64,
+    step: f64,
+    units_per_point: f32,
+    visible_len: f32,
+    level: impl Fn(i64) -> u8,
+) -> impl Iterator<Item = Step> {
+    let visible_units = (units_per_point * visible_len) as f64;
+    let first = (start / step).ceil() as i64;
+    let valid = step > 0.0 && step.is_finite();
+    (first..)
+        .map(move |n| (n, n as f64 * step - start))
+        .take_while(move |&(_, units)| valid && units <= visible_units)
+        .map(move |(n, units)| {
+            let units = units as f32;
+            let x = units / units_per_point;
+            let level = level(n);
+            Step { level, units, x }
+        })
+}
@@ -0,0 +1,69 @@
+use super::cursor::{self, Target};
+use std::{hash::Hash, ops::Range};
+
+/// The kind of time edit previewed by the overlay.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Insert empty time, shifting all later content to the right.
+    Insert,
+    /// Delete time, collapsing all later content to the left.
+    Delete,
+}
+
+/// Instantiate an overlay for inserting or deleting time over the given timeline `rect`.
+///
+/// While the overlay is active, dragging across the rect previews the edit. For `Mode::Insert`
+/// the inserted gap is highlighted with the shifted content boundary marked at its end, while for
+/// `Mode::Delete` the collapsed region is tinted with an arrow marking where later content will
+/// move to. Returns the final range in ticks relative to the start of the timeline once the drag
+/// is released, leaving the host to perform the edit.
+///
+/// This is designed to be called after all tracks have been set, typically while a host-specific
+/// insert or delete time tool is active.
+pub fn overlay(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    rect: egui::Rect,
+    ticks_per_point: f32,
+    mode: Mode,
+) -> Option<Range<f32>> {
+    let id = ui.make_persistent_id(id_source);
+    let response = ui.interact(rect, id, egui::Sense::drag());
+    cursor::set(&response, Target::Edge);
+    let origin = ui.input(|i| i.pointer.press_origin());
+    let (origin, pos) = match (origin, response.interact_pointer_pos()) {
+        (Some(origin), Some(pos)) if response.dragged() || response.drag_stopped() => (origin, pos),
+        _ => return None,
+    };
+    let to_tick = |x: f32| ((x - rect.left()) * ticks_per_point).max(0.0);
+    let (a, b) = (to_tick(origin.x), to_tick(pos.x));
+    let range = a.min(b)..a.max(b);
+
+    // Preview the edit.
+    let to_x = |tick: f32| rect.left() + tick / ticks_per_point;
+    let x = egui::Rangef::new(to_x(range.start), to_x(range.end));
+    let region = egui::Rect::from_x_y_ranges(x, rect.y_range());
+    let painter = ui.painter().with_clip_rect(ui.clip_rect().intersect(rect));
+    let visuals = ui.visuals();
+    let color = match mode {
+        Mode::Insert => visuals.selection.bg_fill,
+        Mode::Delete => visuals.error_fg_color,
+    };
+    painter.rect_filled(region, 0.0, color.linear_multiply(0.2));
+    let stroke = egui::Stroke::new(1.0, color);
+    let (from, to) = match mode {
+        Mode::Insert => (x.min, x.max),
+        Mode::Delete => (x.max, x.min),
+    };
+    let faint = egui::Stroke::new(1.0, color.linear_multiply(0.5));
+    painter.vline(from, rect.y_range(), faint);
+    painter.vline(to, rect.y_range(), egui::Stroke::new(2.0, color));
+    let arrow_y = region.center().y;
+    let origin = egui::Pos2::new(from, arrow_y);
+    painter.arrow(origin, egui::vec2(to - from, 0.0), stroke);
+
+    match response.drag_stopped() && !range.is_empty() {
+        true => Some(range),
+        false => None,
+    }
+}
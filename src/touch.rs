@@ -0,0 +1,33 @@
+/// The minimum size of draggable lines, edges and handles while a touch screen is in use.
+pub const MIN_TARGET_SIZE: f32 = 20.0;
+
+/// Whether or not a touch screen has been detected, or the target is a mobile platform.
+///
+/// Touch-friendly hit areas are used whenever this is `true`. Long-pressing on a touch screen is
+/// treated as a secondary click by `egui`, so any widget response with click sense may be given a
+/// context menu via `egui::Response::context_menu`.
+pub fn is_touch(ctx: &egui::Context) -> bool {
+    cfg!(any(target_os = "android", target_os = "ios")) || ctx.input(|i| i.has_touch_screen())
+}
+
+/// Widen the given hit area about its center to at least `MIN_TARGET_SIZE` on touch screens.
+pub(crate) fn inflate_x(ui: &egui::Ui, rect: egui::Rect) -> egui::Rect {
+    match is_touch(ui.ctx()) {
+        true => rect.expand2(egui::vec2(
+            (MIN_TARGET_SIZE - rect.width()).max(0.0) * 0.5,
+            0.0,
+        )),
+        false => rect,
+    }
+}
+
+/// Heighten the given hit area about its center to at least `MIN_TARGET_SIZE` on touch screens.
+pub(crate) fn inflate_y(ui: &egui::Ui, rect: egui::Rect) -> egui::Rect {
+    match is_touch(ui.ctx()) {
+        true => rect.expand2(egui::vec2(
+            0.0,
+            (MIN_TARGET_SIZE - rect.height()).max(0.0) * 0.5,
+        )),
+        false => rect,
+    }
+}
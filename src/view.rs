@@ -0,0 +1,114 @@
+use super::{ruler::MusicalInfo, Bar, TimeDomain, TimeSig, Timeline, TimelineApi, TracksCtx};
+use std::ops::RangeInclusive;
+
+/// A built-in view over a musical timeline with a constant time signature, for hosts that don't
+/// need to manage the view within their own model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewState {
+    /// The absolute tick at the start (left) of the visible area.
+    pub start: f32,
+    /// The number of ticks per point along the x axis.
+    pub ticks_per_point: f32,
+    /// The number of ticks per beat, also known as PPQN.
+    pub ticks_per_beat: u32,
+    /// The time signature of every bar.
+    pub time_sig: TimeSig,
+    /// The range within which zooming clamps the ticks per point.
+    pub zoom_range: RangeInclusive<f32>,
+}
+
+/// A timeline implementing `egui::Widget`, created via `widget`.
+pub struct SimpleTimeline<'a, F> {
+    state: &'a mut ViewState,
+    tracks: F,
+}
+
+/// A timeline showing the given view, with a function for setting its tracks.
+///
+/// Scrolling and zooming update the `state` and mark the response as changed. For headers,
+/// rulers, playheads and everything else, use `Timeline` directly.
+pub fn widget<F>(state: &mut ViewState, tracks: F) -> SimpleTimeline<'_, F>
+where
+    F: FnOnce(&TracksCtx, egui::Rect, &mut egui::Ui),
+{
+    SimpleTimeline { state, tracks }
+}
+
+impl ViewState {
+    /// The number of ticks in each bar.
+    pub fn bar_ticks(&self) -> f32 {
+        self.time_sig.beats_per_bar() * self.ticks_per_beat as f32
+    }
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self {
+            start: 0.0,
+            ticks_per_point: 960.0 / 16.0,
+            ticks_per_beat: 960,
+            time_sig: TimeSig { top: 4, bottom: 4 },
+            zoom_range: 0.5..=4096.0,
+        }
+    }
+}
+
+impl MusicalInfo for ViewState {
+    fn ticks_per_beat(&self) -> u32 {
+        self.ticks_per_beat
+    }
+    fn bar_at_ticks(&self, tick: f32) -> Bar {
+        let bar_ticks = self.bar_ticks();
+        let start = ((self.start + tick) / bar_ticks).floor() * bar_ticks - self.start;
+        Bar {
+            tick_range: start..start + bar_ticks,
+            time_sig: self.time_sig,
+        }
+    }
+    fn ticks_per_point(&self) -> f32 {
+        self.ticks_per_point
+    }
+    fn first_bar_number(&self) -> i64 {
+        (self.start / self.bar_ticks()).floor() as i64 + 1
+    }
+}
+
+impl TimelineApi for ViewState {
+    fn time_domain(&self) -> &dyn TimeDomain {
+        self
+    }
+    fn shift_timeline_start(&mut self, ticks: f32) {
+        self.start = (self.start + ticks).max(0.0);
+    }
+    fn zoom(&mut self, y_delta: f32) {
+        let tpp = self.ticks_per_point * (-y_delta * 0.005).exp();
+        self.set_ticks_per_point(tpp);
+    }
+    fn timeline_start(&self) -> Option<f32> {
+        Some(self.start)
+    }
+    fn set_ticks_per_point(&mut self, ticks_per_point: f32) {
+        let (min, max) = (*self.zoom_range.start(), *self.zoom_range.end());
+        self.ticks_per_point = ticks_per_point.clamp(min, max);
+    }
+}
+
+impl<'a, F> egui::Widget for SimpleTimeline<'a, F>
+where
+    F: FnOnce(&TracksCtx, egui::Rect, &mut egui::Ui),
+{
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let Self { state, tracks } = self;
+        let before = state.clone();
+        let mut response = ui
+            .scope(|ui| {
+                let show = Timeline::new().show(ui, state);
+                show.paint_grid(&*state).tracks(tracks);
+            })
+            .response;
+        if *state != before {
+            response.mark_changed();
+        }
+        response
+    }
+}
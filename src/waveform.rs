@@ -0,0 +1,173 @@
+use super::{
+    source::{self, LaneDataSource},
+    TimelineCtx,
+};
+#[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+use rayon::prelude::*;
+use std::hash::Hash;
+
+/// Paint a waveform within `rect` from min/max peak pairs spread evenly along the x axis.
+///
+/// Peaks are expected in the range `-1.0..=1.0`. They are scaled by `gain` and clamped to the
+/// `rect` before painting. Only the columns within the painter's clip rect are painted.
+pub fn paint(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    peaks: &[[f32; 2]],
+    gain: f32,
+    color: egui::Color32,
+) {
+    let visible = painter.clip_rect().intersect(rect);
+    if peaks.is_empty() || !visible.is_positive() {
+        return;
+    }
+    let stroke = egui::Stroke::new(1.0, color);
+    let center_y = rect.center().y;
+    let half_h = rect.height() * 0.5;
+    let peaks_per_point = peaks.len() as f32 / rect.width();
+
+    // Draw a single vertical line for each visible point-wide column.
+    let mut x = visible.left().floor().max(rect.left());
+    while x < visible.right() {
+        let start = ((x - rect.left()) * peaks_per_point) as usize;
+        let end = ((x + 1.0 - rect.left()) * peaks_per_point).ceil() as usize;
+        let start = start.min(peaks.len() - 1);
+        let end = end.clamp(start + 1, peaks.len());
+        let (min, max) = peaks[start..end]
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), &[lo, hi]| {
+                (min.min(lo), max.max(hi))
+            });
+        let top = center_y - (max * gain).clamp(-1.0, 1.0) * half_h;
+        let bottom = center_y - (min * gain).clamp(-1.0, 1.0) * half_h;
+        let a = egui::Pos2::new(x, top);
+        let b = egui::Pos2::new(x, bottom);
+        painter.line_segment([a, b], stroke);
+        x += 1.0;
+    }
+}
+
+/// Paint only the envelope of a waveform within `rect`, as a simplified alternative to `paint`
+/// when zoomed out.
+///
+/// Draws the upper and lower outline of the peaks with one vertex per `step` points.
+pub fn paint_outline(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    peaks: &[[f32; 2]],
+    gain: f32,
+    step: f32,
+    color: egui::Color32,
+) {
+    let visible = painter.clip_rect().intersect(rect);
+    if peaks.is_empty() || !visible.is_positive() {
+        return;
+    }
+    let step = step.max(1.0);
+    let center_y = rect.center().y;
+    let half_h = rect.height() * 0.5;
+    let peaks_per_point = peaks.len() as f32 / rect.width();
+    let mut upper = vec![];
+    let mut lower = vec![];
+    let mut x = visible.left().floor().max(rect.left());
+    while x < visible.right() + step {
+        let x0 = x.min(rect.right());
+        let start = ((x0 - rect.left()) * peaks_per_point) as usize;
+        let end = ((x0 + step - rect.left()) * peaks_per_point).ceil() as usize;
+        let start = start.min(peaks.len() - 1);
+        let end = end.clamp(start + 1, peaks.len());
+        let (min, max) = peaks[start..end]
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(min, max), &[lo, hi]| {
+                (min.min(lo), max.max(hi))
+            });
+        upper.push(egui::pos2(
+            x0,
+            center_y - (max * gain).clamp(-1.0, 1.0) * half_h,
+        ));
+        lower.push(egui::pos2(
+            x0,
+            center_y - (min * gain).clamp(-1.0, 1.0) * half_h,
+        ));
+        x += step;
+    }
+    let stroke = egui::Stroke::new(1.0, color);
+    painter.add(egui::Shape::line(upper, stroke));
+    painter.add(egui::Shape::line(lower, stroke));
+}
+
+/// Build min/max peak pairs from `samples`, each covering `samples_per_peak` samples.
+///
+/// With the `rayon` feature enabled, the peaks are computed in parallel.
+pub fn peaks(samples: &[f32], samples_per_peak: usize) -> Vec<[f32; 2]> {
+    let samples_per_peak = samples_per_peak.max(1);
+    #[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+    let chunks = samples.par_chunks(samples_per_peak);
+    #[cfg(not(all(feature = "rayon", not(target_arch = "wasm32"))))]
+    let chunks = samples.chunks(samples_per_peak);
+    chunks
+        .map(|chunk| {
+            chunk
+                .iter()
+                .fold([f32::MAX, f32::MIN], |[lo, hi], &s| [lo.min(s), hi.max(s)])
+        })
+        .collect()
+}
+
+/// Build a pyramid of peaks from `samples`, starting at `samples_per_peak` and halving the
+/// resolution of each following level until a level has no more than `min_len` peaks.
+///
+/// Pass the level returned by `pyramid_level` to `paint` to avoid binning many more peaks than
+/// there are points to draw them. With the `rayon` feature enabled, each level is computed in
+/// parallel.
+pub fn pyramid(samples: &[f32], samples_per_peak: usize, min_len: usize) -> Vec<Vec<[f32; 2]>> {
+    let mut levels = vec![peaks(samples, samples_per_peak)];
+    while let Some(last) = levels.last().filter(|l| l.len() > min_len.max(1)) {
+        #[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+        let pairs = last.par_chunks(2);
+        #[cfg(not(all(feature = "rayon", not(target_arch = "wasm32"))))]
+        let pairs = last.chunks(2);
+        let next = pairs
+            .map(|pair| {
+                pair.iter().fold([f32::MAX, f32::MIN], |[lo, hi], &[l, h]| {
+                    [lo.min(l), hi.max(h)]
+                })
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// The coarsest level of the given pyramid with at least one peak per point across `width`.
+pub fn pyramid_level(pyramid: &[Vec<[f32; 2]>], width: f32) -> &[[f32; 2]] {
+    pyramid
+        .iter()
+        .rev()
+        .find(|level| level.len() as f32 >= width)
+        .or_else(|| pyramid.first())
+        .map(|level| &level[..])
+        .unwrap_or(&[])
+}
+
+/// Instantiate a waveform lane of the given height, progressively filled with peaks from the
+/// given source as they finish loading.
+///
+/// Each chunk's peaks are spread evenly across the chunk. See `source::lane` for details.
+pub fn lane(
+    ui: &mut egui::Ui,
+    id_source: impl Hash,
+    timeline: &TimelineCtx,
+    height: f32,
+    source: &mut dyn LaneDataSource<Data = Vec<[f32; 2]>>,
+) -> egui::Response {
+    let color = ui.visuals().widgets.noninteractive.fg_stroke.color;
+    source::lane(
+        ui,
+        id_source,
+        timeline,
+        height,
+        source,
+        |painter, rect, peaks| paint(painter, rect, peaks, 1.0, color),
+    )
+}
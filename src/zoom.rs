@@ -0,0 +1,193 @@
+use super::{TimelineApi, TimelineCtx};
+use std::ops::RangeInclusive;
+
+/// A ladder of discrete zoom levels, each a constant factor apart in ticks per point.
+///
+/// Used by keyboard `+`/`-` zoom via `Timeline::zoom_levels`, and available to hosts for ruler
+/// double-click zoom, so that repeated zooms land on consistent levels rather than accumulating
+/// arbitrary factors.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ladder {
+    /// The ticks per point at level `0`.
+    pub base: f32,
+    /// The factor between adjacent levels, greater than `1.0`.
+    pub factor: f32,
+}
+
+/// The modifiers held while scrolling to zoom along each axis, set via `Timeline::zoom_modifiers`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Modifiers {
+    /// Zoom the time axis while at least these are held, e.g. `Ctrl` also matches `Ctrl` + `Alt`.
+    /// By default, `Ctrl`.
+    pub time: egui::Modifiers,
+    /// Zoom the value or pitch axis of tall lanes like curves while exactly these are held over
+    /// them, taking precedence over time and track zoom. By default, `Ctrl` + `Shift`.
+    pub value: egui::Modifiers,
+}
+
+/// Value-zoomable lane rects, used to give value zoom precedence while over them.
+#[derive(Clone, Debug, Default)]
+struct ValueLanes {
+    /// The pass during which `current` was collected.
+    pass: u64,
+    /// Rects registered during the previous pass.
+    prev: Vec<egui::Rect>,
+    /// Rects registered so far during the current pass.
+    current: Vec<egui::Rect>,
+}
+
+/// Levels within this fraction of a step are considered to be on the level.
+const LEVEL_EPSILON: f32 = 1e-3;
+/// The smallest fraction of a lane's full value range that may be zoomed in to.
+pub const MIN_VALUE_VIEWPORT: f32 = 1.0 / 64.0;
+
+impl Ladder {
+    /// The ticks per point at the given level, where higher levels are zoomed further out.
+    pub fn ticks_per_point(&self, level: i32) -> f32 {
+        self.base * self.factor.powi(level)
+    }
+
+    /// The fractional level of the given ticks per point.
+    pub fn level(&self, ticks_per_point: f32) -> f32 {
+        (ticks_per_point / self.base).ln() / self.factor.ln()
+    }
+
+    /// The ticks per point of the level `steps` away from the given ticks per point, where
+    /// negative steps zoom in.
+    ///
+    /// If the given ticks per point lies between levels, the first step lands on the adjacent
+    /// level in the direction of the step.
+    pub fn step(&self, ticks_per_point: f32, steps: i32) -> f32 {
+        let level = self.level(ticks_per_point);
+        let level = match steps > 0 {
+            true => (level + LEVEL_EPSILON).floor(),
+            false => (level - LEVEL_EPSILON).ceil(),
+        };
+        self.ticks_per_point(level as i32 + steps)
+    }
+}
+
+impl Default for Ladder {
+    /// Powers of √2 ticks per point.
+    fn default() -> Self {
+        Self {
+            base: 1.0,
+            factor: std::f32::consts::SQRT_2,
+        }
+    }
+}
+
+impl Default for Modifiers {
+    fn default() -> Self {
+        Self {
+            time: egui::Modifiers::CTRL,
+            value: egui::Modifiers::CTRL | egui::Modifiers::SHIFT,
+        }
+    }
+}
+
+/// Set the ticks per point via `TimelineApi::set_ticks_per_point`, shifting the view so that the
+/// point `anchor_x` points from its start remains fixed on screen.
+///
+/// Useful for zooming to a `Ladder` level about a double-clicked ruler position.
+pub fn set(timeline: &mut dyn TimelineApi, ticks_per_point: f32, anchor_x: Option<f32>) {
+    anchored(timeline, anchor_x, |timeline| {
+        timeline.set_ticks_per_point(ticks_per_point)
+    });
+}
+
+/// Zoom via the given function, shifting the view so that the point `anchor_x` points from its
+/// start remains fixed on screen.
+pub(crate) fn anchored(
+    timeline: &mut dyn TimelineApi,
+    anchor_x: Option<f32>,
+    zoom: impl FnOnce(&mut dyn TimelineApi),
+) {
+    let before = timeline.time_domain().units_per_point();
+    zoom(timeline);
+    let after = timeline.time_domain().units_per_point();
+    if let Some(x) = anchor_x.filter(|_| after != before) {
+        timeline.shift_timeline_start(x * (before - after));
+    }
+}
+
+/// The range of values visible within a tall lane occupying `rect`, e.g. a curve or piano roll.
+///
+/// Scrolling or pinching over the lane with `Modifiers::value` held zooms the value axis about the
+/// pointer, within `full_range`. The viewport is persisted under the given `id`, and is the whole
+/// `full_range` until zoomed.
+pub fn value_viewport(
+    ui: &egui::Ui,
+    id: egui::Id,
+    rect: egui::Rect,
+    full_range: RangeInclusive<f32>,
+    timeline: &TimelineCtx,
+) -> RangeInclusive<f32> {
+    let id = id.with("value_viewport");
+    let (min, max) = (*full_range.start(), *full_range.end());
+    let full_len = max - min;
+    let prev: Option<RangeInclusive<f32>> = ui.data(|d| d.get_temp(id));
+    let view = prev.clone().unwrap_or(full_range);
+    let mut len = (view.end() - view.start()).clamp(full_len * MIN_VALUE_VIEWPORT, full_len);
+    let mut start = view.start().clamp(min, max - len);
+    register(ui, rect);
+
+    // Zoom about the value under the pointer.
+    let mods = timeline.zoom_modifiers.value;
+    let pointer = ui.input(|i| i.pointer.hover_pos());
+    let zooming = ui.input(|i| i.modifiers.matches_exact(mods)) && !timeline.lock.zoom;
+    if let Some(pos) = pointer.filter(|&p| zooming && ui.clip_rect().intersect(rect).contains(p)) {
+        let factor = timeline.scroll_input.zoom_factor(ui);
+        if factor != 1.0 && full_len > 0.0 {
+            let anchor = egui::remap(pos.y, rect.y_range().flip(), start..=start + len);
+            let t = (anchor - start) / len;
+            len = (len / factor).clamp(full_len * MIN_VALUE_VIEWPORT, full_len);
+            start = (anchor - t * len).clamp(min, max - len);
+        }
+    }
+    let view = start..=start + len;
+    if prev.as_ref() != Some(&view) {
+        ui.data_mut(|d| d.insert_temp(id, view.clone()));
+    }
+    view
+}
+
+/// Whether or not the pointer is over a lane registered by `value_viewport` as of the previous
+/// pass.
+pub(crate) fn over_value_lane(ui: &egui::Ui) -> bool {
+    let pos = match ui.input(|i| i.pointer.hover_pos()) {
+        Some(pos) => pos,
+        None => return false,
+    };
+    let pass = ui.ctx().cumulative_pass_nr();
+    ui.data(|d| {
+        let lanes = d
+            .get_temp::<ValueLanes>(value_lanes_id())
+            .unwrap_or_default();
+        let rects = match lanes.pass {
+            p if p == pass => lanes.prev,
+            p if p + 1 == pass => lanes.current,
+            _ => vec![],
+        };
+        rects.iter().any(|r| r.contains(pos))
+    })
+}
+
+/// The ID under which the value-zoomable lanes are stored.
+fn value_lanes_id() -> egui::Id {
+    egui::Id::new("egui_timeline::value_lanes")
+}
+
+/// Register the visible part of a value-zoomable lane for the current pass.
+fn register(ui: &egui::Ui, rect: egui::Rect) {
+    let pass = ui.ctx().cumulative_pass_nr();
+    let rect = ui.clip_rect().intersect(rect);
+    ui.data_mut(|d| {
+        let lanes = d.get_temp_mut_or_default::<ValueLanes>(value_lanes_id());
+        if lanes.pass != pass {
+            lanes.prev = std::mem::take(&mut lanes.current);
+            lanes.pass = pass;
+        }
+        lanes.current.push(rect);
+    });
+}
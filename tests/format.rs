@@ -0,0 +1,188 @@
+//! Tests for formatting and parsing timeline positions.
+
+use egui_timeline::{format, ruler::MusicalInfo, time::FrameRate, Bar, TimeSig};
+
+/// A song at 960 ticks per beat, in 4/4 for the first two bars and 3/4 after.
+struct Song;
+
+impl MusicalInfo for Song {
+    fn ticks_per_beat(&self) -> u32 {
+        960
+    }
+    fn bar_at_ticks(&self, tick: f32) -> Bar {
+        let four = 960.0 * 4.0;
+        let three = 960.0 * 3.0;
+        let (start, len, top) = match tick < four * 2.0 {
+            true => ((tick / four).floor() * four, four, 4),
+            false => {
+                let n = ((tick - four * 2.0) / three).floor();
+                (four * 2.0 + n * three, three, 3)
+            }
+        };
+        Bar {
+            tick_range: start..start + len,
+            time_sig: TimeSig { top, bottom: 4 },
+        }
+    }
+}
+
+/// A malformed song where every bar has zero length.
+struct Empty;
+
+impl MusicalInfo for Empty {
+    fn ticks_per_beat(&self) -> u32 {
+        960
+    }
+    fn bar_at_ticks(&self, _tick: f32) -> Bar {
+        Bar {
+            tick_range: 0.0..0.0,
+            time_sig: TimeSig { top: 4, bottom: 4 },
+        }
+    }
+}
+
+#[test]
+fn format_seconds_pads_seconds() {
+    assert_eq!(format::format_seconds(0.0), "0:00");
+    assert_eq!(format::format_seconds(5.0), "0:05");
+    assert_eq!(format::format_seconds(61.0), "1:01");
+    assert_eq!(format::format_seconds(3_600.0), "60:00");
+}
+
+#[test]
+fn format_seconds_trims_millis() {
+    assert_eq!(format::format_seconds(1.5), "0:01.5");
+    assert_eq!(format::format_seconds(1.25), "0:01.25");
+    assert_eq!(format::format_seconds(1.125), "0:01.125");
+    assert_eq!(format::format_seconds(1.0004), "0:01");
+}
+
+#[test]
+fn format_seconds_rounds_into_next_second() {
+    assert_eq!(format::format_seconds(59.9996), "1:00");
+}
+
+#[test]
+fn format_seconds_negative() {
+    assert_eq!(format::format_seconds(-0.25), "-0:00.25");
+    assert_eq!(format::format_seconds(-90.0), "-1:30");
+}
+
+#[test]
+fn format_bbt_counts_from_one() {
+    assert_eq!(format::format_bbt(0.0, &Song), "1.1.000");
+    assert_eq!(format::format_bbt(960.0 + 240.0, &Song), "1.2.240");
+    assert_eq!(format::format_bbt(960.0 * 4.0, &Song), "2.1.000");
+}
+
+#[test]
+fn format_bbt_follows_time_signature_changes() {
+    assert_eq!(format::format_bbt(960.0 * 8.0, &Song), "3.1.000");
+    assert_eq!(format::format_bbt(960.0 * 11.0, &Song), "4.1.000");
+    assert_eq!(format::format_bbt(960.0 * 13.5, &Song), "4.3.480");
+}
+
+#[test]
+fn format_bbt_stops_at_zero_length_bars() {
+    assert_eq!(format::format_bbt(960.0, &Empty), "1.2.000");
+    assert!(format::format_bbt(-960.0, &Empty).starts_with("1."));
+}
+
+#[test]
+fn format_bbt_delta_counts_from_zero() {
+    assert_eq!(format::format_bbt_delta(0.0, &Song), "+0.0.000");
+    assert_eq!(
+        format::format_bbt_delta(960.0 * 5.0 + 10.0, &Song),
+        "+1.1.010"
+    );
+    assert_eq!(format::format_bbt_delta(-480.0, &Song), "-0.0.480");
+}
+
+#[test]
+fn parse_bbt_inverts_format_bbt() {
+    for &tick in [0.0, 1_200.0, 3_840.0, 7_680.0, 12_960.0].iter() {
+        let text = format::format_bbt(tick, &Song);
+        assert_eq!(format::parse_bbt(&text, &Song), Some(tick), "{}", text);
+    }
+}
+
+#[test]
+fn parse_bbt_defaults_beat_and_tick() {
+    assert_eq!(format::parse_bbt("2", &Song), Some(3_840.0));
+    assert_eq!(format::parse_bbt(" 3.2 ", &Song), Some(8_640.0));
+}
+
+#[test]
+fn parse_bbt_rejects_invalid_text() {
+    assert_eq!(format::parse_bbt("", &Song), None);
+    assert_eq!(format::parse_bbt("1.0", &Song), None);
+    assert_eq!(format::parse_bbt("1.1.0.0", &Song), None);
+    assert_eq!(format::parse_bbt("one", &Song), None);
+}
+
+#[test]
+fn parse_bbt_rejects_bars_beyond_zero_length_bars() {
+    assert_eq!(format::parse_bbt("1.2", &Empty), Some(960.0));
+    assert_eq!(format::parse_bbt("2", &Empty), None);
+    assert_eq!(format::parse_bbt("0", &Empty), None);
+}
+
+#[test]
+fn parse_seconds_accepts_each_form() {
+    assert_eq!(format::parse_seconds("5"), Some(5.0));
+    assert_eq!(format::parse_seconds("75.5"), Some(75.5));
+    assert_eq!(format::parse_seconds("1:02.5"), Some(62.5));
+    assert_eq!(format::parse_seconds("1:00:01"), Some(3_601.0));
+    assert_eq!(format::parse_seconds("90:00"), Some(5_400.0));
+    assert_eq!(format::parse_seconds(" -0:30 "), Some(-30.0));
+}
+
+#[test]
+fn parse_seconds_inverts_format_seconds() {
+    for &seconds in [0.0, 1.25, 59.5, 61.0, 754.125, -2.5].iter() {
+        let text = format::format_seconds(seconds);
+        assert_eq!(format::parse_seconds(&text), Some(seconds), "{}", text);
+    }
+}
+
+#[test]
+fn parse_seconds_rejects_out_of_range_fields() {
+    assert_eq!(format::parse_seconds("1:60"), None);
+    assert_eq!(format::parse_seconds("1:-5"), None);
+    assert_eq!(format::parse_seconds("1:60:00"), None);
+    assert_eq!(format::parse_seconds("1:00:00:00"), None);
+    assert_eq!(format::parse_seconds("1::00"), None);
+    assert_eq!(format::parse_seconds("inf"), None);
+}
+
+#[test]
+fn parse_timecode_inverts_timecode() {
+    for &rate in [
+        FrameRate::Fps24,
+        FrameRate::Fps25,
+        FrameRate::Fps29_97DropFrame,
+    ]
+    .iter()
+    {
+        for &frame in [0, 1, 1_799, 1_800, 17_982, 107_892, -30].iter() {
+            let text = rate.timecode(frame);
+            assert_eq!(format::parse_timecode(&text, rate), Some(frame), "{}", text);
+        }
+    }
+}
+
+#[test]
+fn parse_timecode_allows_omitting_leading_fields() {
+    let rate = FrameRate::Fps25;
+    assert_eq!(format::parse_timecode("12", rate), Some(12));
+    assert_eq!(format::parse_timecode("10:12", rate), Some(262));
+    assert_eq!(format::parse_timecode("00;00;10;12", rate), Some(262));
+}
+
+#[test]
+fn parse_timecode_rejects_invalid_text() {
+    let rate = FrameRate::Fps25;
+    assert_eq!(format::parse_timecode("", rate), None);
+    assert_eq!(format::parse_timecode("0:0:0:0:0", rate), None);
+    assert_eq!(format::parse_timecode("00:-1:00", rate), None);
+}
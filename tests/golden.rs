@@ -0,0 +1,293 @@
+//! Golden-image snapshot tests for the grid and ruler.
+//!
+//! Each scene is rendered with a small software rasterizer and compared against the PNG of the
+//! same name within `tests/goldens`. Run with `UPDATE_GOLDENS=1` to write new goldens after an
+//! intentional change to rendering. On a mismatch, the actual image is written to the target's
+//! temporary directory for inspection.
+
+use egui_timeline::{format::TimeFormatter, ruler, view::ViewState, TimeSig, Timeline};
+use std::path::{Path, PathBuf};
+
+/// The size of each rendered scene in pixels.
+const SIZE: [usize; 2] = [480, 120];
+/// The maximum difference in any channel before a pixel is considered changed.
+const TOLERANCE: u8 = 3;
+
+/// An sRGBA image with premultiplied alpha.
+struct Image {
+    size: [usize; 2],
+    pixels: Vec<egui::Color32>,
+}
+
+struct Ruler<'a> {
+    view: &'a ViewState,
+}
+
+impl<'a> ruler::MusicalRuler for Ruler<'a> {
+    fn info(&self) -> &dyn ruler::MusicalInfo {
+        self.view
+    }
+    fn interact(&mut self) -> &mut dyn ruler::MusicalInteract {
+        self
+    }
+    fn formatter(&self) -> Option<&dyn TimeFormatter> {
+        Some(self.view)
+    }
+}
+
+impl<'a> ruler::MusicalInteract for Ruler<'a> {
+    fn click_at_tick(&mut self, _tick: f32) {}
+}
+
+impl Image {
+    fn new(size: [usize; 2], fill: egui::Color32) -> Self {
+        let pixels = vec![fill; size[0] * size[1]];
+        Self { size, pixels }
+    }
+
+    fn get(&self, [x, y]: [usize; 2]) -> egui::Color32 {
+        self.pixels[y * self.size[0] + x]
+    }
+
+    /// Sample the texel nearest the given normalized coordinates.
+    fn sample(&self, uv: egui::Pos2) -> egui::Color32 {
+        let x = (uv.x * self.size[0] as f32) as usize;
+        let y = (uv.y * self.size[1] as f32) as usize;
+        self.get([x.min(self.size[0] - 1), y.min(self.size[1] - 1)])
+    }
+
+    /// Copy the given delta into the image, replacing it entirely if it has no position.
+    fn apply(&mut self, delta: &egui::epaint::ImageDelta) {
+        let (size, pixels): ([usize; 2], Vec<egui::Color32>) = match &delta.image {
+            egui::ImageData::Color(image) => (image.size, image.pixels.clone()),
+            egui::ImageData::Font(image) => (image.size, image.srgba_pixels(None).collect()),
+        };
+        let [x0, y0] = match delta.pos {
+            Some(pos) => pos,
+            None => {
+                *self = Image { size, pixels };
+                return;
+            }
+        };
+        for y in 0..size[1] {
+            let row = &pixels[y * size[0]..(y + 1) * size[0]];
+            let start = (y0 + y) * self.size[0] + x0;
+            self.pixels[start..start + size[0]].copy_from_slice(row);
+        }
+    }
+
+    /// Blend a premultiplied color over the pixel at the given position.
+    fn blend(&mut self, [x, y]: [usize; 2], src: [f32; 4]) {
+        let ix = y * self.size[0] + x;
+        let dst = self.pixels[ix].to_array();
+        let mut out = [0u8; 4];
+        for i in 0..4 {
+            let d = dst[i] as f32 / 255.0;
+            let v = src[i] + d * (1.0 - src[3]);
+            out[i] = (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+        self.pixels[ix] = egui::Color32::from_rgba_premultiplied(out[0], out[1], out[2], out[3]);
+    }
+
+    /// Rasterize the given triangle, interpolating vertex colors and texture coordinates.
+    fn triangle(&mut self, v: [&egui::epaint::Vertex; 3], texture: &Image, clip: egui::Rect) {
+        let edge = |a: egui::Pos2, b: egui::Pos2, p: egui::Pos2| {
+            (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+        };
+        let area = edge(v[0].pos, v[1].pos, v[2].pos);
+        if area == 0.0 {
+            return;
+        }
+        let bounds = egui::Rect::from_points(&[v[0].pos, v[1].pos, v[2].pos]).intersect(clip);
+        if !bounds.is_positive() {
+            return;
+        }
+        let (x0, x1) = (bounds.min.x.floor() as usize, bounds.max.x.ceil() as usize);
+        let (y0, y1) = (bounds.min.y.floor() as usize, bounds.max.y.ceil() as usize);
+        for y in y0..y1.min(self.size[1]) {
+            for x in x0..x1.min(self.size[0]) {
+                let p = egui::pos2(x as f32 + 0.5, y as f32 + 0.5);
+                if !clip.contains(p) {
+                    continue;
+                }
+                let w = [
+                    edge(v[1].pos, v[2].pos, p) / area,
+                    edge(v[2].pos, v[0].pos, p) / area,
+                    edge(v[0].pos, v[1].pos, p) / area,
+                ];
+                if w.iter().any(|&w| w < 0.0) {
+                    continue;
+                }
+                let uv = (0..3).fold(egui::Pos2::ZERO, |uv, i| uv + v[i].uv.to_vec2() * w[i]);
+                let texel = texture.sample(uv).to_array();
+                let mut src = [0.0; 4];
+                for (i, c) in src.iter_mut().enumerate() {
+                    let color: f32 = (0..3).map(|j| v[j].color[i] as f32 * w[j]).sum();
+                    *c = color / 255.0 * texel[i] as f32 / 255.0;
+                }
+                self.blend([x, y], src);
+            }
+        }
+    }
+
+    fn load(path: &Path) -> Option<Self> {
+        let image = image::open(path).ok()?.into_rgba8();
+        let size = [image.width() as usize, image.height() as usize];
+        let pixels = image
+            .pixels()
+            .map(|p| egui::Color32::from_rgba_premultiplied(p[0], p[1], p[2], p[3]))
+            .collect();
+        Some(Self { size, pixels })
+    }
+
+    fn save(&self, path: &Path) {
+        let bytes: Vec<u8> = self.pixels.iter().flat_map(|c| c.to_array()).collect();
+        let [w, h] = self.size;
+        let image = image::RgbaImage::from_raw(w as u32, h as u32, bytes).unwrap();
+        image.save(path).unwrap();
+    }
+
+    /// The number of pixels differing by more than `TOLERANCE` in any channel.
+    fn diff(&self, other: &Image) -> usize {
+        assert_eq!(self.size, other.size, "image size changed");
+        let changed = |(a, b): (&egui::Color32, &egui::Color32)| {
+            let (a, b) = (a.to_array(), b.to_array());
+            (0..4).any(|i| a[i].abs_diff(b[i]) > TOLERANCE)
+        };
+        self.pixels
+            .iter()
+            .zip(&other.pixels)
+            .filter(|&p| changed(p))
+            .count()
+    }
+}
+
+/// Render the grid with a pinned ruler for the given view.
+fn render(mut view: ViewState, visuals: egui::Visuals) -> Image {
+    let ctx = egui::Context::default();
+    ctx.set_visuals(visuals);
+    let screen =
+        egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(SIZE[0] as f32, SIZE[1] as f32));
+    let mut font = Image::new([1, 1], egui::Color32::WHITE);
+    let mut output = None;
+    // Run twice so that any layout remembered between frames has settled.
+    for _ in 0..2 {
+        let input = egui::RawInput {
+            screen_rect: Some(screen),
+            ..Default::default()
+        };
+        let full = ctx.run(input, |ctx| {
+            let frame = egui::Frame::none().fill(ctx.style().visuals.panel_fill);
+            egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
+                let show = Timeline::new().show(ui, &mut view);
+                show.paint_grid(&view)
+                    .pinned_tracks(|tracks, ui| {
+                        tracks.next(ui).show(|_timeline, ui| {
+                            ruler::musical(ui, &mut Ruler { view: &view });
+                        });
+                    })
+                    .tracks(|_tracks, _viewport, _ui| {});
+            });
+        });
+        for (id, delta) in &full.textures_delta.set {
+            if *id == egui::TextureId::default() {
+                font.apply(delta);
+            }
+        }
+        output = Some(full);
+    }
+    let output = output.unwrap();
+    let primitives = ctx.tessellate(output.shapes, output.pixels_per_point);
+    let mut image = Image::new(SIZE, egui::Color32::BLACK);
+    for primitive in primitives {
+        if let egui::epaint::Primitive::Mesh(mesh) = &primitive.primitive {
+            for tri in mesh.indices.chunks_exact(3) {
+                let v = [0, 1, 2].map(|i| &mesh.vertices[tri[i] as usize]);
+                image.triangle(v, &font, primitive.clip_rect);
+            }
+        }
+    }
+    image
+}
+
+/// Compare the rendered image against the golden of the given name.
+fn check(name: &str, image: Image) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/goldens");
+    let path = dir.join(format!("{}.png", name));
+    if std::env::var_os("UPDATE_GOLDENS").is_some() {
+        std::fs::create_dir_all(&dir).unwrap();
+        image.save(&path);
+        return;
+    }
+    let golden = Image::load(&path)
+        .unwrap_or_else(|| panic!("missing golden {:?}, run with `UPDATE_GOLDENS=1`", path));
+    let changed = image.diff(&golden);
+    if changed > 0 {
+        let actual = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.png", name));
+        image.save(&actual);
+        panic!(
+            "{} pixels differ from golden {:?}, actual written to {:?}",
+            changed, path, actual
+        );
+    }
+}
+
+fn view(ticks_per_point: f32, time_sig: TimeSig) -> ViewState {
+    ViewState {
+        ticks_per_point,
+        time_sig,
+        ..Default::default()
+    }
+}
+
+const COMMON: TimeSig = TimeSig { top: 4, bottom: 4 };
+
+#[test]
+fn grid_zoomed_in() {
+    check(
+        "grid_zoomed_in",
+        render(view(4.0, COMMON), egui::Visuals::dark()),
+    );
+}
+
+#[test]
+fn grid_default_zoom() {
+    check(
+        "grid_default_zoom",
+        render(view(60.0, COMMON), egui::Visuals::dark()),
+    );
+}
+
+#[test]
+fn grid_zoomed_out() {
+    check(
+        "grid_zoomed_out",
+        render(view(800.0, COMMON), egui::Visuals::dark()),
+    );
+}
+
+#[test]
+fn grid_seven_eight() {
+    let time_sig = TimeSig { top: 7, bottom: 8 };
+    check(
+        "grid_seven_eight",
+        render(view(30.0, time_sig), egui::Visuals::dark()),
+    );
+}
+
+#[test]
+fn grid_compound() {
+    let time_sig = TimeSig { top: 6, bottom: 8 };
+    check(
+        "grid_compound",
+        render(view(30.0, time_sig), egui::Visuals::dark()),
+    );
+}
+
+#[test]
+fn grid_light() {
+    check(
+        "grid_light",
+        render(view(60.0, COMMON), egui::Visuals::light()),
+    );
+}
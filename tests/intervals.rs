@@ -0,0 +1,77 @@
+//! Tests for inserting, removing and querying the `IntervalIndex`.
+
+use egui_timeline::intervals::IntervalIndex;
+
+/// The items overlapping the given range, in order.
+fn query(index: &IntervalIndex<&'static str>, start: f32, end: f32) -> Vec<&'static str> {
+    index.query(start..end).map(|(_, &item)| item).collect()
+}
+
+fn index() -> IntervalIndex<&'static str> {
+    vec![
+        (0.0..10.0, "a"),
+        (10.0..20.0, "b"),
+        (25.0..30.0, "c"),
+        (5.0..100.0, "long"),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn query_orders_by_start() {
+    assert_eq!(query(&index(), 0.0, 30.0), ["a", "long", "b", "c"]);
+}
+
+#[test]
+fn query_includes_touching_bounds() {
+    let index = index();
+    assert_eq!(query(&index, 20.0, 25.0), ["long", "b", "c"]);
+    assert_eq!(query(&index, 10.0, 10.0), ["a", "long", "b"]);
+}
+
+#[test]
+fn query_finds_long_entries_starting_before_the_range() {
+    assert_eq!(query(&index(), 50.0, 60.0), ["long"]);
+}
+
+#[test]
+fn query_outside_all_entries_is_empty() {
+    let index = index();
+    assert!(query(&index, 101.0, 200.0).is_empty());
+    assert!(query(&IntervalIndex::new(vec![]), 0.0, 10.0).is_empty());
+}
+
+#[test]
+fn insert_keeps_entries_sorted() {
+    let mut index = index();
+    index.insert(2.0..3.0, "d");
+    assert_eq!(index.len(), 5);
+    assert_eq!(query(&index, 0.0, 4.0), ["a", "d"]);
+}
+
+#[test]
+fn remove_matches_range_and_item() {
+    let mut index = index();
+    assert!(!index.remove(0.0..10.0, &"b"));
+    assert!(!index.remove(0.0..11.0, &"a"));
+    assert!(index.remove(0.0..10.0, &"a"));
+    assert_eq!(index.len(), 3);
+    assert_eq!(query(&index, 0.0, 4.0), Vec::<&str>::new());
+}
+
+#[test]
+fn remove_longest_shrinks_queries() {
+    let mut index = index();
+    assert!(index.remove(5.0..100.0, &"long"));
+    assert!(query(&index, 50.0, 60.0).is_empty());
+    assert_eq!(query(&index, 15.0, 26.0), ["b", "c"]);
+}
+
+#[test]
+fn remove_all_leaves_empty() {
+    let mut index = IntervalIndex::new(vec![(0.0..1.0, "a")]);
+    assert!(index.remove(0.0..1.0, &"a"));
+    assert!(index.is_empty());
+    assert!(query(&index, 0.0, 1.0).is_empty());
+}
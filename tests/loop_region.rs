@@ -0,0 +1,88 @@
+//! Tests for dragging the loop brace.
+
+use egui_timeline::{loop_region, view::ViewState, Timeline};
+use std::ops::Range;
+
+/// The height of the loop brace lane.
+const HEIGHT: f32 = 20.0;
+
+struct Loop {
+    range: Option<Range<f32>>,
+}
+
+impl loop_region::LoopRegion for Loop {
+    fn loop_range(&self) -> Option<Range<f32>> {
+        self.range.clone()
+    }
+    fn set_loop_range(&mut self, range: Range<f32>) {
+        self.range = Some(range);
+    }
+}
+
+/// Run a frame with the given events, returning the position of tick `0.0` within the brace.
+fn frame(
+    ctx: &egui::Context,
+    view: &mut ViewState,
+    api: &mut Loop,
+    events: Vec<egui::Event>,
+) -> egui::Pos2 {
+    let input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(480.0, 120.0),
+        )),
+        events,
+        ..Default::default()
+    };
+    let mut origin = egui::Pos2::ZERO;
+    let _ = ctx.run(input, |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            Timeline::new()
+                .show(ui, view)
+                .pinned_tracks(|tracks, ui| {
+                    tracks.next(ui).show(|timeline, ui| {
+                        let top = ui.cursor().top();
+                        loop_region::brace(ui, "loop", timeline, HEIGHT, api);
+                        origin = egui::pos2(timeline.tick_to_x(0.0), top + HEIGHT / 2.0);
+                    });
+                })
+                .tracks(|_tracks, _viewport, _ui| {});
+        });
+    });
+    origin
+}
+
+fn pointer(pos: egui::Pos2, pressed: Option<bool>) -> Vec<egui::Event> {
+    let mut events = vec![egui::Event::PointerMoved(pos)];
+    if let Some(pressed) = pressed {
+        events.push(egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: Default::default(),
+        });
+    }
+    events
+}
+
+#[test]
+fn drag_start_of_short_loop_when_zoomed_out() {
+    let ctx = egui::Context::default();
+    let mut view = ViewState::default();
+    let mut api = Loop {
+        range: Some(0.0..1.0),
+    };
+    // At the default zoom, the handles are far wider than the loop itself.
+    assert!(loop_region::HANDLE_WIDTH * 2.0 * view.ticks_per_point > 1.0);
+    let origin = frame(&ctx, &mut view, &mut api, vec![]);
+    let grab = origin + egui::vec2(1.0, 0.0);
+    frame(&ctx, &mut view, &mut api, pointer(grab, Some(true)));
+    for dx in [-10.0, 10.0, 20.0].iter() {
+        let pos = grab + egui::vec2(*dx, 0.0);
+        frame(&ctx, &mut view, &mut api, pointer(pos, None));
+    }
+    frame(&ctx, &mut view, &mut api, pointer(grab, Some(false)));
+    let range = api.range.unwrap();
+    assert_eq!(range.start, 0.0);
+    assert_eq!(range.end, 1.0);
+}
@@ -0,0 +1,98 @@
+//! Tests for loading Standard MIDI Files.
+
+#![cfg(feature = "midi")]
+
+use egui_timeline::{
+    midi::{self, Error, DEFAULT_TEMPO},
+    note::Note,
+    TimeSig,
+};
+
+/// A file with the given division and tracks of raw event bytes.
+fn smf(division: [u8; 2], tracks: &[&[u8]]) -> Vec<u8> {
+    let mut bytes = b"MThd\0\0\0\x06\0\x01".to_vec();
+    bytes.extend_from_slice(&(tracks.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(&division);
+    for events in tracks {
+        bytes.extend_from_slice(b"MTrk");
+        bytes.extend_from_slice(&(events.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(events);
+    }
+    bytes
+}
+
+/// 100 BPM in 3/4, at 480 ticks per beat.
+const TEMPO_TRACK: &[u8] = &[
+    0x00, 0xFF, 0x51, 0x03, 0x09, 0x27, 0xC0, // Tempo of 600,000us per beat.
+    0x00, 0xFF, 0x58, 0x04, 0x03, 0x02, 0x18, 0x08, // 3/4.
+    0x00, 0xFF, 0x2F, 0x00,
+];
+
+const BASS_TRACK: &[u8] = &[
+    0x00, 0xFF, 0x03, 0x04, b'B', b'a', b's', b's', //
+    0x00, 0x90, 0x24, 0x64, // Key 36 on.
+    0x83, 0x60, 0x80, 0x24, 0x40, // Key 36 off after 480 ticks.
+    0x00, 0x90, 0x28, 0x50, // Key 40 on.
+    0x81, 0x70, 0x90, 0x28, 0x00, // Key 40 off via zero velocity after 240 ticks.
+    0x00, 0x90, 0x2B, 0x60, // Key 43 on, left held.
+    0x83, 0x60, 0xFF, 0x2F, 0x00, // End of track after 480 ticks.
+];
+
+fn note(ticks: std::ops::Range<f32>, key: u8, velocity: u8) -> Note {
+    Note {
+        ticks,
+        key,
+        velocity,
+    }
+}
+
+#[test]
+fn load_notes_tempo_and_time_signature() {
+    let song = midi::load(&smf([0x01, 0xE0], &[TEMPO_TRACK, BASS_TRACK])).unwrap();
+    assert_eq!(song.ticks_per_beat, 480);
+    assert_eq!(song.tempos.len(), 1);
+    assert_eq!(song.tempos[0].tick, 0.0);
+    assert!((song.tempos[0].bpm - 100.0).abs() < 1e-9);
+    assert_eq!(song.time_sigs.len(), 1);
+    assert_eq!(song.time_sigs[0].time_sig, TimeSig { top: 3, bottom: 4 });
+
+    // The tempo track has neither a name nor notes, so is skipped.
+    assert_eq!(song.tracks.len(), 1);
+    let track = &song.tracks[0];
+    assert_eq!(track.name.as_deref(), Some("Bass"));
+    let notes = vec![
+        note(0.0..480.0, 36, 100),
+        note(480.0..720.0, 40, 80),
+        note(720.0..1_200.0, 43, 96),
+    ];
+    assert_eq!(track.notes, notes);
+}
+
+#[test]
+fn load_defaults_tempo_and_time_signature() {
+    let song = midi::load(&smf([0x01, 0xE0], &[BASS_TRACK])).unwrap();
+    assert_eq!(song.tempos[0].tick, 0.0);
+    assert_eq!(song.tempos[0].bpm, DEFAULT_TEMPO);
+    assert_eq!(song.time_sigs[0].tick, 0.0);
+    assert_eq!(song.time_sigs[0].time_sig, TimeSig { top: 4, bottom: 4 });
+}
+
+#[test]
+fn load_rejects_timecode_timing() {
+    let result = midi::load(&smf([0xE7, 0x28], &[BASS_TRACK]));
+    assert!(matches!(result, Err(Error::Timecode)));
+}
+
+#[test]
+fn load_rejects_invalid_bytes() {
+    assert!(matches!(midi::load(b"not midi"), Err(Error::Parse(_))));
+}
+
+#[test]
+fn seconds_and_bars_follow_the_song() {
+    let song = midi::load(&smf([0x01, 0xE0], &[TEMPO_TRACK, BASS_TRACK])).unwrap();
+    assert!((song.seconds_at(480.0) - 0.6).abs() < 1e-9);
+    let bar = song.bar_at(1_500.0);
+    assert_eq!(bar.tick_range, 1_440.0..2_880.0);
+    assert_eq!(bar.time_sig, TimeSig { top: 3, bottom: 4 });
+}
@@ -0,0 +1,55 @@
+//! Tests for choosing and shifting the clips following a ripple edit.
+
+use egui::Id;
+use egui_timeline::ripple::{Ripple, Scope};
+
+fn edit(scope: Scope, track: Option<&str>, from: f32, delta: f32) -> Ripple {
+    Ripple {
+        scope,
+        track: track.map(Id::new),
+        source: Id::new("source"),
+        from,
+        delta,
+    }
+}
+
+#[test]
+fn applies_to_following_clips() {
+    let ripple = edit(Scope::AllTracks, Some("a"), 100.0, -50.0);
+    assert!(ripple.applies_to(Id::new("x"), Some(Id::new("b")), 100.0..120.0));
+    assert!(ripple.applies_to(Id::new("x"), None, 200.0..220.0));
+    assert!(!ripple.applies_to(Id::new("x"), None, 90.0..120.0));
+    assert!(!ripple.applies_to(Id::new("x"), None, 0.0..10.0));
+}
+
+#[test]
+fn never_applies_to_the_source() {
+    let ripple = edit(Scope::AllTracks, Some("a"), 100.0, -50.0);
+    assert!(!ripple.applies_to(Id::new("source"), Some(Id::new("a")), 150.0..160.0));
+}
+
+#[test]
+fn track_scope_requires_the_same_known_track() {
+    let same = edit(Scope::Track, Some("a"), 0.0, 10.0);
+    assert!(same.applies_to(Id::new("x"), Some(Id::new("a")), 10.0..20.0));
+    assert!(!same.applies_to(Id::new("x"), Some(Id::new("b")), 10.0..20.0));
+    assert!(!same.applies_to(Id::new("x"), None, 10.0..20.0));
+    let unknown = edit(Scope::Track, None, 0.0, 10.0);
+    assert!(!unknown.applies_to(Id::new("x"), None, 10.0..20.0));
+}
+
+#[test]
+fn shift_moves_by_delta() {
+    let ripple = edit(Scope::AllTracks, None, 100.0, -40.0);
+    assert_eq!(ripple.shift(100.0..150.0), 60.0..110.0);
+    assert_eq!(ripple.shift(300.0..310.0), 260.0..270.0);
+    let later = edit(Scope::AllTracks, None, 100.0, 25.0);
+    assert_eq!(later.shift(100.0..150.0), 125.0..175.0);
+}
+
+#[test]
+fn shift_clamps_the_whole_batch_equally() {
+    let ripple = edit(Scope::AllTracks, None, 100.0, -150.0);
+    assert_eq!(ripple.shift(100.0..150.0), 0.0..50.0);
+    assert_eq!(ripple.shift(200.0..250.0), 100.0..150.0);
+}
@@ -0,0 +1,75 @@
+//! Tests for selecting items via host commands.
+
+use egui::Id;
+use egui_timeline::selection::{Candidate, Command, Item, SelectionState};
+
+fn clip(name: &str, track: Option<&str>, start: f32, end: f32) -> Candidate {
+    Candidate {
+        item: Item::Clip(Id::new(name)),
+        track: track.map(Id::new),
+        range: start..end,
+    }
+}
+
+fn keyframe(lane: &str, key: &str, tick: f32) -> Candidate {
+    Candidate {
+        item: Item::Keyframe(Id::new(lane), Id::new(key)),
+        track: None,
+        range: tick..tick,
+    }
+}
+
+#[test]
+fn following_matches_items_starting_at_or_after() {
+    let command = Command::Following(10.0);
+    assert!(command.matches(&clip("a", None, 10.0, 20.0)));
+    assert!(command.matches(&clip("b", None, 15.0, 20.0)));
+    assert!(!command.matches(&clip("c", None, 5.0, 20.0)));
+    assert!(command.matches(&keyframe("lane", "k", 10.0)));
+    assert!(!command.matches(&keyframe("lane", "k", 9.0)));
+}
+
+#[test]
+fn within_matches_items_entirely_inside() {
+    let command = Command::Within(10.0..20.0);
+    assert!(command.matches(&clip("a", None, 10.0, 20.0)));
+    assert!(!command.matches(&clip("b", None, 5.0, 15.0)));
+    assert!(!command.matches(&clip("c", None, 15.0, 25.0)));
+    assert!(command.matches(&keyframe("lane", "k", 20.0)));
+}
+
+#[test]
+fn track_matches_items_on_the_track() {
+    let command = Command::Track(Id::new("drums"));
+    assert!(command.matches(&clip("a", Some("drums"), 0.0, 1.0)));
+    assert!(!command.matches(&clip("b", Some("bass"), 0.0, 1.0)));
+    assert!(!command.matches(&clip("c", None, 0.0, 1.0)));
+}
+
+#[test]
+fn select_replaces_or_adds() {
+    let candidates = vec![
+        clip("a", Some("drums"), 0.0, 10.0),
+        clip("b", Some("bass"), 20.0, 30.0),
+        keyframe("lane", "k", 25.0),
+    ];
+    let mut state = SelectionState {
+        time: Some(0.0..5.0),
+        ..Default::default()
+    };
+    let count = state.select(&Command::Following(20.0), candidates.clone(), false);
+    assert_eq!(count, 2);
+    assert!(state.clips.contains(&Id::new("b")));
+    assert!(state.keyframes.contains(&(Id::new("lane"), Id::new("k"))));
+
+    let count = state.select(&Command::Track(Id::new("drums")), candidates.clone(), true);
+    assert_eq!(count, 1);
+    assert_eq!(state.clips.len(), 2);
+    assert!(state.tracks.contains(&Id::new("drums")));
+
+    state.select(&Command::Within(0.0..10.0), candidates, false);
+    assert_eq!(state.clips.len(), 1);
+    assert!(state.keyframes.is_empty());
+    assert!(state.tracks.is_empty());
+    assert_eq!(state.time, Some(0.0..5.0));
+}
@@ -0,0 +1,31 @@
+//! Tests for parsing typed tempos.
+
+use egui_timeline::tempo;
+
+#[test]
+fn parse_numbers() {
+    assert_eq!(tempo::parse("128"), Some(128.0));
+    assert_eq!(tempo::parse(" 92.5 "), Some(92.5));
+}
+
+#[test]
+fn parse_with_unit() {
+    assert_eq!(tempo::parse("140 bpm"), Some(140.0));
+    assert_eq!(tempo::parse("140BPM"), Some(140.0));
+    assert_eq!(tempo::parse("90.25 Bpm "), Some(90.25));
+}
+
+#[test]
+fn parse_rejects_invalid_text() {
+    assert_eq!(tempo::parse(""), None);
+    assert_eq!(tempo::parse("bpm"), None);
+    assert_eq!(tempo::parse("fast"), None);
+    assert_eq!(tempo::parse("inf"), None);
+    assert_eq!(tempo::parse("NaN bpm"), None);
+}
+
+#[test]
+fn parse_handles_multibyte_text() {
+    assert_eq!(tempo::parse("120♩"), None);
+    assert_eq!(tempo::parse("♩"), None);
+}
@@ -0,0 +1,36 @@
+//! Tests for labelling frames with timecode.
+
+use egui_timeline::time::FrameRate;
+
+#[test]
+fn timecode_non_drop_frame() {
+    let rate = FrameRate::Fps25;
+    assert_eq!(rate.timecode(0), "00:00:00:00");
+    assert_eq!(rate.timecode(24), "00:00:00:24");
+    assert_eq!(rate.timecode(25), "00:00:01:00");
+    assert_eq!(rate.timecode(25 * 3_600), "01:00:00:00");
+    assert_eq!(FrameRate::Fps29_97.timecode(1_800), "00:01:00:00");
+}
+
+#[test]
+fn timecode_negative() {
+    assert_eq!(FrameRate::Fps24.timecode(-25), "-00:00:01:01");
+}
+
+#[test]
+fn timecode_drop_frame_skips_first_frames_of_each_minute() {
+    let rate = FrameRate::Fps29_97DropFrame;
+    assert_eq!(rate.timecode(0), "00:00:00;00");
+    assert_eq!(rate.timecode(1_799), "00:00:59;29");
+    assert_eq!(rate.timecode(1_800), "00:01:00;02");
+    assert_eq!(rate.timecode(3_597), "00:01:59;29");
+    assert_eq!(rate.timecode(3_598), "00:02:00;02");
+}
+
+#[test]
+fn timecode_drop_frame_keeps_every_tenth_minute() {
+    let rate = FrameRate::Fps29_97DropFrame;
+    assert_eq!(rate.timecode(17_981), "00:09:59;29");
+    assert_eq!(rate.timecode(17_982), "00:10:00;00");
+    assert_eq!(rate.timecode(17_982 * 6), "01:00:00;00");
+}
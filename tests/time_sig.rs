@@ -0,0 +1,45 @@
+//! Tests for the metric strength of positions within a bar.
+
+use egui_timeline::TimeSig;
+
+const TPB: f32 = 960.0;
+
+fn levels(time_sig: TimeSig, step: f32, steps: u32) -> Vec<u8> {
+    (0..steps)
+        .map(|i| time_sig.metric_level(TPB, i as f32 * step))
+        .collect()
+}
+
+#[test]
+fn simple_meter_levels() {
+    let four_four = TimeSig { top: 4, bottom: 4 };
+    assert_eq!(levels(four_four, TPB, 4), [0, 2, 1, 2]);
+    assert_eq!(levels(four_four, TPB / 2.0, 4), [0, 3, 2, 3]);
+    let three_four = TimeSig { top: 3, bottom: 4 };
+    assert_eq!(levels(three_four, TPB, 3), [0, 2, 2]);
+    let two_four = TimeSig { top: 2, bottom: 4 };
+    assert_eq!(levels(two_four, TPB, 2), [0, 2]);
+}
+
+#[test]
+fn compound_meter_groups_beats_in_threes() {
+    let six_eight = TimeSig { top: 6, bottom: 8 };
+    assert!(six_eight.is_compound());
+    assert_eq!(levels(six_eight, TPB / 2.0, 6), [0, 3, 3, 2, 3, 3]);
+    let twelve_eight = TimeSig { top: 12, bottom: 8 };
+    assert_eq!(levels(twelve_eight, TPB * 1.5, 4), [0, 2, 1, 2]);
+    assert!(!TimeSig { top: 3, bottom: 8 }.is_compound());
+}
+
+#[test]
+fn odd_meter_has_no_mid_bar_beat() {
+    let five_four = TimeSig { top: 5, bottom: 4 };
+    assert_eq!(levels(five_four, TPB, 5), [0, 2, 2, 2, 2]);
+}
+
+#[test]
+fn near_beat_positions_are_beats() {
+    let four_four = TimeSig { top: 4, bottom: 4 };
+    assert_eq!(four_four.metric_level(TPB, TPB * 2.0 + 0.5), 1);
+    assert_eq!(four_four.metric_level(TPB, TPB * 2.0 + 10.0), 3);
+}
@@ -0,0 +1,48 @@
+//! Tests for stepping between discrete zoom levels.
+
+use egui_timeline::zoom::Ladder;
+
+const LADDER: Ladder = Ladder {
+    base: 1.0,
+    factor: 2.0,
+};
+
+fn assert_near(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+}
+
+#[test]
+fn levels_are_a_constant_factor_apart() {
+    assert_near(LADDER.ticks_per_point(0), 1.0);
+    assert_near(LADDER.ticks_per_point(3), 8.0);
+    assert_near(LADDER.ticks_per_point(-2), 0.25);
+    assert_near(LADDER.level(8.0), 3.0);
+}
+
+#[test]
+fn step_from_a_level() {
+    assert_near(LADDER.step(4.0, 1), 8.0);
+    assert_near(LADDER.step(4.0, -1), 2.0);
+    assert_near(LADDER.step(4.0, 3), 32.0);
+    assert_near(LADDER.step(4.0, -3), 0.5);
+}
+
+#[test]
+fn step_from_between_levels_lands_on_adjacent_level() {
+    assert_near(LADDER.step(3.0, 1), 4.0);
+    assert_near(LADDER.step(3.0, -1), 2.0);
+    assert_near(LADDER.step(3.0, 2), 8.0);
+}
+
+#[test]
+fn step_treats_nearly_on_level_as_on_level() {
+    assert_near(LADDER.step(4.001, -1), 2.0);
+    assert_near(LADDER.step(3.999, 1), 8.0);
+}
+
+#[test]
+fn default_ladder_steps_by_sqrt_2() {
+    let ladder = Ladder::default();
+    assert_near(ladder.step(1.0, 2), 2.0);
+    assert_near(ladder.step(1.0, -1), std::f32::consts::FRAC_1_SQRT_2);
+}